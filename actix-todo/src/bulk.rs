@@ -0,0 +1,316 @@
+//! Run many requests with bounded concurrency.
+
+use std::{cell::Cell, future::Future};
+
+use futures_util::{stream, StreamExt};
+use progenitor_client::{Error, ResponseValue};
+use reqwest::StatusCode;
+
+/// Runs a request per item with at most `concurrency` of them in flight, e.g. to delete every
+/// todo of a list.
+///
+/// ```no_run
+/// # async fn example(client: &actix_todo::Client) -> Result<(), Box<dyn std::error::Error>> {
+/// use actix_todo::{Bulk, ClientTodoExt};
+///
+/// let todos = client.get_todos().send().await?.into_inner();
+/// let results = Bulk::new()
+///     .concurrency(4)
+///     .on_progress(|progress| eprintln!("{}/{}", progress.completed, progress.total))
+///     .run(todos, |todo| client.delete_todo().id(todo.id).send())
+///     .await;
+/// if let Some(error) = results.fatal() {
+///     return Err(error.to_string().into());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Every item gets its own result, a failed request does not stop the others. Authentication
+/// and authorization failures, 401 and 403 responses, are fatal though: no further request is
+/// sent after one, as every request would fail the same way. Requests in flight at that point
+/// still complete and get their result.
+pub struct Bulk<'a> {
+    concurrency: usize,
+    progress: Option<Box<dyn FnMut(Progress) + 'a>>,
+}
+
+impl Default for Bulk<'_> {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            progress: None,
+        }
+    }
+}
+
+impl<'a> Bulk<'a> {
+    /// Run with up to 8 requests in flight.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of requests in flight, at least 1.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Call `progress` after every completed request.
+    pub fn on_progress(mut self, progress: impl FnMut(Progress) + 'a) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Send the request made by `request` for every item of `items`, typically a builder's
+    /// `send()`.
+    pub async fn run<I, F, R, T, E>(mut self, items: I, mut request: F) -> BulkResults<T, E>
+    where
+        I: IntoIterator,
+        F: FnMut(I::Item) -> R,
+        R: Future<Output = Result<ResponseValue<T>, Error<E>>>,
+    {
+        let items = items.into_iter().collect::<Vec<_>>();
+        let mut progress = Progress {
+            completed: 0,
+            failed: 0,
+            total: items.len(),
+        };
+        let mut results = BulkResults {
+            results: items.iter().map(|_| None).collect(),
+            stopped_by: None,
+        };
+        let stopped = Cell::new(false);
+
+        // Requests are only sent when polled, items pulled after a fatal error are dropped
+        // unsent.
+        let mut responses = stream::iter(items.into_iter().enumerate())
+            .map(|(index, item)| {
+                let response = request(item);
+                let stopped = &stopped;
+                async move {
+                    match stopped.get() {
+                        true => (index, None),
+                        false => (index, Some(response.await)),
+                    }
+                }
+            })
+            .buffer_unordered(self.concurrency);
+
+        while let Some((index, result)) = responses.next().await {
+            let Some(result) = result else {
+                continue;
+            };
+            if let Err(error) = &result {
+                progress.failed += 1;
+                if is_fatal(error) && !stopped.get() {
+                    stopped.set(true);
+                    results.stopped_by = Some(index);
+                }
+            }
+            progress.completed += 1;
+            results.results[index] = Some(result);
+            if let Some(on_progress) = &mut self.progress {
+                on_progress(progress);
+            }
+        }
+
+        results
+    }
+}
+
+/// Progress of a [`Bulk`] run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of requests completed, successfully or not.
+    pub completed: usize,
+    /// Number of completed requests which failed.
+    pub failed: usize,
+    /// Number of items of the run.
+    pub total: usize,
+}
+
+/// Results of a [`Bulk`] run.
+#[derive(Debug)]
+pub struct BulkResults<T, E> {
+    /// Result of every item in the order of the items, `None` for items whose request was
+    /// not sent because the run stopped.
+    pub results: Vec<Option<Result<ResponseValue<T>, Error<E>>>>,
+    /// Index of the item whose fatal error stopped the run, `None` when the run was not
+    /// stopped early.
+    pub stopped_by: Option<usize>,
+}
+
+impl<T, E> BulkResults<T, E> {
+    /// Whether every item was sent and succeeded.
+    pub fn is_success(&self) -> bool {
+        self.results
+            .iter()
+            .all(|result| matches!(result, Some(Ok(_))))
+    }
+
+    /// Errors with the index of their item.
+    pub fn errors(&self) -> impl Iterator<Item = (usize, &Error<E>)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(index, result)| match result {
+                Some(Err(error)) => Some((index, error)),
+                _ => None,
+            })
+    }
+
+    /// Fatal error the run stopped on. Requests in flight at that point may have failed with
+    /// fatal errors too, even items before it, but only the first one completed stopped it.
+    pub fn fatal(&self) -> Option<&Error<E>> {
+        match self.results.get(self.stopped_by?)? {
+            Some(Err(error)) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `error` would fail every request of the run, i.e. the api key is missing, invalid
+/// or lacks permission.
+fn is_fatal<E>(error: &Error<E>) -> bool {
+    matches!(
+        error.status(),
+        Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use reqwest::header::HeaderMap;
+
+    use super::*;
+
+    type Response = Result<ResponseValue<usize>, Error<()>>;
+
+    /// Requests in flight and the most that were in flight at once, counted while the
+    /// requests are polled rather than when they are made.
+    #[derive(Default)]
+    struct Server {
+        in_flight: Cell<usize>,
+        max_in_flight: Cell<usize>,
+        sent: RefCell<Vec<usize>>,
+    }
+
+    impl Server {
+        /// Respond to the request of `item` with `status` after being polled `polls` times.
+        async fn respond(&self, item: usize, status: StatusCode, polls: usize) -> Response {
+            self.sent.borrow_mut().push(item);
+            self.in_flight.set(self.in_flight.get() + 1);
+            self.max_in_flight
+                .set(self.max_in_flight.get().max(self.in_flight.get()));
+            for _ in 0..polls {
+                tokio::task::yield_now().await;
+            }
+            self.in_flight.set(self.in_flight.get() - 1);
+
+            match status.is_success() {
+                true => Ok(ResponseValue::new(item, status, HeaderMap::new())),
+                false => Err(Error::ErrorResponse(ResponseValue::new(
+                    (),
+                    status,
+                    HeaderMap::new(),
+                ))),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrency_bounds_requests_in_flight() {
+        let server = Server::default();
+        let results = Bulk::new()
+            .concurrency(3)
+            .run(0..10, |item| server.respond(item, StatusCode::OK, item % 4))
+            .await;
+
+        assert!(results.is_success());
+        assert_eq!(server.max_in_flight.get(), 3);
+        assert_eq!(server.sent.borrow().len(), 10);
+        for (index, result) in results.results.into_iter().enumerate() {
+            assert_eq!(result.unwrap().unwrap().into_inner(), index);
+        }
+    }
+
+    #[tokio::test]
+    async fn fatal_error_stops_sending() {
+        let server = Server::default();
+        // Item 0 fails last, after item 1 stopped the run.
+        let results = Bulk::new()
+            .concurrency(2)
+            .run(0..10, |item| match item {
+                0 => server.respond(item, StatusCode::FORBIDDEN, 5),
+                1 => server.respond(item, StatusCode::UNAUTHORIZED, 1),
+                _ => server.respond(item, StatusCode::OK, 1),
+            })
+            .await;
+
+        let mut sent = server.sent.take();
+        sent.sort_unstable();
+        assert_eq!(sent, [0, 1]);
+        assert_eq!(results.stopped_by, Some(1));
+        assert_eq!(
+            results.fatal().and_then(Error::status),
+            Some(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            results.errors().map(|(index, _)| index).collect::<Vec<_>>(),
+            [0, 1]
+        );
+        assert!(results.results[2..].iter().all(Option::is_none));
+    }
+
+    #[tokio::test]
+    async fn other_errors_do_not_stop_the_run() {
+        let server = Server::default();
+        let results = Bulk::new()
+            .run(0..4, |item| match item {
+                2 => server.respond(item, StatusCode::NOT_FOUND, 0),
+                _ => server.respond(item, StatusCode::OK, 0),
+            })
+            .await;
+
+        assert_eq!(results.stopped_by, None);
+        assert!(results.fatal().is_none());
+        assert!(!results.is_success());
+        assert_eq!(
+            results.errors().map(|(index, _)| index).collect::<Vec<_>>(),
+            [2]
+        );
+    }
+
+    #[tokio::test]
+    async fn progress_is_reported_once_per_completed_item() {
+        let server = Server::default();
+        let mut reported = Vec::new();
+        Bulk::new()
+            .concurrency(2)
+            .on_progress(|progress| reported.push(progress))
+            .run(0..4, |item| match item {
+                // Fails last, after every other item completed.
+                3 => server.respond(item, StatusCode::NOT_FOUND, 5),
+                _ => server.respond(item, StatusCode::OK, item),
+            })
+            .await;
+
+        let progress = |completed, failed| Progress {
+            completed,
+            failed,
+            total: 4,
+        };
+        assert_eq!(
+            reported,
+            [
+                progress(1, 0),
+                progress(2, 0),
+                progress(3, 0),
+                progress(4, 1)
+            ]
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Binary representations of response bodies.
+
+use progenitor_client::{Error, ResponseValue};
+use reqwest::header::{HeaderValue, CONTENT_TYPE};
+use serde::de::DeserializeOwned;
+
+/// Representation response bodies are requested in, set per call with the `accept` method of
+/// the request builders.
+///
+/// ```no_run
+/// # async fn example(client: &actix_todo::Client) -> Result<(), Box<dyn std::error::Error>> {
+/// use actix_todo::{ClientTodoExt, Format};
+///
+/// let todos = client.get_todos().accept(Format::Cbor).send().await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Responses are decoded according to their `Content-Type`, so responses the server only
+/// sends as JSON are still decoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// JSON, `application/json`.
+    #[default]
+    Json,
+    /// CBOR, `application/cbor`.
+    Cbor,
+    /// MessagePack, `application/msgpack`.
+    MessagePack,
+}
+
+impl Format {
+    /// Media type of the representation, sent as `Accept` header.
+    pub fn media_type(self) -> HeaderValue {
+        HeaderValue::from_static(match self {
+            Self::Json => "application/json",
+            Self::Cbor => "application/cbor",
+            Self::MessagePack => "application/msgpack",
+        })
+    }
+
+    /// Representation of the body of `response`, JSON unless its `Content-Type` says otherwise.
+    fn of(response: &reqwest::Response) -> Self {
+        let media_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(str::trim);
+        match media_type {
+            Some("application/cbor") => Self::Cbor,
+            Some("application/msgpack" | "application/x-msgpack") => Self::MessagePack,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// Decode the body of `response` into `T` according to its `Content-Type`.
+///
+/// [`Error::InvalidResponsePayload`] can only carry `reqwest` errors and loses the response, so
+/// bodies which fail to decode are returned as [`Error::UnexpectedResponse`] with the status,
+/// headers and body intact.
+pub(crate) async fn decode<T, E>(response: reqwest::Response) -> Result<ResponseValue<T>, Error<E>>
+where
+    T: DeserializeOwned,
+{
+    let format = Format::of(&response);
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let body = response
+        .bytes()
+        .await
+        .map_err(Error::InvalidResponsePayload)?;
+    let inner = match format {
+        Format::Json => serde_json::from_slice(&body).ok(),
+        Format::Cbor => ciborium::from_reader(body.as_ref()).ok(),
+        Format::MessagePack => rmp_serde::from_slice(&body).ok(),
+    };
+
+    match inner {
+        Some(inner) => Ok(ResponseValue::new(inner, status, headers)),
+        None => {
+            let mut response = http::Response::new(body);
+            *response.status_mut() = status;
+            *response.version_mut() = version;
+            *response.headers_mut() = headers;
+            Err(Error::UnexpectedResponse(response.into()))
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Id of todos, used by the generated code in place of a raw integer.
+
+use std::{fmt, num::ParseIntError, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// Unique id of a todo, the `TodoId` schema of the api.
+///
+/// Ids are taken from the todos returned by the server, or made from an integer explicitly,
+/// so other integers cannot be passed where a todo id is expected.
+///
+/// ```no_run
+/// # async fn example(client: &actix_todo::Client) -> Result<(), Box<dyn std::error::Error>> {
+/// use actix_todo::{ClientTodoExt, TodoId};
+///
+/// let todo = client.get_todo_by_id().id(TodoId::new(1)).send().await?;
+/// client.delete_todo().id(todo.id).send().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TodoId(i32);
+
+impl TodoId {
+    pub const fn new(id: i32) -> Self {
+        Self(id)
+    }
+
+    pub const fn get(self) -> i32 {
+        self.0
+    }
+}
+
+impl fmt::Display for TodoId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for TodoId {
+    type Err = ParseIntError;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        id.parse().map(Self)
+    }
+}
@@ -0,0 +1,119 @@
+//! Typed access to the headers of responses.
+
+use std::str::FromStr;
+
+use progenitor_client::{Error, ResponseValue};
+use reqwest::header::HeaderMap;
+
+/// Headers the responses of an operation declare, generated in [`headers`](crate::headers)
+/// for every operation.
+pub trait ResponseHeaders: Sized {
+    /// Read the declared headers from `headers`, headers which are missing or do not parse
+    /// are `None`.
+    fn from_headers(headers: &HeaderMap) -> Self;
+}
+
+/// Operation of the api, implemented by the builder of every operation for
+/// [`ResponseHeadersExt::typed_headers`].
+pub trait Operation {
+    /// Headers the responses of the operation declare.
+    type Headers: ResponseHeaders;
+}
+
+/// Headers of a response, successful or not.
+///
+/// ```no_run
+/// # async fn example(client: &actix_todo::Client) {
+/// use actix_todo::{builder, ClientTodoExt, ResponseHeadersExt};
+///
+/// match client.get_todos().send().await {
+///     Ok(todos) => println!("{} todos", todos.len()),
+///     Err(error) => {
+///         if let Some(headers) = error.typed_headers::<builder::GetTodos>() {
+///             println!("retry after {:?} s", headers.retry_after);
+///         }
+///     }
+/// }
+/// # }
+/// ```
+pub trait ResponseHeadersExt {
+    /// Headers of the response, `None` for errors which occurred before a response was
+    /// received.
+    fn response_headers(&self) -> Option<&HeaderMap>;
+
+    /// Headers of the response declared by operation `O`, the builder of the request.
+    fn typed_headers<O: Operation>(&self) -> Option<O::Headers> {
+        self.response_headers().map(O::Headers::from_headers)
+    }
+}
+
+impl<T> ResponseHeadersExt for ResponseValue<T> {
+    fn response_headers(&self) -> Option<&HeaderMap> {
+        Some(self.headers())
+    }
+}
+
+impl<E> ResponseHeadersExt for Error<E> {
+    fn response_headers(&self) -> Option<&HeaderMap> {
+        match self {
+            Error::ErrorResponse(response) => Some(response.headers()),
+            Error::UnexpectedResponse(response) => Some(response.headers()),
+            Error::InvalidRequest(_)
+            | Error::CommunicationError(_)
+            | Error::InvalidResponsePayload(_) => None,
+        }
+    }
+}
+
+/// Value of header `name` parsed as `T`.
+pub(crate) fn header<T: FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+    headers.get(name)?.to_str().ok()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use super::*;
+    use crate::{builder, types};
+
+    fn rate_limited() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("RateLimit-Remaining", "0".parse().unwrap());
+        headers.insert("Retry-After", "7".parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn error_responses_keep_headers() {
+        let error = Error::ErrorResponse(ResponseValue::new(
+            types::ErrorResponse::TooManyRequests("slow down".into()),
+            StatusCode::TOO_MANY_REQUESTS,
+            rate_limited(),
+        ));
+
+        let headers = error.typed_headers::<builder::GetTodos>().unwrap();
+        assert_eq!(headers.ratelimit_remaining, Some(0));
+        assert_eq!(headers.retry_after, Some(7));
+    }
+
+    #[test]
+    fn unexpected_responses_keep_headers() {
+        let mut response = http::Response::new("");
+        *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+        *response.headers_mut() = rate_limited();
+        let error = Error::<types::ErrorResponse>::UnexpectedResponse(response.into());
+
+        let headers = error.typed_headers::<builder::GetTodos>().unwrap();
+        assert_eq!(headers.ratelimit_remaining, Some(0));
+        assert_eq!(headers.retry_after, Some(7));
+    }
+
+    #[test]
+    fn errors_before_a_response_have_no_headers() {
+        let error = Error::<types::ErrorResponse>::InvalidRequest("missing id".into());
+
+        assert!(error.response_headers().is_none());
+        assert!(error.typed_headers::<builder::GetTodos>().is_none());
+    }
+}
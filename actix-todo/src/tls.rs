@@ -0,0 +1,98 @@
+//! TLS options of the client for servers with private certificate authorities and mutual TLS.
+
+use std::time::Duration;
+
+pub use reqwest::{Certificate, Identity};
+
+use crate::Client;
+
+/// Options of a [`Client`] connecting over TLS.
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use actix_todo::{Certificate, Client, ClientOptions, Identity};
+///
+/// let options = ClientOptions::new()
+///     .root_certificate(Certificate::from_pem(&std::fs::read("ca.pem")?)?)
+///     .identity(Identity::from_pem(&std::fs::read("client.pem")?)?);
+/// let client = Client::new_with_options("https://localhost:8080", options)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ClientOptions {
+    root_certificates: Vec<Certificate>,
+    built_in_root_certificates: bool,
+    identity: Option<Identity>,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            root_certificates: Vec::new(),
+            built_in_root_certificates: true,
+            identity: None,
+        }
+    }
+}
+
+impl ClientOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust server certificates issued by `certificate` in addition to the built-in roots.
+    pub fn root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Trust only the root certificates added with [`root_certificate`][Self::root_certificate],
+    /// not the built-in ones.
+    pub fn only_custom_root_certificates(mut self) -> Self {
+        self.built_in_root_certificates = false;
+        self
+    }
+
+    /// Present `identity`, a PEM encoded certificate chain and private key, to servers
+    /// requesting a client certificate. The server maps its subject to a principal, so no
+    /// api key needs to be sent.
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Apply the options to `builder`, for clients which need further customization, e.g.
+    /// default headers.
+    pub fn apply(self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        let builder = self
+            .root_certificates
+            .into_iter()
+            .fold(builder.use_rustls_tls(), |builder, certificate| {
+                builder.add_root_certificate(certificate)
+            })
+            .tls_built_in_root_certs(self.built_in_root_certificates);
+
+        match self.identity {
+            Some(identity) => builder.identity(identity),
+            None => builder,
+        }
+    }
+}
+
+impl Client {
+    /// Create a new client for `baseurl` using `options`, with the same timeouts as
+    /// [`Client::new`].
+    pub fn new_with_options(baseurl: &str, options: ClientOptions) -> reqwest::Result<Self> {
+        let dur = Duration::from_secs(15);
+        let client = options
+            .apply(
+                reqwest::ClientBuilder::new()
+                    .connect_timeout(dur)
+                    .timeout(dur),
+            )
+            .build()?;
+
+        Ok(Self::new_with_client(baseurl, client))
+    }
+}
@@ -0,0 +1,31 @@
+//! W3C trace context propagation for outgoing requests.
+
+use opentelemetry::{global, propagation::Injector};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Inject `traceparent` and `tracestate` headers of the current `tracing` span into `request`
+/// using the globally registered text map propagator.
+///
+/// Nothing is injected unless the application installed a propagator, e.g.
+/// `opentelemetry_sdk::propagation::TraceContextPropagator`, and the current span is recorded
+/// by a `tracing_opentelemetry` layer.
+pub(crate) fn inject_context(request: &mut reqwest::Request) {
+    let context = tracing::Span::current().context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(request.headers_mut()))
+    });
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
@@ -0,0 +1,3226 @@
+//! Client of an older version of the todo api, generated from `docs/v1/openapi.json`.
+
+#[allow(unused_imports)]
+use progenitor_client::{encode_path, RequestBuilderExt};
+pub use progenitor_client::{ByteStream, Error, ResponseValue};
+#[allow(unused_imports)]
+use reqwest::header::{HeaderMap, HeaderValue};
+#[allow(clippy::clone_on_copy, clippy::to_string_trait_impl)]
+pub mod types {
+    use serde::{Deserialize, Serialize};
+    #[allow(unused_imports)]
+    use std::convert::TryFrom;
+    ///Recorded mutation of a single `Todo`.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct AuditEntry {
+        ///Id of the api key the mutation was made with, `system` for mutations
+        /// made by the server itself. There is no anonymous actor,
+        /// every mutation of the api requires an api key.
+        pub actor: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub after: Option<Todo>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub before: Option<Todo>,
+        ///Names of the `Todo` fields changed by the mutation.
+        pub changes: Vec<String>,
+        ///Sequence number of the entry, increasing in the order entries were
+        /// recorded.
+        pub id: i64,
+        pub operation: Operation,
+        ///Id of the request the mutation was made in, missing for mutations
+        /// made by the server itself.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub request_id: Option<String>,
+        ///Tenant owning the mutated todo.
+        pub tenant: String,
+        ///Time the mutation was made at.
+        pub timestamp: chrono::DateTime<chrono::offset::Utc>,
+        pub todo_id: crate::TodoId,
+    }
+
+    impl From<&AuditEntry> for AuditEntry {
+        fn from(value: &AuditEntry) -> Self {
+            value.clone()
+        }
+    }
+
+    impl AuditEntry {
+        pub fn builder() -> builder::AuditEntry {
+            builder::AuditEntry::default()
+        }
+    }
+
+    ///Page of audit entries.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct AuditPage {
+        ///Matching entries of the requested page, oldest first.
+        pub entries: Vec<AuditEntry>,
+        ///Number of entries matching the query across all pages.
+        pub total: u64,
+    }
+
+    impl From<&AuditPage> for AuditPage {
+        fn from(value: &AuditPage) -> Self {
+            value.clone()
+        }
+    }
+
+    impl AuditPage {
+        pub fn builder() -> builder::AuditPage {
+            builder::AuditPage::default()
+        }
+    }
+
+    ///How to handle imported todos whose id already exists in the store.
+    #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+    pub enum ConflictPolicy {
+        #[serde(rename = "skip")]
+        Skip,
+        #[serde(rename = "overwrite")]
+        Overwrite,
+        #[serde(rename = "fail")]
+        Fail,
+    }
+
+    impl From<&ConflictPolicy> for ConflictPolicy {
+        fn from(value: &ConflictPolicy) -> Self {
+            value.clone()
+        }
+    }
+
+    impl ToString for ConflictPolicy {
+        fn to_string(&self) -> String {
+            match *self {
+                Self::Skip => "skip".to_string(),
+                Self::Overwrite => "overwrite".to_string(),
+                Self::Fail => "fail".to_string(),
+            }
+        }
+    }
+
+    impl std::str::FromStr for ConflictPolicy {
+        type Err = &'static str;
+        fn from_str(value: &str) -> Result<Self, &'static str> {
+            match value {
+                "skip" => Ok(Self::Skip),
+                "overwrite" => Ok(Self::Overwrite),
+                "fail" => Ok(Self::Fail),
+                _ => Err("invalid value"),
+            }
+        }
+    }
+
+    impl std::convert::TryFrom<&str> for ConflictPolicy {
+        type Error = &'static str;
+        fn try_from(value: &str) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    impl std::convert::TryFrom<&String> for ConflictPolicy {
+        type Error = &'static str;
+        fn try_from(value: &String) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    impl std::convert::TryFrom<String> for ConflictPolicy {
+        type Error = &'static str;
+        fn try_from(value: String) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    ///Todo endpoint error responses
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub enum ErrorResponse {
+        BadRequest(String),
+        NotFound(String),
+        Conflict(String),
+        Unauthorized(String),
+        PayloadTooLarge(String),
+        TooManyRequests(String),
+    }
+
+    impl From<&ErrorResponse> for ErrorResponse {
+        fn from(value: &ErrorResponse) -> Self {
+            value.clone()
+        }
+    }
+
+    ///Liveness of the server.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Health {
+        ///Always `ok` when the server is able to respond.
+        pub status: String,
+    }
+
+    impl From<&Health> for Health {
+        fn from(value: &Health) -> Self {
+            value.clone()
+        }
+    }
+
+    impl Health {
+        pub fn builder() -> builder::Health {
+            builder::Health::default()
+        }
+    }
+
+    ///Range of a matched word in a todo value, in bytes.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Highlight {
+        ///Byte offset one past the last byte of the match.
+        pub end: u64,
+        ///Byte offset of the first byte of the match.
+        pub start: u64,
+    }
+
+    impl From<&Highlight> for Highlight {
+        fn from(value: &Highlight) -> Self {
+            value.clone()
+        }
+    }
+
+    impl Highlight {
+        pub fn builder() -> builder::Highlight {
+            builder::Highlight::default()
+        }
+    }
+
+    ///Summary of an import.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct ImportReport {
+        ///Number of todos added to the storage.
+        pub imported: u64,
+        ///Number of existing todos replaced with the imported ones.
+        pub overwritten: u64,
+        ///Number of imported todos skipped because their id already existed.
+        pub skipped: u64,
+    }
+
+    impl From<&ImportReport> for ImportReport {
+        fn from(value: &ImportReport) -> Self {
+            value.clone()
+        }
+    }
+
+    impl ImportReport {
+        pub fn builder() -> builder::ImportReport {
+            builder::ImportReport::default()
+        }
+    }
+
+    ///Kind of mutation recorded in the audit trail.
+    #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+    pub enum Operation {
+        #[serde(rename = "create")]
+        Create,
+        #[serde(rename = "update")]
+        Update,
+        #[serde(rename = "delete")]
+        Delete,
+        #[serde(rename = "restore")]
+        Restore,
+        #[serde(rename = "revert")]
+        Revert,
+        #[serde(rename = "import")]
+        Import,
+        #[serde(rename = "purge")]
+        Purge,
+    }
+
+    impl From<&Operation> for Operation {
+        fn from(value: &Operation) -> Self {
+            value.clone()
+        }
+    }
+
+    impl ToString for Operation {
+        fn to_string(&self) -> String {
+            match *self {
+                Self::Create => "create".to_string(),
+                Self::Update => "update".to_string(),
+                Self::Delete => "delete".to_string(),
+                Self::Restore => "restore".to_string(),
+                Self::Revert => "revert".to_string(),
+                Self::Import => "import".to_string(),
+                Self::Purge => "purge".to_string(),
+            }
+        }
+    }
+
+    impl std::str::FromStr for Operation {
+        type Err = &'static str;
+        fn from_str(value: &str) -> Result<Self, &'static str> {
+            match value {
+                "create" => Ok(Self::Create),
+                "update" => Ok(Self::Update),
+                "delete" => Ok(Self::Delete),
+                "restore" => Ok(Self::Restore),
+                "revert" => Ok(Self::Revert),
+                "import" => Ok(Self::Import),
+                "purge" => Ok(Self::Purge),
+                _ => Err("invalid value"),
+            }
+        }
+    }
+
+    impl std::convert::TryFrom<&str> for Operation {
+        type Error = &'static str;
+        fn try_from(value: &str) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    impl std::convert::TryFrom<&String> for Operation {
+        type Error = &'static str;
+        fn try_from(value: &String) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    impl std::convert::TryFrom<String> for Operation {
+        type Error = &'static str;
+        fn try_from(value: String) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    ///Priority of a `Todo`.
+    #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+    pub enum Priority {
+        #[serde(rename = "low")]
+        Low,
+        #[serde(rename = "normal")]
+        Normal,
+        #[serde(rename = "high")]
+        High,
+        #[serde(rename = "urgent")]
+        Urgent,
+    }
+
+    impl From<&Priority> for Priority {
+        fn from(value: &Priority) -> Self {
+            value.clone()
+        }
+    }
+
+    impl ToString for Priority {
+        fn to_string(&self) -> String {
+            match *self {
+                Self::Low => "low".to_string(),
+                Self::Normal => "normal".to_string(),
+                Self::High => "high".to_string(),
+                Self::Urgent => "urgent".to_string(),
+            }
+        }
+    }
+
+    impl std::str::FromStr for Priority {
+        type Err = &'static str;
+        fn from_str(value: &str) -> Result<Self, &'static str> {
+            match value {
+                "low" => Ok(Self::Low),
+                "normal" => Ok(Self::Normal),
+                "high" => Ok(Self::High),
+                "urgent" => Ok(Self::Urgent),
+                _ => Err("invalid value"),
+            }
+        }
+    }
+
+    impl std::convert::TryFrom<&str> for Priority {
+        type Error = &'static str;
+        fn try_from(value: &str) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    impl std::convert::TryFrom<&String> for Priority {
+        type Error = &'static str;
+        fn try_from(value: &String) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    impl std::convert::TryFrom<String> for Priority {
+        type Error = &'static str;
+        fn try_from(value: String) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    ///Readiness of the server and its dependencies.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Readiness {
+        ///Whether the OpenAPI document was generated on startup.
+        pub openapi_spec: bool,
+        ///Whether the server is ready to serve traffic.
+        pub ready: bool,
+        ///Whether the todo storage backend is usable.
+        pub storage: bool,
+    }
+
+    impl From<&Readiness> for Readiness {
+        fn from(value: &Readiness) -> Self {
+            value.clone()
+        }
+    }
+
+    impl Readiness {
+        pub fn builder() -> builder::Readiness {
+            builder::Readiness::default()
+        }
+    }
+
+    ///Version of a `Todo`.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Revision {
+        ///Time the revision was stored at.
+        pub recorded_at: chrono::DateTime<chrono::offset::Utc>,
+        ///Number of the revision, starting from 1 and increasing by one with
+        /// every change.
+        pub rev: i64,
+        pub todo: Todo,
+    }
+
+    impl From<&Revision> for Revision {
+        fn from(value: &Revision) -> Self {
+            value.clone()
+        }
+    }
+
+    impl Revision {
+        pub fn builder() -> builder::Revision {
+            builder::Revision::default()
+        }
+    }
+
+    ///`Todo` found by search.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct SearchHit {
+        ///Words of the todo's value matching the search, in order.
+        pub highlights: Vec<Highlight>,
+        pub score: f64,
+        pub todo: Todo,
+    }
+
+    impl From<&SearchHit> for SearchHit {
+        fn from(value: &SearchHit) -> Self {
+            value.clone()
+        }
+    }
+
+    impl SearchHit {
+        pub fn builder() -> builder::SearchHit {
+            builder::SearchHit::default()
+        }
+    }
+
+    ///Task to do.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct Todo {
+        ///Mark is the task done or not
+        pub checked: bool,
+        ///Time the todo was last checked done at, maintained by the server.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub completed_at: Option<chrono::DateTime<chrono::offset::Utc>>,
+        ///Time the todo was created at, maintained by the server.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub created_at: Option<chrono::DateTime<chrono::offset::Utc>>,
+        ///Time the todo was moved to the trash at, maintained by the server.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub deleted_at: Option<chrono::DateTime<chrono::offset::Utc>>,
+        ///Optional deadline of the task.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub due_at: Option<chrono::DateTime<chrono::offset::Utc>>,
+        pub id: crate::TodoId,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub priority: Option<Priority>,
+        ///Free form labels of the task.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub tags: Vec<String>,
+        ///Time the todo was last modified at, maintained by the server.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub updated_at: Option<chrono::DateTime<chrono::offset::Utc>>,
+        ///Description of the tasks to do.
+        pub value: String,
+    }
+
+    impl From<&Todo> for Todo {
+        fn from(value: &Todo) -> Self {
+            value.clone()
+        }
+    }
+
+    impl Todo {
+        pub fn builder() -> builder::Todo {
+            builder::Todo::default()
+        }
+    }
+
+    ///Request to update existing `Todo` item.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct TodoUpdateRequest {
+        ///Optional check status to mark is the task done or not.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub checked: Option<bool>,
+        ///Optional new deadline of the task, `null` removes the deadline.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub due_at: Option<chrono::DateTime<chrono::offset::Utc>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub priority: Option<Priority>,
+        ///Optional new tags replacing the current tags of the task.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub tags: Option<Vec<String>>,
+        ///Optional new value for the `Todo` task.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub value: Option<String>,
+    }
+
+    impl From<&TodoUpdateRequest> for TodoUpdateRequest {
+        fn from(value: &TodoUpdateRequest) -> Self {
+            value.clone()
+        }
+    }
+
+    impl TodoUpdateRequest {
+        pub fn builder() -> builder::TodoUpdateRequest {
+            builder::TodoUpdateRequest::default()
+        }
+    }
+
+    ///Format of exported and imported todos.
+    #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+    pub enum TransferFormat {
+        #[serde(rename = "ndjson")]
+        Ndjson,
+        #[serde(rename = "csv")]
+        Csv,
+    }
+
+    impl From<&TransferFormat> for TransferFormat {
+        fn from(value: &TransferFormat) -> Self {
+            value.clone()
+        }
+    }
+
+    impl ToString for TransferFormat {
+        fn to_string(&self) -> String {
+            match *self {
+                Self::Ndjson => "ndjson".to_string(),
+                Self::Csv => "csv".to_string(),
+            }
+        }
+    }
+
+    impl std::str::FromStr for TransferFormat {
+        type Err = &'static str;
+        fn from_str(value: &str) -> Result<Self, &'static str> {
+            match value {
+                "ndjson" => Ok(Self::Ndjson),
+                "csv" => Ok(Self::Csv),
+                _ => Err("invalid value"),
+            }
+        }
+    }
+
+    impl std::convert::TryFrom<&str> for TransferFormat {
+        type Error = &'static str;
+        fn try_from(value: &str) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    impl std::convert::TryFrom<&String> for TransferFormat {
+        type Error = &'static str;
+        fn try_from(value: &String) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    impl std::convert::TryFrom<String> for TransferFormat {
+        type Error = &'static str;
+        fn try_from(value: String) -> Result<Self, &'static str> {
+            value.parse()
+        }
+    }
+
+    ///Build information of the running server.
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct VersionInfo {
+        ///Version of the served API from `info.version` of the OpenAPI
+        /// document.
+        pub api_version: String,
+        ///Time the server was built in RFC 3339 format.
+        pub build_time: String,
+        ///Commit time of the git commit in RFC 3339 format.
+        pub commit_time: String,
+        ///Git commit the server was built from.
+        pub git_sha: String,
+        ///Version of the server crate.
+        pub version: String,
+    }
+
+    impl From<&VersionInfo> for VersionInfo {
+        fn from(value: &VersionInfo) -> Self {
+            value.clone()
+        }
+    }
+
+    impl VersionInfo {
+        pub fn builder() -> builder::VersionInfo {
+            builder::VersionInfo::default()
+        }
+    }
+
+    pub mod builder {
+        #[derive(Clone, Debug)]
+        pub struct AuditEntry {
+            actor: Result<String, String>,
+            after: Result<Option<super::Todo>, String>,
+            before: Result<Option<super::Todo>, String>,
+            changes: Result<Vec<String>, String>,
+            id: Result<i64, String>,
+            operation: Result<super::Operation, String>,
+            request_id: Result<Option<String>, String>,
+            tenant: Result<String, String>,
+            timestamp: Result<chrono::DateTime<chrono::offset::Utc>, String>,
+            todo_id: Result<crate::TodoId, String>,
+        }
+
+        impl Default for AuditEntry {
+            fn default() -> Self {
+                Self {
+                    actor: Err("no value supplied for actor".to_string()),
+                    after: Ok(Default::default()),
+                    before: Ok(Default::default()),
+                    changes: Err("no value supplied for changes".to_string()),
+                    id: Err("no value supplied for id".to_string()),
+                    operation: Err("no value supplied for operation".to_string()),
+                    request_id: Ok(Default::default()),
+                    tenant: Err("no value supplied for tenant".to_string()),
+                    timestamp: Err("no value supplied for timestamp".to_string()),
+                    todo_id: Err("no value supplied for todo_id".to_string()),
+                }
+            }
+        }
+
+        impl AuditEntry {
+            pub fn actor<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<String>,
+                T::Error: std::fmt::Display,
+            {
+                self.actor = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for actor: {}", e));
+                self
+            }
+            pub fn after<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<super::Todo>>,
+                T::Error: std::fmt::Display,
+            {
+                self.after = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for after: {}", e));
+                self
+            }
+            pub fn before<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<super::Todo>>,
+                T::Error: std::fmt::Display,
+            {
+                self.before = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for before: {}", e));
+                self
+            }
+            pub fn changes<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Vec<String>>,
+                T::Error: std::fmt::Display,
+            {
+                self.changes = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for changes: {}", e));
+                self
+            }
+            pub fn id<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<i64>,
+                T::Error: std::fmt::Display,
+            {
+                self.id = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for id: {}", e));
+                self
+            }
+            pub fn operation<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<super::Operation>,
+                T::Error: std::fmt::Display,
+            {
+                self.operation = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for operation: {}", e));
+                self
+            }
+            pub fn request_id<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<String>>,
+                T::Error: std::fmt::Display,
+            {
+                self.request_id = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for request_id: {}", e));
+                self
+            }
+            pub fn tenant<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<String>,
+                T::Error: std::fmt::Display,
+            {
+                self.tenant = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for tenant: {}", e));
+                self
+            }
+            pub fn timestamp<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<chrono::DateTime<chrono::offset::Utc>>,
+                T::Error: std::fmt::Display,
+            {
+                self.timestamp = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for timestamp: {}", e));
+                self
+            }
+            pub fn todo_id<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<crate::TodoId>,
+                T::Error: std::fmt::Display,
+            {
+                self.todo_id = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for todo_id: {}", e));
+                self
+            }
+        }
+
+        impl std::convert::TryFrom<AuditEntry> for super::AuditEntry {
+            type Error = String;
+            fn try_from(value: AuditEntry) -> Result<Self, String> {
+                Ok(Self {
+                    actor: value.actor?,
+                    after: value.after?,
+                    before: value.before?,
+                    changes: value.changes?,
+                    id: value.id?,
+                    operation: value.operation?,
+                    request_id: value.request_id?,
+                    tenant: value.tenant?,
+                    timestamp: value.timestamp?,
+                    todo_id: value.todo_id?,
+                })
+            }
+        }
+
+        impl From<super::AuditEntry> for AuditEntry {
+            fn from(value: super::AuditEntry) -> Self {
+                Self {
+                    actor: Ok(value.actor),
+                    after: Ok(value.after),
+                    before: Ok(value.before),
+                    changes: Ok(value.changes),
+                    id: Ok(value.id),
+                    operation: Ok(value.operation),
+                    request_id: Ok(value.request_id),
+                    tenant: Ok(value.tenant),
+                    timestamp: Ok(value.timestamp),
+                    todo_id: Ok(value.todo_id),
+                }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct AuditPage {
+            entries: Result<Vec<super::AuditEntry>, String>,
+            total: Result<u64, String>,
+        }
+
+        impl Default for AuditPage {
+            fn default() -> Self {
+                Self {
+                    entries: Err("no value supplied for entries".to_string()),
+                    total: Err("no value supplied for total".to_string()),
+                }
+            }
+        }
+
+        impl AuditPage {
+            pub fn entries<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Vec<super::AuditEntry>>,
+                T::Error: std::fmt::Display,
+            {
+                self.entries = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for entries: {}", e));
+                self
+            }
+            pub fn total<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<u64>,
+                T::Error: std::fmt::Display,
+            {
+                self.total = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for total: {}", e));
+                self
+            }
+        }
+
+        impl std::convert::TryFrom<AuditPage> for super::AuditPage {
+            type Error = String;
+            fn try_from(value: AuditPage) -> Result<Self, String> {
+                Ok(Self {
+                    entries: value.entries?,
+                    total: value.total?,
+                })
+            }
+        }
+
+        impl From<super::AuditPage> for AuditPage {
+            fn from(value: super::AuditPage) -> Self {
+                Self {
+                    entries: Ok(value.entries),
+                    total: Ok(value.total),
+                }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Health {
+            status: Result<String, String>,
+        }
+
+        impl Default for Health {
+            fn default() -> Self {
+                Self {
+                    status: Err("no value supplied for status".to_string()),
+                }
+            }
+        }
+
+        impl Health {
+            pub fn status<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<String>,
+                T::Error: std::fmt::Display,
+            {
+                self.status = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for status: {}", e));
+                self
+            }
+        }
+
+        impl std::convert::TryFrom<Health> for super::Health {
+            type Error = String;
+            fn try_from(value: Health) -> Result<Self, String> {
+                Ok(Self {
+                    status: value.status?,
+                })
+            }
+        }
+
+        impl From<super::Health> for Health {
+            fn from(value: super::Health) -> Self {
+                Self {
+                    status: Ok(value.status),
+                }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Highlight {
+            end: Result<u64, String>,
+            start: Result<u64, String>,
+        }
+
+        impl Default for Highlight {
+            fn default() -> Self {
+                Self {
+                    end: Err("no value supplied for end".to_string()),
+                    start: Err("no value supplied for start".to_string()),
+                }
+            }
+        }
+
+        impl Highlight {
+            pub fn end<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<u64>,
+                T::Error: std::fmt::Display,
+            {
+                self.end = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for end: {}", e));
+                self
+            }
+            pub fn start<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<u64>,
+                T::Error: std::fmt::Display,
+            {
+                self.start = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for start: {}", e));
+                self
+            }
+        }
+
+        impl std::convert::TryFrom<Highlight> for super::Highlight {
+            type Error = String;
+            fn try_from(value: Highlight) -> Result<Self, String> {
+                Ok(Self {
+                    end: value.end?,
+                    start: value.start?,
+                })
+            }
+        }
+
+        impl From<super::Highlight> for Highlight {
+            fn from(value: super::Highlight) -> Self {
+                Self {
+                    end: Ok(value.end),
+                    start: Ok(value.start),
+                }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct ImportReport {
+            imported: Result<u64, String>,
+            overwritten: Result<u64, String>,
+            skipped: Result<u64, String>,
+        }
+
+        impl Default for ImportReport {
+            fn default() -> Self {
+                Self {
+                    imported: Err("no value supplied for imported".to_string()),
+                    overwritten: Err("no value supplied for overwritten".to_string()),
+                    skipped: Err("no value supplied for skipped".to_string()),
+                }
+            }
+        }
+
+        impl ImportReport {
+            pub fn imported<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<u64>,
+                T::Error: std::fmt::Display,
+            {
+                self.imported = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for imported: {}", e));
+                self
+            }
+            pub fn overwritten<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<u64>,
+                T::Error: std::fmt::Display,
+            {
+                self.overwritten = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for overwritten: {}", e));
+                self
+            }
+            pub fn skipped<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<u64>,
+                T::Error: std::fmt::Display,
+            {
+                self.skipped = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for skipped: {}", e));
+                self
+            }
+        }
+
+        impl std::convert::TryFrom<ImportReport> for super::ImportReport {
+            type Error = String;
+            fn try_from(value: ImportReport) -> Result<Self, String> {
+                Ok(Self {
+                    imported: value.imported?,
+                    overwritten: value.overwritten?,
+                    skipped: value.skipped?,
+                })
+            }
+        }
+
+        impl From<super::ImportReport> for ImportReport {
+            fn from(value: super::ImportReport) -> Self {
+                Self {
+                    imported: Ok(value.imported),
+                    overwritten: Ok(value.overwritten),
+                    skipped: Ok(value.skipped),
+                }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Readiness {
+            openapi_spec: Result<bool, String>,
+            ready: Result<bool, String>,
+            storage: Result<bool, String>,
+        }
+
+        impl Default for Readiness {
+            fn default() -> Self {
+                Self {
+                    openapi_spec: Err("no value supplied for openapi_spec".to_string()),
+                    ready: Err("no value supplied for ready".to_string()),
+                    storage: Err("no value supplied for storage".to_string()),
+                }
+            }
+        }
+
+        impl Readiness {
+            pub fn openapi_spec<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<bool>,
+                T::Error: std::fmt::Display,
+            {
+                self.openapi_spec = value.try_into().map_err(|e| {
+                    format!("error converting supplied value for openapi_spec: {}", e)
+                });
+                self
+            }
+            pub fn ready<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<bool>,
+                T::Error: std::fmt::Display,
+            {
+                self.ready = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for ready: {}", e));
+                self
+            }
+            pub fn storage<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<bool>,
+                T::Error: std::fmt::Display,
+            {
+                self.storage = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for storage: {}", e));
+                self
+            }
+        }
+
+        impl std::convert::TryFrom<Readiness> for super::Readiness {
+            type Error = String;
+            fn try_from(value: Readiness) -> Result<Self, String> {
+                Ok(Self {
+                    openapi_spec: value.openapi_spec?,
+                    ready: value.ready?,
+                    storage: value.storage?,
+                })
+            }
+        }
+
+        impl From<super::Readiness> for Readiness {
+            fn from(value: super::Readiness) -> Self {
+                Self {
+                    openapi_spec: Ok(value.openapi_spec),
+                    ready: Ok(value.ready),
+                    storage: Ok(value.storage),
+                }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Revision {
+            recorded_at: Result<chrono::DateTime<chrono::offset::Utc>, String>,
+            rev: Result<i64, String>,
+            todo: Result<super::Todo, String>,
+        }
+
+        impl Default for Revision {
+            fn default() -> Self {
+                Self {
+                    recorded_at: Err("no value supplied for recorded_at".to_string()),
+                    rev: Err("no value supplied for rev".to_string()),
+                    todo: Err("no value supplied for todo".to_string()),
+                }
+            }
+        }
+
+        impl Revision {
+            pub fn recorded_at<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<chrono::DateTime<chrono::offset::Utc>>,
+                T::Error: std::fmt::Display,
+            {
+                self.recorded_at = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for recorded_at: {}", e));
+                self
+            }
+            pub fn rev<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<i64>,
+                T::Error: std::fmt::Display,
+            {
+                self.rev = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for rev: {}", e));
+                self
+            }
+            pub fn todo<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<super::Todo>,
+                T::Error: std::fmt::Display,
+            {
+                self.todo = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for todo: {}", e));
+                self
+            }
+        }
+
+        impl std::convert::TryFrom<Revision> for super::Revision {
+            type Error = String;
+            fn try_from(value: Revision) -> Result<Self, String> {
+                Ok(Self {
+                    recorded_at: value.recorded_at?,
+                    rev: value.rev?,
+                    todo: value.todo?,
+                })
+            }
+        }
+
+        impl From<super::Revision> for Revision {
+            fn from(value: super::Revision) -> Self {
+                Self {
+                    recorded_at: Ok(value.recorded_at),
+                    rev: Ok(value.rev),
+                    todo: Ok(value.todo),
+                }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct SearchHit {
+            highlights: Result<Vec<super::Highlight>, String>,
+            score: Result<f64, String>,
+            todo: Result<super::Todo, String>,
+        }
+
+        impl Default for SearchHit {
+            fn default() -> Self {
+                Self {
+                    highlights: Err("no value supplied for highlights".to_string()),
+                    score: Err("no value supplied for score".to_string()),
+                    todo: Err("no value supplied for todo".to_string()),
+                }
+            }
+        }
+
+        impl SearchHit {
+            pub fn highlights<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Vec<super::Highlight>>,
+                T::Error: std::fmt::Display,
+            {
+                self.highlights = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for highlights: {}", e));
+                self
+            }
+            pub fn score<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<f64>,
+                T::Error: std::fmt::Display,
+            {
+                self.score = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for score: {}", e));
+                self
+            }
+            pub fn todo<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<super::Todo>,
+                T::Error: std::fmt::Display,
+            {
+                self.todo = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for todo: {}", e));
+                self
+            }
+        }
+
+        impl std::convert::TryFrom<SearchHit> for super::SearchHit {
+            type Error = String;
+            fn try_from(value: SearchHit) -> Result<Self, String> {
+                Ok(Self {
+                    highlights: value.highlights?,
+                    score: value.score?,
+                    todo: value.todo?,
+                })
+            }
+        }
+
+        impl From<super::SearchHit> for SearchHit {
+            fn from(value: super::SearchHit) -> Self {
+                Self {
+                    highlights: Ok(value.highlights),
+                    score: Ok(value.score),
+                    todo: Ok(value.todo),
+                }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Todo {
+            checked: Result<bool, String>,
+            completed_at: Result<Option<chrono::DateTime<chrono::offset::Utc>>, String>,
+            created_at: Result<Option<chrono::DateTime<chrono::offset::Utc>>, String>,
+            deleted_at: Result<Option<chrono::DateTime<chrono::offset::Utc>>, String>,
+            due_at: Result<Option<chrono::DateTime<chrono::offset::Utc>>, String>,
+            id: Result<crate::TodoId, String>,
+            priority: Result<Option<super::Priority>, String>,
+            tags: Result<Vec<String>, String>,
+            updated_at: Result<Option<chrono::DateTime<chrono::offset::Utc>>, String>,
+            value: Result<String, String>,
+        }
+
+        impl Default for Todo {
+            fn default() -> Self {
+                Self {
+                    checked: Err("no value supplied for checked".to_string()),
+                    completed_at: Ok(Default::default()),
+                    created_at: Ok(Default::default()),
+                    deleted_at: Ok(Default::default()),
+                    due_at: Ok(Default::default()),
+                    id: Err("no value supplied for id".to_string()),
+                    priority: Ok(Default::default()),
+                    tags: Ok(Default::default()),
+                    updated_at: Ok(Default::default()),
+                    value: Err("no value supplied for value".to_string()),
+                }
+            }
+        }
+
+        impl Todo {
+            pub fn checked<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<bool>,
+                T::Error: std::fmt::Display,
+            {
+                self.checked = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for checked: {}", e));
+                self
+            }
+            pub fn completed_at<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<chrono::DateTime<chrono::offset::Utc>>>,
+                T::Error: std::fmt::Display,
+            {
+                self.completed_at = value.try_into().map_err(|e| {
+                    format!("error converting supplied value for completed_at: {}", e)
+                });
+                self
+            }
+            pub fn created_at<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<chrono::DateTime<chrono::offset::Utc>>>,
+                T::Error: std::fmt::Display,
+            {
+                self.created_at = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for created_at: {}", e));
+                self
+            }
+            pub fn deleted_at<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<chrono::DateTime<chrono::offset::Utc>>>,
+                T::Error: std::fmt::Display,
+            {
+                self.deleted_at = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for deleted_at: {}", e));
+                self
+            }
+            pub fn due_at<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<chrono::DateTime<chrono::offset::Utc>>>,
+                T::Error: std::fmt::Display,
+            {
+                self.due_at = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for due_at: {}", e));
+                self
+            }
+            pub fn id<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<crate::TodoId>,
+                T::Error: std::fmt::Display,
+            {
+                self.id = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for id: {}", e));
+                self
+            }
+            pub fn priority<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<super::Priority>>,
+                T::Error: std::fmt::Display,
+            {
+                self.priority = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for priority: {}", e));
+                self
+            }
+            pub fn tags<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Vec<String>>,
+                T::Error: std::fmt::Display,
+            {
+                self.tags = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for tags: {}", e));
+                self
+            }
+            pub fn updated_at<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<chrono::DateTime<chrono::offset::Utc>>>,
+                T::Error: std::fmt::Display,
+            {
+                self.updated_at = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for updated_at: {}", e));
+                self
+            }
+            pub fn value<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<String>,
+                T::Error: std::fmt::Display,
+            {
+                self.value = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for value: {}", e));
+                self
+            }
+        }
+
+        impl std::convert::TryFrom<Todo> for super::Todo {
+            type Error = String;
+            fn try_from(value: Todo) -> Result<Self, String> {
+                Ok(Self {
+                    checked: value.checked?,
+                    completed_at: value.completed_at?,
+                    created_at: value.created_at?,
+                    deleted_at: value.deleted_at?,
+                    due_at: value.due_at?,
+                    id: value.id?,
+                    priority: value.priority?,
+                    tags: value.tags?,
+                    updated_at: value.updated_at?,
+                    value: value.value?,
+                })
+            }
+        }
+
+        impl From<super::Todo> for Todo {
+            fn from(value: super::Todo) -> Self {
+                Self {
+                    checked: Ok(value.checked),
+                    completed_at: Ok(value.completed_at),
+                    created_at: Ok(value.created_at),
+                    deleted_at: Ok(value.deleted_at),
+                    due_at: Ok(value.due_at),
+                    id: Ok(value.id),
+                    priority: Ok(value.priority),
+                    tags: Ok(value.tags),
+                    updated_at: Ok(value.updated_at),
+                    value: Ok(value.value),
+                }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct TodoUpdateRequest {
+            checked: Result<Option<bool>, String>,
+            due_at: Result<Option<chrono::DateTime<chrono::offset::Utc>>, String>,
+            priority: Result<Option<super::Priority>, String>,
+            tags: Result<Option<Vec<String>>, String>,
+            value: Result<Option<String>, String>,
+        }
+
+        impl Default for TodoUpdateRequest {
+            fn default() -> Self {
+                Self {
+                    checked: Ok(Default::default()),
+                    due_at: Ok(Default::default()),
+                    priority: Ok(Default::default()),
+                    tags: Ok(Default::default()),
+                    value: Ok(Default::default()),
+                }
+            }
+        }
+
+        impl TodoUpdateRequest {
+            pub fn checked<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<bool>>,
+                T::Error: std::fmt::Display,
+            {
+                self.checked = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for checked: {}", e));
+                self
+            }
+            pub fn due_at<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<chrono::DateTime<chrono::offset::Utc>>>,
+                T::Error: std::fmt::Display,
+            {
+                self.due_at = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for due_at: {}", e));
+                self
+            }
+            pub fn priority<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<super::Priority>>,
+                T::Error: std::fmt::Display,
+            {
+                self.priority = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for priority: {}", e));
+                self
+            }
+            pub fn tags<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<Vec<String>>>,
+                T::Error: std::fmt::Display,
+            {
+                self.tags = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for tags: {}", e));
+                self
+            }
+            pub fn value<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<Option<String>>,
+                T::Error: std::fmt::Display,
+            {
+                self.value = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for value: {}", e));
+                self
+            }
+        }
+
+        impl std::convert::TryFrom<TodoUpdateRequest> for super::TodoUpdateRequest {
+            type Error = String;
+            fn try_from(value: TodoUpdateRequest) -> Result<Self, String> {
+                Ok(Self {
+                    checked: value.checked?,
+                    due_at: value.due_at?,
+                    priority: value.priority?,
+                    tags: value.tags?,
+                    value: value.value?,
+                })
+            }
+        }
+
+        impl From<super::TodoUpdateRequest> for TodoUpdateRequest {
+            fn from(value: super::TodoUpdateRequest) -> Self {
+                Self {
+                    checked: Ok(value.checked),
+                    due_at: Ok(value.due_at),
+                    priority: Ok(value.priority),
+                    tags: Ok(value.tags),
+                    value: Ok(value.value),
+                }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct VersionInfo {
+            api_version: Result<String, String>,
+            build_time: Result<String, String>,
+            commit_time: Result<String, String>,
+            git_sha: Result<String, String>,
+            version: Result<String, String>,
+        }
+
+        impl Default for VersionInfo {
+            fn default() -> Self {
+                Self {
+                    api_version: Err("no value supplied for api_version".to_string()),
+                    build_time: Err("no value supplied for build_time".to_string()),
+                    commit_time: Err("no value supplied for commit_time".to_string()),
+                    git_sha: Err("no value supplied for git_sha".to_string()),
+                    version: Err("no value supplied for version".to_string()),
+                }
+            }
+        }
+
+        impl VersionInfo {
+            pub fn api_version<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<String>,
+                T::Error: std::fmt::Display,
+            {
+                self.api_version = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for api_version: {}", e));
+                self
+            }
+            pub fn build_time<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<String>,
+                T::Error: std::fmt::Display,
+            {
+                self.build_time = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for build_time: {}", e));
+                self
+            }
+            pub fn commit_time<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<String>,
+                T::Error: std::fmt::Display,
+            {
+                self.commit_time = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for commit_time: {}", e));
+                self
+            }
+            pub fn git_sha<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<String>,
+                T::Error: std::fmt::Display,
+            {
+                self.git_sha = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for git_sha: {}", e));
+                self
+            }
+            pub fn version<T>(mut self, value: T) -> Self
+            where
+                T: std::convert::TryInto<String>,
+                T::Error: std::fmt::Display,
+            {
+                self.version = value
+                    .try_into()
+                    .map_err(|e| format!("error converting supplied value for version: {}", e));
+                self
+            }
+        }
+
+        impl std::convert::TryFrom<VersionInfo> for super::VersionInfo {
+            type Error = String;
+            fn try_from(value: VersionInfo) -> Result<Self, String> {
+                Ok(Self {
+                    api_version: value.api_version?,
+                    build_time: value.build_time?,
+                    commit_time: value.commit_time?,
+                    git_sha: value.git_sha?,
+                    version: value.version?,
+                })
+            }
+        }
+
+        impl From<super::VersionInfo> for VersionInfo {
+            fn from(value: super::VersionInfo) -> Self {
+                Self {
+                    api_version: Ok(value.api_version),
+                    build_time: Ok(value.build_time),
+                    commit_time: Ok(value.commit_time),
+                    git_sha: Ok(value.git_sha),
+                    version: Ok(value.version),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+///Client for todo-actix
+///
+///Simple actix-web todo example api with utoipa and Swagger UI and Redoc
+///
+///The `/v1/todo` routes are also served without the `/v1` prefix, as `/todo`,
+/// for clients deployed before versioning.
+///
+///Version: 0.1.0
+pub struct Client {
+    pub(crate) baseurl: String,
+    pub(crate) client: reqwest::Client,
+}
+
+impl Client {
+    /// Create a new client.
+    ///
+    /// `baseurl` is the base URL provided to the internal
+    /// `reqwest::Client`, and should include a scheme and hostname,
+    /// as well as port and a path stem if applicable.
+    pub fn new(baseurl: &str) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let client = {
+            let dur = std::time::Duration::from_secs(15);
+            reqwest::ClientBuilder::new()
+                .connect_timeout(dur)
+                .timeout(dur)
+        };
+        #[cfg(target_arch = "wasm32")]
+        let client = reqwest::ClientBuilder::new();
+        Self::new_with_client(baseurl, client.build().unwrap())
+    }
+
+    /// Construct a new client with an existing `reqwest::Client`,
+    /// allowing more control over its configuration.
+    ///
+    /// `baseurl` is the base URL provided to the internal
+    /// `reqwest::Client`, and should include a scheme and hostname,
+    /// as well as port and a path stem if applicable.
+    pub fn new_with_client(baseurl: &str, client: reqwest::Client) -> Self {
+        Self {
+            baseurl: baseurl.to_string(),
+            client,
+        }
+    }
+
+    /// Get the base URL to which requests are made.
+    pub fn baseurl(&self) -> &String {
+        &self.baseurl
+    }
+
+    /// Get the internal `reqwest::Client` used to make requests.
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// Get the version of this API.
+    ///
+    /// This string is pulled directly from the source OpenAPI
+    /// document and may be in any format the API selects.
+    pub fn api_version(&self) -> &'static str {
+        "0.1.0"
+    }
+}
+
+pub trait ClientAuditExt {
+    ///Get audit trail
+    ///
+    ///This endpoint needs `api_key` authentication in order to call. Api key
+    /// can be found from README.md.
+    ///
+    ///List recorded mutations of the todos of the caller's tenant, oldest
+    /// first. Entries can be filtered by operation, todo id, actor and time
+    /// range and are paged with `offset` and `limit`. Only the most recent
+    /// entries are kept in memory and can be listed, older entries are only
+    /// found from the audit file.
+    ///
+    ///One could call the api endpoint with following curl.
+    ///```text
+    /// curl 'localhost:8080/audit?todo_id=1&limit=10' -H 'todo_apikey: utoipa-rocks'
+    /// ```
+    ///
+    ///Sends a `GET` request to `/audit`
+    ///
+    ///Arguments:
+    /// - `actor`: Only entries made by this actor.
+    /// - `limit`: Maximum number of entries to return, defaults to 100 and is
+    ///   capped at 1000.
+    /// - `offset`: Number of matching entries to skip, defaults to 0.
+    /// - `operation`: Only entries of this operation.
+    /// - `since`: Only entries recorded at or after this time.
+    /// - `todo_id`: Only entries of the todo with this id.
+    /// - `until`: Only entries recorded before this time.
+    ///```ignore
+    /// let response = client.get_audit()
+    ///    .actor(actor)
+    ///    .limit(limit)
+    ///    .offset(offset)
+    ///    .operation(operation)
+    ///    .since(since)
+    ///    .todo_id(todo_id)
+    ///    .until(until)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn get_audit(&self) -> builder::GetAudit<'_>;
+}
+
+impl ClientAuditExt for Client {
+    fn get_audit(&self) -> builder::GetAudit<'_> {
+        builder::GetAudit::new(self)
+    }
+}
+
+pub trait ClientOpsExt {
+    ///Check liveness of the server
+    ///
+    ///Returns 200 whenever the server is able to handle requests.
+    ///
+    ///Sends a `GET` request to `/healthz`
+    ///
+    ///```ignore
+    /// let response = client.healthz()
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn healthz(&self) -> builder::Healthz<'_>;
+    ///Check readiness of the server
+    ///
+    ///Returns 200 when the todo storage is usable and the OpenAPI document was
+    /// generated on startup, 503 otherwise.
+    ///
+    ///Sends a `GET` request to `/readyz`
+    ///
+    ///```ignore
+    /// let response = client.readyz()
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn readyz(&self) -> builder::Readyz<'_>;
+    ///Get build information
+    ///
+    ///Returns the server version, the git commit it was built from and its
+    /// commit time, the time it was built and the API version.
+    ///
+    ///Sends a `GET` request to `/version`
+    ///
+    ///```ignore
+    /// let response = client.version()
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn version(&self) -> builder::Version<'_>;
+}
+
+impl ClientOpsExt for Client {
+    fn healthz(&self) -> builder::Healthz<'_> {
+        builder::Healthz::new(self)
+    }
+
+    fn readyz(&self) -> builder::Readyz<'_> {
+        builder::Readyz::new(self)
+    }
+
+    fn version(&self) -> builder::Version<'_> {
+        builder::Version::new(self)
+    }
+}
+
+pub trait ClientTodoExt {
+    ///Get list of todos
+    ///
+    ///List todos of the caller's tenant from in-memory todo store. Todos in
+    /// the trash are only listed when `include_deleted=true` is given.
+    /// Todos are listed in order of their id, not in the order they were
+    /// created.
+    ///
+    ///One could call the api endpoint with following curl.
+    ///```text
+    /// curl localhost:8080/v2/todo
+    /// ```
+    ///
+    ///Sends a `GET` request to `/v1/todo`
+    ///
+    ///Arguments:
+    /// - `include_deleted`: Include todos moved to the trash, defaults to
+    ///   `false`.
+    ///```ignore
+    /// let response = client.get_todos()
+    ///    .include_deleted(include_deleted)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn get_todos(&self) -> builder::GetTodos<'_>;
+    ///Create new Todo to shared in-memory storage
+    ///
+    ///Post a new `Todo` in request body as json to store it. Api will return
+    ///created `Todo` on success or `ErrorResponse::Conflict` if todo with same
+    /// id already exists. Bodies which are not a valid `Todo` are rejected
+    /// with `ErrorResponse::BadRequest`.
+    ///
+    ///One could call the api with.
+    ///```text
+    /// curl localhost:8080/v2/todo -d '{"id": 1, "value": "Buy movie ticket", "checked": false}'
+    /// ```
+    ///
+    ///Sends a `POST` request to `/v1/todo`
+    ///
+    ///```ignore
+    /// let response = client.create_todo()
+    ///    .body(body)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn create_todo(&self) -> builder::CreateTodo<'_>;
+    ///Export all todos
+    ///
+    ///Streams every `Todo` of the caller's tenant from the in-memory storage
+    /// as newline delimited JSON or as CSV with a header row, which is
+    /// written even if there are no todos. The format is taken from
+    /// `format` query parameter or negotiated from `Accept` header and
+    /// defaults to newline delimited JSON.
+    ///
+    ///One could export todos as CSV with.
+    ///```text
+    /// curl localhost:8080/v2/todo/export -H 'Accept: text/csv'
+    /// ```
+    ///
+    ///Sends a `GET` request to `/v1/todo/export`
+    ///
+    ///Arguments:
+    /// - `format`: Format of the export, overrides the format negotiated from
+    ///   `Accept` header.
+    ///```ignore
+    /// let response = client.export_todos()
+    ///    .format(format)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn export_todos(&self) -> builder::ExportTodos<'_>;
+    ///Import todos
+    ///
+    ///Read todos from request body in newline delimited JSON or CSV format,
+    /// the same formats produced by export. The format is taken from
+    /// `format` query parameter or resolved from `Content-Type` header.
+    /// Otherwise, e.g. for `application/octet-stream` bodies sent by the
+    /// generated client, a body starting with a JSON object is read as newline
+    /// delimited JSON and any other body as CSV.
+    ///
+    ///Todos whose id already exists are handled according to `conflict` query
+    /// parameter. With the default `fail` policy nothing is imported and
+    /// `ErrorResponse::Conflict` is returned if any of the ids already
+    /// exists.
+    ///
+    ///One could import a CSV export with.
+    ///```text
+    /// curl localhost:8080/v2/todo/import?conflict=skip -H 'Content-Type: text/csv' --data-binary @todos.csv
+    /// ```
+    ///
+    ///Sends a `POST` request to `/v1/todo/import`
+    ///
+    ///Arguments:
+    /// - `conflict`: How to handle todos whose id already exists, defaults to
+    ///   `fail`.
+    /// - `format`: Format of the request body, overrides the format resolved
+    ///   from `Content-Type` header.
+    /// - `body`: Todos in newline delimited JSON or CSV format
+    ///```ignore
+    /// let response = client.import_todos()
+    ///    .conflict(conflict)
+    ///    .format(format)
+    ///    .body(body)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn import_todos(&self) -> builder::ImportTodos<'_>;
+    ///Search Todos with by value
+    ///
+    ///Perform search from `Todo`s of the caller's tenant present in in-memory
+    /// storage by matching Todo's value to value provided as query
+    /// parameter. Returns 200 and `Todo` items whose value contains the
+    /// given value regardless of case, in order of their id.
+    ///
+    ///The current version of the api searches by words and ranks the found
+    /// todos instead.
+    ///
+    ///One could call the api endpoint with following curl.
+    ///```text
+    /// curl 'localhost:8080/v1/todo/search?value=milk'
+    /// ```
+    ///
+    ///Sends a `GET` request to `/v1/todo/search`
+    ///
+    ///Arguments:
+    /// - `include_deleted`: Include todos moved to the trash, defaults to
+    ///   `false`.
+    /// - `value`: Content that should be found from Todo's value field
+    ///```ignore
+    /// let response = client.search_todos()
+    ///    .include_deleted(include_deleted)
+    ///    .value(value)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn search_todos(&self) -> builder::SearchTodos<'_>;
+    ///Get list of todos in the trash
+    ///
+    ///List deleted todos of the caller's tenant which were not purged yet.
+    /// They can be brought back with restore.
+    ///
+    ///One could call the api endpoint with following curl.
+    ///```text
+    /// curl localhost:8080/v2/todo/trash
+    /// ```
+    ///
+    ///Sends a `GET` request to `/v1/todo/trash`
+    ///
+    ///```ignore
+    /// let response = client.get_trash()
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn get_trash(&self) -> builder::GetTrash<'_>;
+    ///Get Todo by given todo id
+    ///
+    ///Return found `Todo` with status 200 or 404 not found if `Todo` is not
+    /// found from shared in-memory storage or it is in the trash.
+    ///
+    ///With `at` query parameter the todo is returned as it was in the given
+    /// revision or at the given time, 404 not found is returned if it did
+    /// not exist yet or was in the trash then.
+    ///
+    ///Sends a `GET` request to `/v1/todo/{id}`
+    ///
+    ///Arguments:
+    /// - `id`: Unique storage id of Todo
+    /// - `at`: Revision number or RFC 3339 timestamp to read the todo at,
+    ///   defaults to latest revision.
+    ///```ignore
+    /// let response = client.get_todo_by_id()
+    ///    .id(id)
+    ///    .at(at)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn get_todo_by_id(&self) -> builder::GetTodoById<'_>;
+    ///Update Todo with given id
+    ///
+    ///This endpoint needs `api_key` authentication in order to call.
+    ///Calls without a valid api key are rejected with 401, they used to be
+    /// served and only logged.
+    ///
+    ///Tries to update `Todo` by given id as path variable. If todo is found by
+    /// id values are updated according `TodoUpdateRequest` and updated
+    /// `Todo` is returned with status 200. If todo is not found or it is in
+    /// the trash then 404 not found is returned.
+    ///
+    ///Sends a `PUT` request to `/v1/todo/{id}`
+    ///
+    ///Arguments:
+    /// - `id`: Unique storage id of Todo
+    /// - `body`
+    ///```ignore
+    /// let response = client.update_todo()
+    ///    .id(id)
+    ///    .body(body)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn update_todo(&self) -> builder::UpdateTodo<'_>;
+    ///Delete Todo by given path variable id
+    ///
+    ///This endpoint needs `api_key` authentication in order to call. Api key
+    /// can be found from README.md.
+    ///
+    ///Api will move todo with the provided id to the trash and return success
+    /// 200. Todos in the trash are purged permanently once their retention
+    /// expires unless they are restored before. If storage does not contain
+    /// `Todo` with given id or it is already in the trash 404 not
+    /// found will be returned.
+    ///
+    ///Sends a `DELETE` request to `/v1/todo/{id}`
+    ///
+    ///Arguments:
+    /// - `id`: Unique storage id of Todo
+    ///```ignore
+    /// let response = client.delete_todo()
+    ///    .id(id)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn delete_todo(&self) -> builder::DeleteTodo<'_>;
+    ///Restore Todo from the trash by given path variable id
+    ///
+    ///This endpoint needs `api_key` authentication in order to call. Api key
+    /// can be found from README.md.
+    ///
+    ///Api will move todo with the provided id out of the trash and return the
+    /// restored `Todo` with status 200. If the trash does not contain
+    /// `Todo` with given id 404 not found will be returned.
+    ///
+    ///Sends a `POST` request to `/v1/todo/{id}/restore`
+    ///
+    ///Arguments:
+    /// - `id`: Unique storage id of Todo
+    ///```ignore
+    /// let response = client.restore_todo()
+    ///    .id(id)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn restore_todo(&self) -> builder::RestoreTodo<'_>;
+    ///Revert Todo with given id to an earlier revision
+    ///
+    ///This endpoint needs `api_key` authentication in order to call.
+    ///Calls without a valid api key are rejected with 401, they used to be
+    /// served and only logged.
+    ///
+    ///Stores a new revision of the `Todo` with the value, check status,
+    /// priority, tags and deadline of revision `rev` and returns the
+    /// reverted `Todo` with status 200. If the todo is not found, it is in
+    /// the trash or it has no revision `rev` then 404 not found is returned.
+    ///
+    ///Sends a `POST` request to `/v1/todo/{id}/revert/{rev}`
+    ///
+    ///Arguments:
+    /// - `id`: Unique storage id of Todo
+    /// - `rev`: Revision to revert the todo to
+    ///```ignore
+    /// let response = client.revert_todo()
+    ///    .id(id)
+    ///    .rev(rev)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn revert_todo(&self) -> builder::RevertTodo<'_>;
+    ///List revisions of Todo by given todo id
+    ///
+    ///Return every revision of the `Todo` oldest first with status 200,
+    /// including the revisions of a todo in the trash, or 404 not found if
+    /// `Todo` is not found from shared in-memory storage.
+    ///
+    ///Sends a `GET` request to `/v1/todo/{id}/revisions`
+    ///
+    ///Arguments:
+    /// - `id`: Unique storage id of Todo
+    ///```ignore
+    /// let response = client.get_revisions()
+    ///    .id(id)
+    ///    .send()
+    ///    .await;
+    /// ```
+    fn get_revisions(&self) -> builder::GetRevisions<'_>;
+}
+
+impl ClientTodoExt for Client {
+    fn get_todos(&self) -> builder::GetTodos<'_> {
+        builder::GetTodos::new(self)
+    }
+
+    fn create_todo(&self) -> builder::CreateTodo<'_> {
+        builder::CreateTodo::new(self)
+    }
+
+    fn export_todos(&self) -> builder::ExportTodos<'_> {
+        builder::ExportTodos::new(self)
+    }
+
+    fn import_todos(&self) -> builder::ImportTodos<'_> {
+        builder::ImportTodos::new(self)
+    }
+
+    fn search_todos(&self) -> builder::SearchTodos<'_> {
+        builder::SearchTodos::new(self)
+    }
+
+    fn get_trash(&self) -> builder::GetTrash<'_> {
+        builder::GetTrash::new(self)
+    }
+
+    fn get_todo_by_id(&self) -> builder::GetTodoById<'_> {
+        builder::GetTodoById::new(self)
+    }
+
+    fn update_todo(&self) -> builder::UpdateTodo<'_> {
+        builder::UpdateTodo::new(self)
+    }
+
+    fn delete_todo(&self) -> builder::DeleteTodo<'_> {
+        builder::DeleteTodo::new(self)
+    }
+
+    fn restore_todo(&self) -> builder::RestoreTodo<'_> {
+        builder::RestoreTodo::new(self)
+    }
+
+    fn revert_todo(&self) -> builder::RevertTodo<'_> {
+        builder::RevertTodo::new(self)
+    }
+
+    fn get_revisions(&self) -> builder::GetRevisions<'_> {
+        builder::GetRevisions::new(self)
+    }
+}
+
+pub mod builder {
+    use super::types;
+    #[allow(unused_imports)]
+    use super::{
+        encode_path, ByteStream, Error, HeaderMap, HeaderValue, RequestBuilderExt, ResponseValue,
+    };
+    ///Builder for [`ClientAuditExt::get_audit`]
+    ///
+    ///[`ClientAuditExt::get_audit`]: super::ClientAuditExt::get_audit
+    #[derive(Debug, Clone)]
+    pub struct GetAudit<'a> {
+        client: &'a super::Client,
+        actor: Result<Option<String>, String>,
+        limit: Result<Option<u64>, String>,
+        offset: Result<Option<u64>, String>,
+        operation: Result<Option<types::Operation>, String>,
+        since: Result<Option<chrono::DateTime<chrono::offset::Utc>>, String>,
+        todo_id: Result<Option<crate::TodoId>, String>,
+        until: Result<Option<chrono::DateTime<chrono::offset::Utc>>, String>,
+    }
+
+    impl<'a> GetAudit<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                actor: Ok(None),
+                limit: Ok(None),
+                offset: Ok(None),
+                operation: Ok(None),
+                since: Ok(None),
+                todo_id: Ok(None),
+                until: Ok(None),
+            }
+        }
+
+        pub fn actor<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<String>,
+        {
+            self.actor = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `String` for actor failed".to_string());
+            self
+        }
+
+        pub fn limit<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<u64>,
+        {
+            self.limit = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `u64` for limit failed".to_string());
+            self
+        }
+
+        pub fn offset<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<u64>,
+        {
+            self.offset = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `u64` for offset failed".to_string());
+            self
+        }
+
+        pub fn operation<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<types::Operation>,
+        {
+            self.operation = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `Operation` for operation failed".to_string());
+            self
+        }
+
+        pub fn since<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<chrono::DateTime<chrono::offset::Utc>>,
+        {
+            self . since = value . try_into () . map (Some) . map_err (| _ | "conversion to `chrono :: DateTime < chrono :: offset :: Utc >` for since failed" . to_string ()) ;
+            self
+        }
+
+        pub fn todo_id<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<crate::TodoId>,
+        {
+            self.todo_id = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `crate :: TodoId` for todo_id failed".to_string());
+            self
+        }
+
+        pub fn until<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<chrono::DateTime<chrono::offset::Utc>>,
+        {
+            self . until = value . try_into () . map (Some) . map_err (| _ | "conversion to `chrono :: DateTime < chrono :: offset :: Utc >` for until failed" . to_string ()) ;
+            self
+        }
+
+        ///Sends a `GET` request to `/audit`
+        pub async fn send(
+            self,
+        ) -> Result<ResponseValue<types::AuditPage>, Error<types::ErrorResponse>> {
+            let Self {
+                client,
+                actor,
+                limit,
+                offset,
+                operation,
+                since,
+                todo_id,
+                until,
+            } = self;
+            let actor = actor.map_err(Error::InvalidRequest)?;
+            let limit = limit.map_err(Error::InvalidRequest)?;
+            let offset = offset.map_err(Error::InvalidRequest)?;
+            let operation = operation.map_err(Error::InvalidRequest)?;
+            let since = since.map_err(Error::InvalidRequest)?;
+            let todo_id = todo_id.map_err(Error::InvalidRequest)?;
+            let until = until.map_err(Error::InvalidRequest)?;
+            let url = format!("{}/audit", client.baseurl,);
+            let mut query = Vec::with_capacity(7usize);
+            if let Some(v) = &actor {
+                query.push(("actor", v.to_string()));
+            }
+            if let Some(v) = &limit {
+                query.push(("limit", v.to_string()));
+            }
+            if let Some(v) = &offset {
+                query.push(("offset", v.to_string()));
+            }
+            if let Some(v) = &operation {
+                query.push(("operation", v.to_string()));
+            }
+            if let Some(v) = &since {
+                query.push(("since", v.to_string()));
+            }
+            if let Some(v) = &todo_id {
+                query.push(("todo_id", v.to_string()));
+            }
+            if let Some(v) = &until {
+                query.push(("until", v.to_string()));
+            }
+            let request = client
+                .client
+                .get(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .query(&query)
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientOpsExt::healthz`]
+    ///
+    ///[`ClientOpsExt::healthz`]: super::ClientOpsExt::healthz
+    #[derive(Debug, Clone)]
+    pub struct Healthz<'a> {
+        client: &'a super::Client,
+    }
+
+    impl<'a> Healthz<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self { client }
+        }
+
+        ///Sends a `GET` request to `/healthz`
+        pub async fn send(self) -> Result<ResponseValue<types::Health>, Error<()>> {
+            let Self { client } = self;
+            let url = format!("{}/healthz", client.baseurl,);
+            let request = client
+                .client
+                .get(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientOpsExt::readyz`]
+    ///
+    ///[`ClientOpsExt::readyz`]: super::ClientOpsExt::readyz
+    #[derive(Debug, Clone)]
+    pub struct Readyz<'a> {
+        client: &'a super::Client,
+    }
+
+    impl<'a> Readyz<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self { client }
+        }
+
+        ///Sends a `GET` request to `/readyz`
+        pub async fn send(
+            self,
+        ) -> Result<ResponseValue<types::Readiness>, Error<types::Readiness>> {
+            let Self { client } = self;
+            let url = format!("{}/readyz", client.baseurl,);
+            let request = client
+                .client
+                .get(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                503u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::get_todos`]
+    ///
+    ///[`ClientTodoExt::get_todos`]: super::ClientTodoExt::get_todos
+    #[derive(Debug, Clone)]
+    pub struct GetTodos<'a> {
+        client: &'a super::Client,
+        include_deleted: Result<Option<bool>, String>,
+    }
+
+    impl<'a> GetTodos<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                include_deleted: Ok(None),
+            }
+        }
+
+        pub fn include_deleted<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<bool>,
+        {
+            self.include_deleted = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `bool` for include_deleted failed".to_string());
+            self
+        }
+
+        ///Sends a `GET` request to `/v1/todo`
+        pub async fn send(
+            self,
+        ) -> Result<ResponseValue<Vec<types::Todo>>, Error<types::ErrorResponse>> {
+            let Self {
+                client,
+                include_deleted,
+            } = self;
+            let include_deleted = include_deleted.map_err(Error::InvalidRequest)?;
+            let url = format!("{}/v1/todo", client.baseurl,);
+            let mut query = Vec::with_capacity(1usize);
+            if let Some(v) = &include_deleted {
+                query.push(("include_deleted", v.to_string()));
+            }
+            let request = client
+                .client
+                .get(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .query(&query)
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::create_todo`]
+    ///
+    ///[`ClientTodoExt::create_todo`]: super::ClientTodoExt::create_todo
+    #[derive(Debug, Clone)]
+    pub struct CreateTodo<'a> {
+        client: &'a super::Client,
+        body: Result<types::builder::Todo, String>,
+    }
+
+    impl<'a> CreateTodo<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                body: Ok(types::builder::Todo::default()),
+            }
+        }
+
+        pub fn body<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<types::Todo>,
+        {
+            self.body = value
+                .try_into()
+                .map(From::from)
+                .map_err(|_| "conversion to `Todo` for body failed".to_string());
+            self
+        }
+
+        pub fn body_map<F>(mut self, f: F) -> Self
+        where
+            F: std::ops::FnOnce(types::builder::Todo) -> types::builder::Todo,
+        {
+            self.body = self.body.map(f);
+            self
+        }
+
+        ///Sends a `POST` request to `/v1/todo`
+        pub async fn send(self) -> Result<ResponseValue<types::Todo>, Error<types::ErrorResponse>> {
+            let Self { client, body } = self;
+            let body = body
+                .and_then(std::convert::TryInto::<types::Todo>::try_into)
+                .map_err(Error::InvalidRequest)?;
+            let url = format!("{}/v1/todo", client.baseurl,);
+            let request = client
+                .client
+                .post(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .json(&body)
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                201u16 => ResponseValue::from_response(response).await,
+                400u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                409u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                413u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::export_todos`]
+    ///
+    ///[`ClientTodoExt::export_todos`]: super::ClientTodoExt::export_todos
+    #[derive(Debug, Clone)]
+    pub struct ExportTodos<'a> {
+        client: &'a super::Client,
+        format: Result<Option<types::TransferFormat>, String>,
+    }
+
+    impl<'a> ExportTodos<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                format: Ok(None),
+            }
+        }
+
+        pub fn format<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<types::TransferFormat>,
+        {
+            self.format = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `TransferFormat` for format failed".to_string());
+            self
+        }
+
+        ///Sends a `GET` request to `/v1/todo/export`
+        pub async fn send(self) -> Result<ResponseValue<ByteStream>, Error<types::ErrorResponse>> {
+            let Self { client, format } = self;
+            let format = format.map_err(Error::InvalidRequest)?;
+            let url = format!("{}/v1/todo/export", client.baseurl,);
+            let mut query = Vec::with_capacity(1usize);
+            if let Some(v) = &format {
+                query.push(("format", v.to_string()));
+            }
+            let request = client.client.get(url).query(&query).build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => Ok(ResponseValue::stream(response)),
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::import_todos`]
+    ///
+    ///[`ClientTodoExt::import_todos`]: super::ClientTodoExt::import_todos
+    #[derive(Debug)]
+    pub struct ImportTodos<'a> {
+        client: &'a super::Client,
+        conflict: Result<Option<types::ConflictPolicy>, String>,
+        format: Result<Option<types::TransferFormat>, String>,
+        body: Result<reqwest::Body, String>,
+    }
+
+    impl<'a> ImportTodos<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                conflict: Ok(None),
+                format: Ok(None),
+                body: Err("body was not initialized".to_string()),
+            }
+        }
+
+        pub fn conflict<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<types::ConflictPolicy>,
+        {
+            self.conflict = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `ConflictPolicy` for conflict failed".to_string());
+            self
+        }
+
+        pub fn format<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<types::TransferFormat>,
+        {
+            self.format = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `TransferFormat` for format failed".to_string());
+            self
+        }
+
+        pub fn body<B>(mut self, value: B) -> Self
+        where
+            B: std::convert::TryInto<reqwest::Body>,
+        {
+            self.body = value
+                .try_into()
+                .map_err(|_| "conversion to `reqwest::Body` for body failed".to_string());
+            self
+        }
+
+        ///Sends a `POST` request to `/v1/todo/import`
+        pub async fn send(
+            self,
+        ) -> Result<ResponseValue<types::ImportReport>, Error<types::ErrorResponse>> {
+            let Self {
+                client,
+                conflict,
+                format,
+                body,
+            } = self;
+            let conflict = conflict.map_err(Error::InvalidRequest)?;
+            let format = format.map_err(Error::InvalidRequest)?;
+            let body = body.map_err(Error::InvalidRequest)?;
+            let url = format!("{}/v1/todo/import", client.baseurl,);
+            let mut query = Vec::with_capacity(2usize);
+            if let Some(v) = &conflict {
+                query.push(("conflict", v.to_string()));
+            }
+            if let Some(v) = &format {
+                query.push(("format", v.to_string()));
+            }
+            let request = client
+                .client
+                .post(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .header(
+                    reqwest::header::CONTENT_TYPE,
+                    reqwest::header::HeaderValue::from_static("application/octet-stream"),
+                )
+                .body(body)
+                .query(&query)
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                400u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                409u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                413u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::search_todos`]
+    ///
+    ///[`ClientTodoExt::search_todos`]: super::ClientTodoExt::search_todos
+    #[derive(Debug, Clone)]
+    pub struct SearchTodos<'a> {
+        client: &'a super::Client,
+        include_deleted: Result<Option<bool>, String>,
+        value: Result<Option<String>, String>,
+    }
+
+    impl<'a> SearchTodos<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                include_deleted: Ok(None),
+                value: Ok(None),
+            }
+        }
+
+        pub fn include_deleted<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<bool>,
+        {
+            self.include_deleted = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `bool` for include_deleted failed".to_string());
+            self
+        }
+
+        pub fn value<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<String>,
+        {
+            self.value = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `String` for value failed".to_string());
+            self
+        }
+
+        ///Sends a `GET` request to `/v1/todo/search`
+        pub async fn send(
+            self,
+        ) -> Result<ResponseValue<Vec<types::Todo>>, Error<types::ErrorResponse>> {
+            let Self {
+                client,
+                include_deleted,
+                value,
+            } = self;
+            let include_deleted = include_deleted.map_err(Error::InvalidRequest)?;
+            let value = value.map_err(Error::InvalidRequest)?;
+            let url = format!("{}/v1/todo/search", client.baseurl,);
+            let mut query = Vec::with_capacity(2usize);
+            if let Some(v) = &include_deleted {
+                query.push(("include_deleted", v.to_string()));
+            }
+            if let Some(v) = &value {
+                query.push(("value", v.to_string()));
+            }
+            let request = client
+                .client
+                .get(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .query(&query)
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::get_trash`]
+    ///
+    ///[`ClientTodoExt::get_trash`]: super::ClientTodoExt::get_trash
+    #[derive(Debug, Clone)]
+    pub struct GetTrash<'a> {
+        client: &'a super::Client,
+    }
+
+    impl<'a> GetTrash<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self { client }
+        }
+
+        ///Sends a `GET` request to `/v1/todo/trash`
+        pub async fn send(
+            self,
+        ) -> Result<ResponseValue<Vec<types::Todo>>, Error<types::ErrorResponse>> {
+            let Self { client } = self;
+            let url = format!("{}/v1/todo/trash", client.baseurl,);
+            let request = client
+                .client
+                .get(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::get_todo_by_id`]
+    ///
+    ///[`ClientTodoExt::get_todo_by_id`]: super::ClientTodoExt::get_todo_by_id
+    #[derive(Debug, Clone)]
+    pub struct GetTodoById<'a> {
+        client: &'a super::Client,
+        id: Result<crate::TodoId, String>,
+        at: Result<Option<String>, String>,
+    }
+
+    impl<'a> GetTodoById<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                id: Err("id was not initialized".to_string()),
+                at: Ok(None),
+            }
+        }
+
+        pub fn id<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<crate::TodoId>,
+        {
+            self.id = value
+                .try_into()
+                .map_err(|_| "conversion to `crate :: TodoId` for id failed".to_string());
+            self
+        }
+
+        pub fn at<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<String>,
+        {
+            self.at = value
+                .try_into()
+                .map(Some)
+                .map_err(|_| "conversion to `String` for at failed".to_string());
+            self
+        }
+
+        ///Sends a `GET` request to `/v1/todo/{id}`
+        pub async fn send(self) -> Result<ResponseValue<types::Todo>, Error<types::ErrorResponse>> {
+            let Self { client, id, at } = self;
+            let id = id.map_err(Error::InvalidRequest)?;
+            let at = at.map_err(Error::InvalidRequest)?;
+            let url = format!(
+                "{}/v1/todo/{}",
+                client.baseurl,
+                encode_path(&id.to_string()),
+            );
+            let mut query = Vec::with_capacity(1usize);
+            if let Some(v) = &at {
+                query.push(("at", v.to_string()));
+            }
+            let request = client
+                .client
+                .get(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .query(&query)
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                400u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                404u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::update_todo`]
+    ///
+    ///[`ClientTodoExt::update_todo`]: super::ClientTodoExt::update_todo
+    #[derive(Debug, Clone)]
+    pub struct UpdateTodo<'a> {
+        client: &'a super::Client,
+        id: Result<crate::TodoId, String>,
+        body: Result<types::builder::TodoUpdateRequest, String>,
+    }
+
+    impl<'a> UpdateTodo<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                id: Err("id was not initialized".to_string()),
+                body: Ok(types::builder::TodoUpdateRequest::default()),
+            }
+        }
+
+        pub fn id<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<crate::TodoId>,
+        {
+            self.id = value
+                .try_into()
+                .map_err(|_| "conversion to `crate :: TodoId` for id failed".to_string());
+            self
+        }
+
+        pub fn body<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<types::TodoUpdateRequest>,
+        {
+            self.body = value
+                .try_into()
+                .map(From::from)
+                .map_err(|_| "conversion to `TodoUpdateRequest` for body failed".to_string());
+            self
+        }
+
+        pub fn body_map<F>(mut self, f: F) -> Self
+        where
+            F: std::ops::FnOnce(
+                types::builder::TodoUpdateRequest,
+            ) -> types::builder::TodoUpdateRequest,
+        {
+            self.body = self.body.map(f);
+            self
+        }
+
+        ///Sends a `PUT` request to `/v1/todo/{id}`
+        pub async fn send(self) -> Result<ResponseValue<types::Todo>, Error<types::ErrorResponse>> {
+            let Self { client, id, body } = self;
+            let id = id.map_err(Error::InvalidRequest)?;
+            let body = body
+                .and_then(std::convert::TryInto::<types::TodoUpdateRequest>::try_into)
+                .map_err(Error::InvalidRequest)?;
+            let url = format!(
+                "{}/v1/todo/{}",
+                client.baseurl,
+                encode_path(&id.to_string()),
+            );
+            let request = client
+                .client
+                .put(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .json(&body)
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                400u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                404u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                413u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::delete_todo`]
+    ///
+    ///[`ClientTodoExt::delete_todo`]: super::ClientTodoExt::delete_todo
+    #[derive(Debug, Clone)]
+    pub struct DeleteTodo<'a> {
+        client: &'a super::Client,
+        id: Result<crate::TodoId, String>,
+    }
+
+    impl<'a> DeleteTodo<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                id: Err("id was not initialized".to_string()),
+            }
+        }
+
+        pub fn id<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<crate::TodoId>,
+        {
+            self.id = value
+                .try_into()
+                .map_err(|_| "conversion to `crate :: TodoId` for id failed".to_string());
+            self
+        }
+
+        ///Sends a `DELETE` request to `/v1/todo/{id}`
+        pub async fn send(self) -> Result<ResponseValue<()>, Error<types::ErrorResponse>> {
+            let Self { client, id } = self;
+            let id = id.map_err(Error::InvalidRequest)?;
+            let url = format!(
+                "{}/v1/todo/{}",
+                client.baseurl,
+                encode_path(&id.to_string()),
+            );
+            let request = client
+                .client
+                .delete(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => Ok(ResponseValue::empty(response)),
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                404u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::restore_todo`]
+    ///
+    ///[`ClientTodoExt::restore_todo`]: super::ClientTodoExt::restore_todo
+    #[derive(Debug, Clone)]
+    pub struct RestoreTodo<'a> {
+        client: &'a super::Client,
+        id: Result<crate::TodoId, String>,
+    }
+
+    impl<'a> RestoreTodo<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                id: Err("id was not initialized".to_string()),
+            }
+        }
+
+        pub fn id<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<crate::TodoId>,
+        {
+            self.id = value
+                .try_into()
+                .map_err(|_| "conversion to `crate :: TodoId` for id failed".to_string());
+            self
+        }
+
+        ///Sends a `POST` request to `/v1/todo/{id}/restore`
+        pub async fn send(self) -> Result<ResponseValue<types::Todo>, Error<types::ErrorResponse>> {
+            let Self { client, id } = self;
+            let id = id.map_err(Error::InvalidRequest)?;
+            let url = format!(
+                "{}/v1/todo/{}/restore",
+                client.baseurl,
+                encode_path(&id.to_string()),
+            );
+            let request = client
+                .client
+                .post(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                404u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::revert_todo`]
+    ///
+    ///[`ClientTodoExt::revert_todo`]: super::ClientTodoExt::revert_todo
+    #[derive(Debug, Clone)]
+    pub struct RevertTodo<'a> {
+        client: &'a super::Client,
+        id: Result<crate::TodoId, String>,
+        rev: Result<i64, String>,
+    }
+
+    impl<'a> RevertTodo<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                id: Err("id was not initialized".to_string()),
+                rev: Err("rev was not initialized".to_string()),
+            }
+        }
+
+        pub fn id<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<crate::TodoId>,
+        {
+            self.id = value
+                .try_into()
+                .map_err(|_| "conversion to `crate :: TodoId` for id failed".to_string());
+            self
+        }
+
+        pub fn rev<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<i64>,
+        {
+            self.rev = value
+                .try_into()
+                .map_err(|_| "conversion to `i64` for rev failed".to_string());
+            self
+        }
+
+        ///Sends a `POST` request to `/v1/todo/{id}/revert/{rev}`
+        pub async fn send(self) -> Result<ResponseValue<types::Todo>, Error<types::ErrorResponse>> {
+            let Self { client, id, rev } = self;
+            let id = id.map_err(Error::InvalidRequest)?;
+            let rev = rev.map_err(Error::InvalidRequest)?;
+            let url = format!(
+                "{}/v1/todo/{}/revert/{}",
+                client.baseurl,
+                encode_path(&id.to_string()),
+                encode_path(&rev.to_string()),
+            );
+            let request = client
+                .client
+                .post(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                404u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientTodoExt::get_revisions`]
+    ///
+    ///[`ClientTodoExt::get_revisions`]: super::ClientTodoExt::get_revisions
+    #[derive(Debug, Clone)]
+    pub struct GetRevisions<'a> {
+        client: &'a super::Client,
+        id: Result<crate::TodoId, String>,
+    }
+
+    impl<'a> GetRevisions<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self {
+                client,
+                id: Err("id was not initialized".to_string()),
+            }
+        }
+
+        pub fn id<V>(mut self, value: V) -> Self
+        where
+            V: std::convert::TryInto<crate::TodoId>,
+        {
+            self.id = value
+                .try_into()
+                .map_err(|_| "conversion to `crate :: TodoId` for id failed".to_string());
+            self
+        }
+
+        ///Sends a `GET` request to `/v1/todo/{id}/revisions`
+        pub async fn send(
+            self,
+        ) -> Result<ResponseValue<Vec<types::Revision>>, Error<types::ErrorResponse>> {
+            let Self { client, id } = self;
+            let id = id.map_err(Error::InvalidRequest)?;
+            let url = format!(
+                "{}/v1/todo/{}/revisions",
+                client.baseurl,
+                encode_path(&id.to_string()),
+            );
+            let request = client
+                .client
+                .get(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                401u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                404u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                429u16 => Err(Error::ErrorResponse(
+                    ResponseValue::from_response(response).await?,
+                )),
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+
+    ///Builder for [`ClientOpsExt::version`]
+    ///
+    ///[`ClientOpsExt::version`]: super::ClientOpsExt::version
+    #[derive(Debug, Clone)]
+    pub struct Version<'a> {
+        client: &'a super::Client,
+    }
+
+    impl<'a> Version<'a> {
+        pub fn new(client: &'a super::Client) -> Self {
+            Self { client }
+        }
+
+        ///Sends a `GET` request to `/version`
+        pub async fn send(self) -> Result<ResponseValue<types::VersionInfo>, Error<()>> {
+            let Self { client } = self;
+            let url = format!("{}/version", client.baseurl,);
+            let request = client
+                .client
+                .get(url)
+                .header(
+                    reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                )
+                .build()?;
+            let result = client.client.execute(request).await;
+            let response = result?;
+            match response.status().as_u16() {
+                200u16 => ResponseValue::from_response(response).await,
+                _ => Err(Error::UnexpectedResponse(response)),
+            }
+        }
+    }
+}
+
+pub mod prelude {
+    pub use super::Client;
+    pub use super::ClientAuditExt;
+    pub use super::ClientOpsExt;
+    pub use super::ClientTodoExt;
+}
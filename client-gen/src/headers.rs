@@ -0,0 +1,213 @@
+//! Structs of the response headers each operation declares.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use serde_json::Value;
+
+/// Header declared by responses of an operation.
+struct Header {
+    /// Rust type the value is parsed as.
+    rust: &'static str,
+    description: Option<String>,
+    /// Statuses of the responses declaring the header.
+    statuses: Vec<String>,
+}
+
+/// `get_todo_by_id` as `GetTodoById`, the name progenitor gives the builder of an operation.
+pub fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
+/// `RateLimit-Limit` as `ratelimit_limit`.
+fn field_name(header: &str) -> String {
+    header
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_lowercase(),
+            false => '_',
+        })
+        .collect()
+}
+
+fn rust_type(schema: &Value) -> &'static str {
+    match (
+        schema.get("type").and_then(Value::as_str),
+        schema.get("format").and_then(Value::as_str),
+    ) {
+        (Some("integer"), Some("int32")) => "i32",
+        (Some("integer"), _) => "i64",
+        (Some("number"), _) => "f64",
+        (Some("boolean"), _) => "bool",
+        _ => "String",
+    }
+}
+
+/// Headers declared by the responses of `operation`, by name.
+fn declared(operation: &Value) -> BTreeMap<&str, Header> {
+    let mut declared = BTreeMap::<&str, Header>::new();
+    let responses = operation["responses"].as_object().into_iter().flatten();
+    for (status, response) in responses {
+        for (name, header) in response["headers"].as_object().into_iter().flatten() {
+            let header = declared.entry(name).or_insert_with(|| Header {
+                rust: rust_type(&header["schema"]),
+                description: header["description"].as_str().map(String::from),
+                statuses: Vec::new(),
+            });
+            header.statuses.push(status.clone());
+        }
+    }
+    declared
+}
+
+/// Module `headers` with a struct for every operation of `document` holding the headers its
+/// responses declare.
+pub fn module(document: &Value) -> String {
+    let mut operations = document["paths"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .flat_map(|(_, path_item)| path_item.as_object().into_iter().flatten())
+        .filter_map(|(_, operation)| Some((operation["operationId"].as_str()?, operation)))
+        .collect::<Vec<_>>();
+    operations.sort_by_key(|(id, _)| *id);
+
+    let mut module = String::from(
+        "///Headers declared by the responses of every operation, read with\n\
+         ///[`ResponseHeadersExt::typed_headers`](crate::ResponseHeadersExt::typed_headers) and\n\
+         ///the builder of the operation.\n\
+         pub mod headers {\n",
+    );
+    for (id, operation) in operations {
+        let name = pascal_case(id);
+        let headers = declared(operation);
+        let operation_impl = format!(
+            "impl crate::Operation for super::builder::{name}<'_> {{\n\
+             type Headers = {name};\n\
+             }}\n"
+        );
+        writeln!(
+            module,
+            "///Headers declared by the responses of \
+             [`{name}`](super::builder::{name}).\n\
+             #[derive(Clone, Debug, Default, PartialEq)]"
+        )
+        .unwrap();
+        if headers.is_empty() {
+            writeln!(
+                module,
+                "pub struct {name};\n\n\
+                 impl crate::ResponseHeaders for {name} {{\n\
+                 fn from_headers(_: &reqwest::header::HeaderMap) -> Self {{ Self }}\n\
+                 }}\n\n\
+                 {operation_impl}"
+            )
+            .unwrap();
+            continue;
+        }
+
+        writeln!(module, "pub struct {name} {{").unwrap();
+        for (header, declared) in &headers {
+            if let Some(description) = &declared.description {
+                for line in description.lines() {
+                    writeln!(module, "///{line}").unwrap();
+                }
+                writeln!(module, "///").unwrap();
+            }
+            writeln!(
+                module,
+                "///`{header}` header, declared by {} responses.\n\
+                 pub {}: Option<{}>,",
+                declared.statuses.join(", "),
+                field_name(header),
+                declared.rust,
+            )
+            .unwrap();
+        }
+        writeln!(
+            module,
+            "}}\n\n\
+             impl crate::ResponseHeaders for {name} {{\n\
+             fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {{\n\
+             Self {{"
+        )
+        .unwrap();
+        for header in headers.keys() {
+            writeln!(
+                module,
+                "{}: crate::response::header(headers, \"{header}\"),",
+                field_name(header)
+            )
+            .unwrap();
+        }
+        writeln!(module, "}}\n}}\n}}\n\n{operation_impl}").unwrap();
+    }
+    module.push_str("}\n");
+    module
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn pascal_case_matches_builder_names() {
+        assert_eq!(pascal_case("get_todo_by_id"), "GetTodoById");
+        assert_eq!(pascal_case("search_todos_v1"), "SearchTodosV1");
+        assert_eq!(pascal_case("health"), "Health");
+    }
+
+    #[test]
+    fn field_name_is_snake_case() {
+        assert_eq!(field_name("RateLimit-Limit"), "ratelimit_limit");
+        assert_eq!(field_name("X-Request-Id"), "x_request_id");
+        assert_eq!(field_name("ETag"), "etag");
+    }
+
+    #[test]
+    fn rust_type_follows_schema() {
+        assert_eq!(rust_type(&json!({"type": "integer"})), "i64");
+        assert_eq!(
+            rust_type(&json!({"type": "integer", "format": "int32"})),
+            "i32"
+        );
+        assert_eq!(rust_type(&json!({"type": "number"})), "f64");
+        assert_eq!(rust_type(&json!({"type": "boolean"})), "bool");
+        assert_eq!(rust_type(&json!({"type": "string"})), "String");
+        assert_eq!(rust_type(&json!({})), "String");
+    }
+
+    #[test]
+    fn multi_line_descriptions_stay_doc_comments() {
+        let document = json!({"paths": {"/todo": {"get": {
+            "operationId": "get_todos",
+            "responses": {"200": {"headers": {"RateLimit-Policy": {
+                "description": "Quota of the route.\n\nSee the README.",
+                "schema": {"type": "string"},
+            }}}},
+        }}}});
+
+        let module = module(&document);
+        let field = module
+            .split("pub struct GetTodos {\n")
+            .nth(1)
+            .and_then(|rest| rest.split("pub ratelimit_policy").next())
+            .unwrap();
+        assert_eq!(
+            field,
+            "///Quota of the route.\n///\n///See the README.\n///\n\
+             ///`RateLimit-Policy` header, declared by 200 responses.\n"
+        );
+        assert!(module.contains("impl crate::Operation for super::builder::GetTodos<'_>"));
+    }
+}
@@ -0,0 +1,125 @@
+//! Generate `actix-todo/src/lib.rs` from the OpenAPI document of the current version, and a
+//! module of `actix-todo` from the document of every older version.
+//!
+//! ```sh
+//! cargo run -p client-gen
+//! ```
+//!
+//! Schemas of `docs/type-mappings.json` are represented with the Rust types of the table instead
+//! of generated ones. The code is formatted with nightly rustfmt like `cargo progenitor` does,
+//! then the hand-maintained additions of the client and structs of the response headers
+//! each operation declares are added to the client of the current version.
+
+mod headers;
+mod mappings;
+mod patch;
+
+use std::{
+    fs,
+    io::Write,
+    path::Path,
+    process::{Command, ExitCode, Stdio},
+};
+
+use progenitor::{GenerationSettings, Generator, InterfaceStyle, TagStyle};
+use serde_json::Value;
+
+use crate::mappings::Mappings;
+
+/// Paths relative to the root of the workspace.
+const DOCUMENT: &str = "docs/v2/openapi.json";
+const MAPPINGS: &str = "docs/type-mappings.json";
+const CLIENT: &str = "actix-todo/src/lib.rs";
+
+/// Documents of the older versions and the modules of `actix-todo` their clients are written
+/// to, relative to the root of the workspace.
+const OLDER_CLIENTS: [(&str, &str); 1] = [("docs/v1/openapi.json", "actix-todo/src/v1.rs")];
+
+/// Format `code` with rustfmt, passing it `args`.
+fn rustfmt(code: &str, args: &[&str]) -> Result<String, String> {
+    let mut rustfmt = Command::new("rustfmt")
+        .args(args)
+        .args(["--edition", "2021", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("rustfmt: {error}"))?;
+    rustfmt
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(code.as_bytes())
+        .map_err(|error| format!("rustfmt: {error}"))?;
+    let output = rustfmt
+        .wait_with_output()
+        .map_err(|error| format!("rustfmt: {error}"))?;
+    if !output.status.success() {
+        return Err(format!("rustfmt: {}", output.status));
+    }
+    String::from_utf8(output.stdout).map_err(|error| format!("rustfmt: {error}"))
+}
+
+/// Generate the client of `document` formatted like `cargo progenitor` does, returning it with
+/// the parsed document.
+fn generate(root: &Path, document: &str) -> Result<(String, Value), String> {
+    let path = root.join(document);
+    let document = fs::read_to_string(&path).map_err(|error| format!("{path:?}: {error}"))?;
+    let document: Value =
+        serde_json::from_str(&document).map_err(|error| format!("{path:?}: {error}"))?;
+    let mappings = Mappings::load(&root.join(MAPPINGS))?;
+    mappings.check(&document)?;
+
+    let mut settings = GenerationSettings::default();
+    settings
+        .with_interface(InterfaceStyle::Builder)
+        .with_tag(TagStyle::Separate);
+    mappings.apply(&mut settings)?;
+    let spec =
+        serde_json::from_value(document.clone()).map_err(|error| format!("{path:?}: {error}"))?;
+    let tokens = Generator::new(&settings)
+        .generate_tokens(&spec)
+        .map_err(|error| format!("{path:?}: {error}"))?;
+
+    let code = rustfmt(
+        &tokens.to_string(),
+        &[
+            "+nightly",
+            "--config",
+            "normalize_doc_attributes=true,wrap_comments=true",
+        ],
+    )?;
+    Ok((patch::space_items(&code), document))
+}
+
+/// Generate the client of the current version and the modules of the older ones, returning
+/// each with the path it is written to.
+fn generate_all(root: &Path) -> Result<Vec<(&'static str, String)>, String> {
+    let (code, document) = generate(root, DOCUMENT)?;
+    let mut clients = vec![(
+        CLIENT,
+        rustfmt(&patch::apply(&code, &headers::module(&document))?, &[])?,
+    )];
+    for (document, module) in OLDER_CLIENTS {
+        let (code, _) = generate(root, document)?;
+        clients.push((module, rustfmt(&patch::older(&code, document), &[])?));
+    }
+    Ok(clients)
+}
+
+fn main() -> ExitCode {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let clients = match generate_all(&root) {
+        Ok(clients) => clients,
+        Err(error) => {
+            eprintln!("client-gen: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    for (path, client) in clients {
+        if let Err(error) = fs::write(root.join(path), client) {
+            eprintln!("client-gen: {path}: {error}");
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
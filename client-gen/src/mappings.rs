@@ -0,0 +1,175 @@
+//! Table of the schema types the client represents with its own Rust types.
+
+use std::{fs, path::Path};
+
+use progenitor::{GenerationSettings, TypeImpl};
+use schemars::schema::SchemaObject;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Types of `docs/type-mappings.json`, shared by the server and the generated client.
+#[derive(Deserialize, Debug)]
+pub struct Mappings {
+    /// Component schemas replaced by a Rust type instead of generating one.
+    pub replacements: Vec<Replacement>,
+    /// Schemas of a type and format converted to a Rust type wherever they are used.
+    pub conversions: Vec<Conversion>,
+}
+
+/// Component schema `schema` replaced by Rust type `rust`.
+#[derive(Deserialize, Debug)]
+pub struct Replacement {
+    pub schema: String,
+    /// JSON type the server declares for the schema, e.g. `integer`.
+    #[serde(rename = "type")]
+    pub schema_type: String,
+    /// Format the server declares for the schema, e.g. `int32`.
+    pub format: Option<String>,
+    pub rust: String,
+    /// Traits of `Display`, `FromStr` and `Default` the Rust type implements.
+    #[serde(default)]
+    pub impls: Vec<String>,
+}
+
+/// Schemas of type `schema_type` and `format` converted to Rust type `rust`.
+#[derive(Deserialize, Debug)]
+pub struct Conversion {
+    #[serde(rename = "type")]
+    pub schema_type: String,
+    pub format: String,
+    pub rust: String,
+    /// Traits of `Display`, `FromStr` and `Default` the Rust type implements.
+    #[serde(default)]
+    pub impls: Vec<String>,
+}
+
+impl Mappings {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mappings = fs::read_to_string(path).map_err(|error| format!("{path:?}: {error}"))?;
+        serde_json::from_str(&mappings).map_err(|error| format!("{path:?}: {error}"))
+    }
+
+    /// Check every replaced schema is declared by `document` with the type and format of the
+    /// table, so the Rust type agrees with what the server sends.
+    pub fn check(&self, document: &Value) -> Result<(), String> {
+        for replacement in &self.replacements {
+            let schema = document
+                .pointer(&format!("/components/schemas/{}", replacement.schema))
+                .ok_or_else(|| format!("schema `{}` is not declared", replacement.schema))?;
+            let declared = (
+                schema.get("type").and_then(Value::as_str),
+                schema.get("format").and_then(Value::as_str),
+            );
+            if declared
+                != (
+                    Some(&*replacement.schema_type),
+                    replacement.format.as_deref(),
+                )
+            {
+                return Err(format!(
+                    "schema `{}` is declared as {} {}, the table expects {} {}",
+                    replacement.schema,
+                    declared.0.unwrap_or("untyped"),
+                    declared.1.unwrap_or("without format"),
+                    replacement.schema_type,
+                    replacement.format.as_deref().unwrap_or("without format"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Register the replacements and conversions with the generator `settings`.
+    pub fn apply(&self, settings: &mut GenerationSettings) -> Result<(), String> {
+        for replacement in &self.replacements {
+            settings.with_replacement(
+                &replacement.schema,
+                &replacement.rust,
+                impls(&replacement.impls)?.into_iter(),
+            );
+        }
+        for conversion in &self.conversions {
+            let schema: SchemaObject = serde_json::from_value(json!({
+                "type": conversion.schema_type,
+                "format": conversion.format,
+            }))
+            .map_err(|error| {
+                format!("{} {}: {error}", conversion.schema_type, conversion.format)
+            })?;
+            settings.with_conversion(
+                schema,
+                &conversion.rust,
+                impls(&conversion.impls)?.into_iter(),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn impls(names: &[String]) -> Result<Vec<TypeImpl>, String> {
+    names.iter().map(|name| name.parse()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use progenitor::Generator;
+
+    use super::*;
+
+    /// Types generated for component `schemas` with the table of the workspace, without
+    /// whitespace.
+    fn generate(schemas: Value) -> String {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mappings = Mappings::load(&root.join("docs/type-mappings.json")).unwrap();
+        let mut settings = GenerationSettings::default();
+        mappings.apply(&mut settings).unwrap();
+        let spec = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "mappings", "version": "1"},
+            "paths": {},
+            "components": {"schemas": schemas},
+        }))
+        .unwrap();
+
+        let code = Generator::new(&settings)
+            .generate_tokens(&spec)
+            .unwrap()
+            .to_string();
+        code.split_whitespace().collect()
+    }
+
+    #[test]
+    fn string_formats_are_converted() {
+        let code = generate(json!({"Reminder": {
+            "type": "object",
+            "required": ["id", "at", "on"],
+            "properties": {
+                "id": {"type": "string", "format": "uuid"},
+                "at": {"type": "string", "format": "date-time"},
+                "on": {"type": "string", "format": "date"},
+            },
+        }}));
+
+        assert!(code.contains("pubid:uuid::Uuid,"), "{code}");
+        assert!(
+            code.contains("pubat:chrono::DateTime<chrono::offset::Utc>,"),
+            "{code}"
+        );
+        assert!(code.contains("pubon:chrono::NaiveDate,"), "{code}");
+    }
+
+    #[test]
+    fn replaced_schemas_are_not_generated() {
+        let code = generate(json!({
+            "TodoId": {"type": "integer", "format": "int32"},
+            "Todo": {
+                "type": "object",
+                "required": ["id"],
+                "properties": {"id": {"$ref": "#/components/schemas/TodoId"}},
+            },
+        }));
+
+        assert!(code.contains("pubid:crate::TodoId,"), "{code}");
+        assert!(!code.contains("pubstructTodoId"), "{code}");
+    }
+}
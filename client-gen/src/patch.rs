@@ -0,0 +1,231 @@
+//! Hand-maintained additions applied on top of the code generated by progenitor.
+
+use regex::{Captures, Regex};
+
+use crate::headers;
+
+/// Modules of `actix-todo` written by hand, declared and re-exported by the generated code.
+const MODULES: &str = "
+mod bulk;
+mod format;
+mod id;
+mod response;
+mod tls;
+mod trace;
+pub mod v1;
+
+pub use bulk::{Bulk, BulkResults, Progress};
+pub use format::Format;
+pub use id::TodoId;
+pub use response::{Operation, ResponseHeaders, ResponseHeadersExt};
+pub use tls::{Certificate, ClientOptions, Identity};
+";
+
+/// `Accept` header of the builders of operations responding with JSON.
+const JSON_ACCEPT: &str = "reqwest::header::ACCEPT,
+                    reqwest::header::HeaderValue::from_static(\"application/json\"),";
+
+/// Method of those builders selecting the representation of the response.
+const ACCEPT_METHOD: &str =
+    "        ///Representation the response is requested in, JSON unless set.
+        pub fn accept(mut self, format: super::Format) -> Self {
+            self.accept = format;
+            self
+        }
+
+";
+
+/// Separate the items of `code` formatted by rustfmt with blank lines, as `cargo progenitor`
+/// does.
+pub fn space_items(code: &str) -> String {
+    let item_end = Regex::new(r"(\n\s*\})(\n\s{0,8}[^} ])").unwrap();
+    item_end.replace_all(code, "$1\n$2").into_owned()
+}
+
+/// Apply the hand-maintained additions to the formatted client `code`, adding module
+/// `headers`.
+pub fn apply(code: &str, headers: &str) -> Result<String, String> {
+    let code = code.replacen(
+        "use reqwest::header::{HeaderMap, HeaderValue};\n",
+        &format!("use reqwest::header::{{HeaderMap, HeaderValue}};\n{MODULES}"),
+        1,
+    );
+
+    // Requests carry the trace context of the current span.
+    let code = code.replace(
+        "            let request = client",
+        "            let mut request = client",
+    );
+    let execute = Regex::new(
+        r"(\.build\(\)\?;\n)(            let result = client\.client\.execute\(request\)\.await;)",
+    )
+    .unwrap();
+    let code = execute.replace_all(
+        &code,
+        "${1}            super::trace::inject_context(&mut request);\n${2}",
+    );
+
+    let mut builders = code.split("    ///Builder for ");
+    let head = builders.next().unwrap_or_default();
+    let builders = builders
+        .map(|builder| with_send_raw(&with_accept(builder)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let code = [head.to_owned()]
+        .into_iter()
+        .chain(builders)
+        .collect::<Vec<_>>()
+        .join("    ///Builder for ");
+
+    let code = code.replacen(
+        "\npub mod prelude {",
+        &format!("\n{headers}\npub mod prelude {{"),
+        1,
+    );
+
+    Ok(elide_builder_lifetimes(&allow_type_lints(&code)))
+}
+
+/// Turn the formatted client `code` of an older version generated from `document` into a
+/// module of `actix-todo`, without the hand-maintained additions.
+pub fn older(code: &str, document: &str) -> String {
+    format!(
+        "//! Client of an older version of the todo api, generated from `{document}`.\n\n{}",
+        elide_builder_lifetimes(&allow_type_lints(code))
+    )
+}
+
+// The generated types clone `Copy` enums and implement `ToString` directly, which is left to
+// progenitor rather than allowed for the hand-written modules too.
+fn allow_type_lints(code: &str) -> String {
+    code.replacen(
+        "\npub mod types {",
+        "\n#[allow(clippy::clone_on_copy, clippy::to_string_trait_impl)]\npub mod types {",
+        1,
+    )
+}
+
+// The operation methods return builders borrowing the client without naming the lifetime,
+// which `mismatched_lifetime_syntaxes` rejects.
+fn elide_builder_lifetimes(code: &str) -> String {
+    Regex::new(r"(\(&self\) -> builder::\w+)([ ;])")
+        .unwrap()
+        .replace_all(code, "$1<'_>$2")
+        .into_owned()
+}
+
+/// Give the builder of an operation responding with JSON an `accept` method selecting the
+/// response representation.
+fn with_accept(builder: &str) -> String {
+    if !builder.contains(JSON_ACCEPT) {
+        return builder.to_owned();
+    }
+    let builder = builder.replacen(
+        "        client: &'a super::Client,\n",
+        "        client: &'a super::Client,\n        accept: super::Format,\n",
+        1,
+    );
+
+    // The constructor initializes `accept`, `send` destructures it.
+    let constructor = Regex::new(r"(let )?Self \{(\s*)client\b").unwrap();
+    let mut initialized = false;
+    let mut destructured = false;
+    let builder = constructor.replace_all(&builder, |captures: &Captures| {
+        let whitespace = &captures[2];
+        match captures.get(1) {
+            None if !initialized => {
+                initialized = true;
+                format!("Self {{{whitespace}client, accept: super::Format::Json")
+            }
+            Some(_) if !destructured => {
+                destructured = true;
+                format!("let Self {{{whitespace}client, accept")
+            }
+            _ => captures[0].to_owned(),
+        }
+    });
+
+    builder
+        .replacen(
+            "        ///Sends a ",
+            &format!("{ACCEPT_METHOD}        ///Sends a "),
+            1,
+        )
+        .replacen(
+            JSON_ACCEPT,
+            "reqwest::header::ACCEPT,\n                    accept.media_type(),",
+            1,
+        )
+        .replace(
+            "ResponseValue::from_response(response)",
+            "super::format::decode(response)",
+        )
+}
+
+/// Turn the `send` method of `builder` into a `send_raw` method returning successful responses
+/// undecoded, and add a `send` decoding those on top of it.
+fn with_send_raw(builder: &str) -> Result<String, String> {
+    let operation = Regex::new(r"^\[`\w+::(\w+)`\]").unwrap();
+    let (Some(operation), Some(start)) = (
+        operation.captures(builder),
+        builder.find("        ///Sends a "),
+    ) else {
+        return Ok(builder.to_owned());
+    };
+    let name = headers::pascal_case(&operation[1]);
+    let malformed = |what: &str| format!("send method of builder {name} {what}");
+
+    let end = builder[start..]
+        .find("\n        }\n")
+        .ok_or_else(|| malformed("does not end"))?
+        + start
+        + "\n        }\n".len();
+    let send = &builder[start..end];
+    let (summary, send) = send.split_at(send.find('\n').ok_or_else(|| malformed("is empty"))? + 1);
+
+    let signature =
+        Regex::new(r"(?s)pub async fn send\(\s*self,?\s*\)\s*->\s*(Result<.*?>)\s*\{").unwrap();
+    let return_type = &signature
+        .captures(send)
+        .ok_or_else(|| malformed("has no signature"))?[1];
+    let raw_return_type = Regex::new(r"(?s)^Result<\s*ResponseValue<.*?>,\s*Error<").unwrap();
+    if !raw_return_type.is_match(return_type) {
+        return Err(malformed("does not return a response value"));
+    }
+    let success = Regex::new(
+        r"(?m)^(\s+\S+ => )(?:Ok\(ResponseValue::\w+\(response\)\)|super::format::decode\(response\)\.await),$",
+    )
+    .unwrap();
+    let decoded = success
+        .find_iter(send)
+        .map(|arm| format!("{}\n", arm.as_str()))
+        .collect::<String>();
+    if decoded.is_empty() {
+        return Err(malformed("has no successful response"));
+    }
+
+    let raw = send.replacen("pub async fn send(", "pub async fn send_raw(", 1);
+    let raw = raw.replacen(
+        return_type,
+        &raw_return_type.replacen(return_type, 1, "Result<reqwest::Response, Error<"),
+        1,
+    );
+    let raw = success.replace_all(&raw, "${1}Ok(response),");
+
+    Ok(format!(
+        "{head}{summary}        ///\n        ///Headers declared by its responses, \
+         [`headers::{name}`](super::headers::{name}), are read with \
+         `typed_headers::<builder::{name}>()` of \
+         [`ResponseHeadersExt`](super::ResponseHeadersExt).
+        pub async fn send(self) -> {return_type} {{
+            let response = self.send_raw().await?;
+            match response.status().as_u16() {{
+{decoded}                _ => Err(Error::UnexpectedResponse(response)),
+            }}
+        }}
+
+{summary}        ///\n        ///Unlike `send` the body of a successful response is not \
+         decoded, error responses are.\n{raw}{tail}",
+        head = &builder[..start],
+        tail = &builder[end..],
+    ))
+}
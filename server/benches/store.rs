@@ -0,0 +1,79 @@
+//! Compares the todo store with the store it replaced, a single mutex over vectors of todo
+//! records, under concurrent mixed read/write workloads.
+//!
+//! Run with `cargo bench -p todo-actix --features bench`.
+
+use std::{hint::black_box, thread};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use todo_actix::bench::{BaselineStore, CurrentStore, Store};
+
+const TENANTS: usize = 4;
+const THREADS: usize = 4;
+const OPS_PER_THREAD: usize = 1_000;
+
+fn fill<S: Store + Default>(size: usize) -> S {
+    let store = S::default();
+    for id in 0..size {
+        store.create(id % TENANTS, id as i32);
+    }
+    store
+}
+
+/// Run `OPS_PER_THREAD` operations on each of `THREADS` threads, `read_percent` percent of
+/// them lookups and the rest updates, a fifth of which move the todo to the trash and back.
+fn run(store: &impl Store, size: usize, read_percent: u64) {
+    thread::scope(|scope| {
+        for thread in 0..THREADS {
+            scope.spawn(move || {
+                // Cheap deterministic pseudo random sequence per thread.
+                let mut state = 0x9e37_79b9_7f4a_7c15_u64 ^ thread as u64;
+                for _ in 0..OPS_PER_THREAD {
+                    state = state
+                        .wrapping_mul(6_364_136_223_846_793_005)
+                        .wrapping_add(1_442_695_040_888_963_407);
+                    let id = ((state >> 33) % size as u64) as usize;
+                    let tenant = id % TENANTS;
+                    let id = id as i32;
+                    match (state >> 16) % 100 {
+                        roll if roll < read_percent => {
+                            black_box(store.get(tenant, id));
+                        }
+                        roll if roll < read_percent + (100 - read_percent) / 5 => {
+                            if store.delete(tenant, id) {
+                                store.restore(tenant, id);
+                            }
+                        }
+                        _ => {
+                            black_box(store.check(tenant, id));
+                        }
+                    }
+                }
+            });
+        }
+    });
+}
+
+fn mixed_workloads(c: &mut Criterion) {
+    for (name, read_percent) in [("read_heavy", 90), ("balanced", 50), ("write_heavy", 10)] {
+        let mut group = c.benchmark_group(format!("mixed/{name}"));
+        group.throughput(Throughput::Elements((THREADS * OPS_PER_THREAD) as u64));
+
+        for size in [100, 1_000, 10_000] {
+            let baseline = fill::<BaselineStore>(size);
+            group.bench_with_input(BenchmarkId::new("baseline", size), &size, |b, &size| {
+                b.iter(|| run(&baseline, size, read_percent))
+            });
+
+            let current = fill::<CurrentStore>(size);
+            group.bench_with_input(BenchmarkId::new("current", size), &size, |b, &size| {
+                b.iter(|| run(&current, size, read_percent))
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, mixed_workloads);
+criterion_main!(benches);
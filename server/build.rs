@@ -0,0 +1,55 @@
+use std::{env, process::Command};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// Trimmed output of `git <args>`, `None` if git is unavailable or fails.
+fn git(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|output| output.trim().to_owned())
+}
+
+fn main() {
+    let git_sha = git(&["rev-parse", "--short", "HEAD"]).unwrap_or_else(|| String::from("unknown"));
+    let commit_time =
+        git(&["log", "-1", "--format=%cI", "HEAD"]).unwrap_or_else(|| String::from("unknown"));
+
+    // `SOURCE_DATE_EPOCH` pins the build time for reproducible builds.
+    let build_time = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| DateTime::from_timestamp(epoch.parse().ok()?, 0))
+        .unwrap_or_else(Utc::now)
+        .to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    println!("cargo:rustc-env=GIT_SHA={git_sha}");
+    println!("cargo:rustc-env=COMMIT_TIME={commit_time}");
+    println!("cargo:rustc-env=BUILD_TIME={build_time}");
+    // The build time has to be taken again whenever the server is rebuilt, not only when the
+    // commit changes, or it goes stale.
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    // The git values only change with the commit, so rebuild when HEAD moves, is switched to
+    // another branch or the branch is updated, wherever its ref is stored.
+    let head_ref = git(&["symbolic-ref", "-q", "HEAD"]);
+    for path in ["HEAD", "packed-refs"]
+        .into_iter()
+        .chain(head_ref.as_deref())
+    {
+        if let Some(path) = git(&["rev-parse", "--git-path", path]) {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+
+    // Compile the gRPC interface with protox, so building does not need protoc installed.
+    let descriptors = protox::compile(["proto/todo.proto"], ["proto"])
+        .unwrap_or_else(|error| panic!("failed to compile proto/todo.proto: {error}"));
+    tonic_build::configure()
+        .compile_fds(descriptors)
+        .unwrap_or_else(|error| panic!("failed to generate gRPC code: {error}"));
+    println!("cargo:rerun-if-changed=proto");
+}
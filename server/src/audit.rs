@@ -0,0 +1,497 @@
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{mpsc, Mutex, PoisonError},
+    thread,
+};
+
+use actix_web::{
+    get,
+    web::{Data, Query, ServiceConfig},
+    HttpResponse, Responder,
+};
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast::{self, error::RecvError};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    auth::{Principal, Tenant},
+    telemetry::RequestId,
+    todo::{ErrorResponse, Todo, TodoId},
+    RequireApiKey,
+};
+
+/// Number of entries a subscriber may fall behind before it misses entries.
+const CHANGES_CAPACITY: usize = 1024;
+
+pub(super) fn configure(audit: Data<AuditLog>) -> impl FnOnce(&mut ServiceConfig) {
+    |config: &mut ServiceConfig| {
+        config.app_data(audit).service(get_audit);
+    }
+}
+
+/// Kind of mutation recorded in the audit trail.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum Operation {
+    /// Todo was created.
+    Create,
+    /// Todo was updated.
+    Update,
+    /// Todo was moved to the trash.
+    Delete,
+    /// Todo was restored from the trash.
+    Restore,
+    /// Todo was reverted to an earlier revision.
+    Revert,
+    /// Todo was created or overwritten by an import.
+    Import,
+    /// Todo was removed from the trash for good after its retention expired.
+    Purge,
+}
+
+/// Recorded mutation of a single `Todo`.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub(super) struct AuditEntry {
+    /// Sequence number of the entry, increasing in the order entries were recorded.
+    #[schema(example = 1)]
+    pub(super) id: u64,
+    /// Time the mutation was made at.
+    pub(super) timestamp: DateTime<Utc>,
+    /// Kind of the mutation.
+    pub(super) operation: Operation,
+    /// Id of the mutated todo.
+    #[schema(example = 1)]
+    pub(super) todo_id: TodoId,
+    /// Tenant owning the mutated todo.
+    #[schema(value_type = String, example = "default")]
+    pub(super) tenant: Tenant,
+    /// Id of the api key the mutation was made with, `system` for mutations made by the
+    /// server itself. There is no anonymous actor, every mutation of the api requires an api
+    /// key.
+    #[schema(example = "default")]
+    pub(super) actor: String,
+    /// Id of the request the mutation was made in, missing for mutations made by the server
+    /// itself.
+    request_id: Option<String>,
+    /// Names of the `Todo` fields changed by the mutation.
+    #[schema(example = json!(["checked", "completed_at", "updated_at"]))]
+    changes: Vec<String>,
+    /// State of the todo before the mutation, missing when the todo was created.
+    pub(super) before: Option<Todo>,
+    /// State of the todo after the mutation, missing when the todo was removed.
+    pub(super) after: Option<Todo>,
+}
+
+/// Who made a mutation.
+pub(super) struct Actor {
+    key_id: String,
+    request_id: Option<String>,
+}
+
+impl Actor {
+    /// Caller of the request `request_id`.
+    pub(super) fn new(principal: &Principal, request_id: &RequestId) -> Self {
+        Self {
+            key_id: principal.key_id.clone(),
+            request_id: Some(request_id.to_string()),
+        }
+    }
+
+    /// The server itself, e.g. a background task.
+    pub(super) fn system() -> Self {
+        Self {
+            key_id: String::from("system"),
+            request_id: None,
+        }
+    }
+}
+
+/// Append-only audit trail of todo mutations.
+///
+/// The most recent entries are kept in memory to be queried, and every entry is appended to
+/// a JSON lines file by an [`AuditWriter`] when persistence is enabled. New entries are also
+/// broadcast to [subscribers][AuditLog::subscribe].
+pub(super) struct AuditLog {
+    state: Mutex<AuditState>,
+    changes: broadcast::Sender<AuditEntry>,
+}
+
+struct AuditState {
+    entries: VecDeque<AuditEntry>,
+    capacity: NonZeroUsize,
+    next_id: u64,
+    file: Option<AuditWriter>,
+}
+
+impl AuditLog {
+    /// Audit log keeping up to `capacity` entries in memory only.
+    pub(super) fn in_memory(capacity: NonZeroUsize) -> Self {
+        Self {
+            state: Mutex::new(AuditState {
+                entries: VecDeque::new(),
+                capacity,
+                next_id: 1,
+                file: None,
+            }),
+            changes: broadcast::channel(CHANGES_CAPACITY).0,
+        }
+    }
+
+    /// Audit log persisted to `file`, restoring the most recent entries from it.
+    pub(super) fn open(file: AuditFile, capacity: NonZeroUsize) -> io::Result<Self> {
+        let mut entries = VecDeque::new();
+        for entry in file.read()? {
+            if entries.len() == capacity.get() {
+                entries.pop_front();
+            }
+            entries.push_back(entry);
+        }
+        let next_id = entries.back().map_or(1, |entry: &AuditEntry| entry.id + 1);
+
+        Ok(Self {
+            state: Mutex::new(AuditState {
+                entries,
+                capacity,
+                next_id,
+                file: Some(AuditWriter::spawn(file)?),
+            }),
+            changes: broadcast::channel(CHANGES_CAPACITY).0,
+        })
+    }
+
+    /// Record mutation of a todo of `tenant` from `before` to `after`.
+    pub(super) fn record(
+        &self,
+        actor: &Actor,
+        tenant: &Tenant,
+        operation: Operation,
+        before: Option<&Todo>,
+        after: Option<&Todo>,
+    ) {
+        let Some(todo_id) = after.or(before).map(Todo::id) else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let entry = AuditEntry {
+            id: state.next_id,
+            timestamp: Utc::now(),
+            operation,
+            todo_id,
+            tenant: tenant.clone(),
+            actor: actor.key_id.clone(),
+            request_id: actor.request_id.clone(),
+            changes: changes(before, after),
+            before: before.cloned(),
+            after: after.cloned(),
+        };
+        state.next_id += 1;
+
+        if let Some(file) = &state.file {
+            file.append(entry.clone());
+        }
+        if state.entries.len() == state.capacity.get() {
+            state.entries.pop_front();
+        }
+        // Sending only fails when nobody is subscribed.
+        let _ = self.changes.send(entry.clone());
+        state.entries.push_back(entry);
+    }
+
+    /// Stop persisting entries, waiting until the recorded ones are written to the audit file.
+    pub(super) fn close(&self) {
+        let file = self
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .file
+            .take();
+        if let Some(file) = file {
+            file.close();
+        }
+    }
+
+    /// Receive every entry recorded from now on, of all tenants.
+    pub(super) fn subscribe(&self) -> broadcast::Receiver<AuditEntry> {
+        self.changes.subscribe()
+    }
+
+    /// Stream of the entries of `tenant` recorded from now on.
+    ///
+    /// A subscriber falling more than [`CHANGES_CAPACITY`] entries behind misses entries, the
+    /// stream then ends with the number of missed entries.
+    pub(super) fn changes(
+        &self,
+        tenant: Tenant,
+    ) -> impl Stream<Item = Result<AuditEntry, u64>> + Send + 'static {
+        futures::stream::unfold(Some(self.subscribe()), move |changes| {
+            let tenant = tenant.clone();
+            async move {
+                let mut changes = changes?;
+                loop {
+                    match changes.recv().await {
+                        Ok(entry) if entry.tenant == tenant => {
+                            return Some((Ok(entry), Some(changes)))
+                        }
+                        Ok(_) => continue,
+                        Err(RecvError::Lagged(missed)) => return Some((Err(missed), None)),
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Names of the fields differing between `before` and `after`.
+fn changes(before: Option<&Todo>, after: Option<&Todo>) -> Vec<String> {
+    let fields = |todo: Option<&Todo>| match todo.map(serde_json::to_value) {
+        Some(Ok(Value::Object(fields))) => fields,
+        _ => serde_json::Map::new(),
+    };
+    let (before, after) = (fields(before), fields(after));
+
+    before
+        .keys()
+        .chain(after.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|field| before.get(*field) != after.get(*field))
+        .cloned()
+        .collect()
+}
+
+/// JSON lines file audit entries are appended to.
+///
+/// When appending an entry would grow the file over `max_bytes` the file is rotated:
+/// `<path>` is renamed to `<path>.1`, `<path>.1` to `<path>.2` and so on, dropping the
+/// oldest file so at most `max_files` rotated files are kept.
+pub(super) struct AuditFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    len: u64,
+}
+
+impl AuditFile {
+    pub(super) fn open(path: PathBuf, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files,
+            file,
+            len,
+        })
+    }
+
+    /// Entries of the rotated files and the current file, oldest first.
+    fn read(&self) -> io::Result<Vec<AuditEntry>> {
+        let mut entries = Vec::new();
+        for path in (1..=self.max_files)
+            .rev()
+            .map(|index| rotated_path(&self.path, index))
+            .chain([self.path.clone()])
+        {
+            let file = match File::open(&path) {
+                Ok(file) => BufReader::new(file),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(error),
+            };
+            for (index, line) in file.lines().enumerate() {
+                match serde_json::from_str(&line?) {
+                    Ok(entry) => entries.push(entry),
+                    Err(error) => tracing::warn!(
+                        path = %path.display(),
+                        line = index + 1,
+                        %error,
+                        "skipping malformed audit entry"
+                    ),
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn append(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry).map_err(io::Error::other)?;
+        line.push(b'\n');
+
+        if self.len > 0 && self.len + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(&line)?;
+        self.len += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.max_files > 0 {
+            for index in (1..self.max_files).rev() {
+                match fs::rename(
+                    rotated_path(&self.path, index),
+                    rotated_path(&self.path, index + 1),
+                ) {
+                    Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+                    _ => {}
+                }
+            }
+            fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        }
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+/// Appends entries to an [`AuditFile`] on a thread of its own, so recording an entry never
+/// waits for file I/O, e.g. while the todo store is locked.
+struct AuditWriter {
+    entries: mpsc::Sender<AuditEntry>,
+    thread: thread::JoinHandle<()>,
+}
+
+impl AuditWriter {
+    fn spawn(mut file: AuditFile) -> io::Result<Self> {
+        let (entries, received) = mpsc::channel::<AuditEntry>();
+        let thread = thread::Builder::new()
+            .name(String::from("audit-writer"))
+            .spawn(move || {
+                for entry in received {
+                    if let Err(error) = file.append(&entry) {
+                        tracing::error!(path = %file.path.display(), %error, "failed to write audit entry");
+                    }
+                }
+            })?;
+
+        Ok(Self { entries, thread })
+    }
+
+    /// Queue `entry` to be appended to the file.
+    fn append(&self, entry: AuditEntry) {
+        // Sending only fails when the thread is gone, which is reported when closing.
+        let _ = self.entries.send(entry);
+    }
+
+    /// Write the queued entries and stop the thread.
+    fn close(self) {
+        drop(self.entries);
+        if self.thread.join().is_err() {
+            tracing::error!("audit writer panicked, entries may be missing from the audit file");
+        }
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_owned();
+    file_name.push(format!(".{index}"));
+    path.with_file_name(file_name)
+}
+
+/// Audit trail query
+#[derive(Deserialize, Debug, IntoParams)]
+pub(super) struct AuditQuery {
+    /// Only entries of this operation.
+    operation: Option<Operation>,
+    /// Only entries of the todo with this id.
+    todo_id: Option<TodoId>,
+    /// Only entries made by this actor.
+    actor: Option<String>,
+    /// Only entries recorded at or after this time.
+    since: Option<DateTime<Utc>>,
+    /// Only entries recorded before this time.
+    until: Option<DateTime<Utc>>,
+    /// Number of matching entries to skip, defaults to 0.
+    offset: Option<usize>,
+    /// Maximum number of entries to return, defaults to 100 and is capped at 1000.
+    limit: Option<usize>,
+}
+
+/// Page of audit entries.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub(super) struct AuditPage {
+    /// Number of entries matching the query across all pages.
+    total: usize,
+    /// Matching entries of the requested page, oldest first.
+    entries: Vec<AuditEntry>,
+}
+
+/// Get audit trail.
+///
+/// This endpoint needs `api_key` authentication in order to call. Api key can be found from README.md.
+///
+/// List recorded mutations of the todos of the caller's tenant, oldest first. Entries can be
+/// filtered by operation, todo id, actor and time range and are paged with `offset` and
+/// `limit`. Only the most recent entries are kept in memory and can be listed, older entries
+/// are only found from the audit file.
+///
+/// One could call the api endpoint with following curl.
+/// ```text
+/// curl 'localhost:8080/audit?todo_id=1&limit=10' -H 'todo_apikey: utoipa-rocks'
+/// ```
+#[utoipa::path(
+    tag = "audit",
+    params(
+        AuditQuery
+    ),
+    responses(
+        (status = 200, description = "Page of matching audit entries", body = AuditPage),
+        (status = 401, description = "Unauthorized to read the audit trail", body = ErrorResponse, example = json!(ErrorResponse::Unauthorized(String::from("missing api key"))))
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+#[get("/audit", wrap = "RequireApiKey")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn get_audit(
+    query: Query<AuditQuery>,
+    principal: Principal,
+    audit: Data<AuditLog>,
+) -> impl Responder {
+    let state = audit.state.lock().unwrap_or_else(PoisonError::into_inner);
+    let matching = state
+        .entries
+        .iter()
+        .filter(|entry| entry.tenant == principal.tenant)
+        .filter(|entry| {
+            query
+                .operation
+                .is_none_or(|operation| entry.operation == operation)
+        })
+        .filter(|entry| query.todo_id.is_none_or(|todo_id| entry.todo_id == todo_id))
+        .filter(|entry| {
+            query
+                .actor
+                .as_ref()
+                .is_none_or(|actor| &entry.actor == actor)
+        })
+        .filter(|entry| query.since.is_none_or(|since| entry.timestamp >= since))
+        .filter(|entry| query.until.is_none_or(|until| entry.timestamp < until))
+        .collect::<Vec<_>>();
+
+    HttpResponse::Ok().json(AuditPage {
+        total: matching.len(),
+        entries: matching
+            .into_iter()
+            .skip(query.offset.unwrap_or_default())
+            .take(query.limit.unwrap_or(100).min(1000))
+            .cloned()
+            .collect(),
+    })
+}
@@ -0,0 +1,172 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    future::{self, Ready},
+    str::FromStr,
+};
+
+use actix_web::{
+    dev::Payload, error::InternalError, web::Data, FromRequest, HttpRequest, HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{todo::ErrorResponse, API_KEY_NAME};
+
+/// Owner of a todo list. Todos of one tenant are invisible to every other tenant.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(super) struct Tenant(String);
+
+impl Tenant {
+    /// Tenant of the default api key.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub(super) fn default_key() -> Self {
+        Self(String::from("default"))
+    }
+
+    /// Tenant called `name`, tenants are otherwise only created from the configured api keys.
+    #[cfg(feature = "bench")]
+    pub(super) fn named(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+}
+
+impl fmt::Display for Tenant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Authenticated caller of the api.
+#[derive(Clone, Debug)]
+pub(super) struct Principal {
+    /// Id of the api key or client certificate the caller authenticated with.
+    pub(super) key_id: String,
+    /// Tenant the caller acts on behalf of.
+    pub(super) tenant: Tenant,
+}
+
+/// Authenticate the caller of `req` by the `todo_apikey` header or, when the request has no
+/// api key, by the client certificate of its connection.
+///
+/// Returns the reason when the caller could not be authenticated.
+pub(super) fn authenticate(req: &HttpRequest) -> Result<Principal, &'static str> {
+    match req.headers().get(API_KEY_NAME) {
+        Some(key) => req
+            .app_data::<Data<ApiKeys>>()
+            .and_then(|api_keys| api_keys.authenticate(key.as_bytes()))
+            .cloned()
+            .ok_or("incorrect api key"),
+        None => req
+            .conn_data::<CertificatePrincipal>()
+            .map(|principal| principal.0.clone())
+            .ok_or("missing api key"),
+    }
+}
+
+/// Resolves the principal of the request from `todo_apikey` header or client certificate.
+///
+/// Requests with a missing or incorrect api key are rejected with 401, every todo list
+/// belongs to the tenant of an api key.
+impl FromRequest for Principal {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        future::ready(authenticate(req).map_err(|reason| {
+            InternalError::from_response(
+                reason,
+                HttpResponse::Unauthorized()
+                    .json(ErrorResponse::Unauthorized(String::from(reason))),
+            )
+            .into()
+        }))
+    }
+}
+
+/// Principal of the verified client certificate of a connection.
+#[derive(Clone, Debug)]
+pub(super) struct CertificatePrincipal(pub(super) Principal);
+
+/// Known api keys and the principals they authenticate.
+#[derive(Clone, Debug)]
+pub(super) struct ApiKeys(HashMap<Vec<u8>, Principal>);
+
+impl ApiKeys {
+    /// Principal authenticated by `key`, `None` if the key is unknown.
+    pub(super) fn authenticate(&self, key: &[u8]) -> Option<&Principal> {
+        self.0.get(key)
+    }
+}
+
+impl Default for ApiKeys {
+    fn default() -> Self {
+        "default:default:utoipa-rocks".parse().unwrap()
+    }
+}
+
+/// Parse comma separated list of `<key id>:<tenant>:<key>` entries.
+impl FromStr for ApiKeys {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_principals(s, "api key", "key").map(|entries| {
+            Self(
+                entries
+                    .into_iter()
+                    .map(|(key, principal)| (key.into_bytes(), principal))
+                    .collect(),
+            )
+        })
+    }
+}
+
+/// Client certificate subject common names and the principals they authenticate.
+#[derive(Clone, Debug, Default)]
+pub(super) struct ClientSubjects(HashMap<String, Principal>);
+
+impl ClientSubjects {
+    /// Principal authenticated by a certificate of `common_name`, `None` if it is unknown.
+    pub(super) fn authenticate(&self, common_name: &str) -> Option<&Principal> {
+        self.0.get(common_name)
+    }
+}
+
+/// Parse comma separated list of `<key id>:<tenant>:<common name>` entries.
+impl FromStr for ClientSubjects {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_principals(s, "client subject", "common name")
+            .map(|entries| Self(entries.into_iter().collect()))
+    }
+}
+
+/// Split comma separated `<key id>:<tenant>:<credential>` entries into the credentials and
+/// the principals they authenticate.
+fn parse_principals(
+    s: &str,
+    entry_name: &str,
+    credential_name: &str,
+) -> Result<Vec<(String, Principal)>, String> {
+    s.split(',')
+        .map(
+            |entry| match entry.trim().splitn(3, ':').collect::<Vec<_>>()[..] {
+                [key_id, tenant, credential]
+                    if !key_id.is_empty() && !tenant.is_empty() && !credential.is_empty() =>
+                {
+                    Ok((
+                        credential.to_owned(),
+                        Principal {
+                            key_id: key_id.to_owned(),
+                            tenant: Tenant(tenant.to_owned()),
+                        },
+                    ))
+                }
+                _ => Err(format!(
+                    "expected {entry_name} entry `<key id>:<tenant>:<{credential_name}>`, got `{entry}`"
+                )),
+            },
+        )
+        .collect()
+}
@@ -0,0 +1,251 @@
+//! Entry points of the store benchmark in `benches/`, which cannot reach the private modules.
+//!
+//! [`TodoStore`] is compared with [`BaselineStore`], the todo store as it was before it was
+//! redesigned around [`PartitionedStore`](crate::store::PartitionedStore).
+
+use std::{collections::HashMap, num::NonZeroUsize, sync::Mutex};
+
+use chrono::Utc;
+use serde_json::json;
+
+use crate::{
+    audit::{Actor, AuditLog, Operation},
+    auth::Tenant,
+    todo::{Todo, TodoId, TodoRecord, TodoStore, TodoUpdateRequest},
+};
+
+/// Operations the handlers perform on a todo store, on todo `id` of tenant number `tenant`.
+pub trait Store: Sync {
+    /// Create an unchecked todo `id`, `false` if the id is taken.
+    fn create(&self, tenant: usize, id: i32) -> bool;
+    /// Value of todo `id`, `None` if it is not found.
+    fn get(&self, tenant: usize, id: i32) -> Option<String>;
+    /// Mark todo `id` done, `false` if it is not found.
+    fn check(&self, tenant: usize, id: i32) -> bool;
+    /// Move todo `id` to the trash, `false` if it is not found.
+    fn delete(&self, tenant: usize, id: i32) -> bool;
+    /// Move todo `id` out of the trash, `false` if it is not in the trash.
+    fn restore(&self, tenant: usize, id: i32) -> bool;
+}
+
+fn tenant(tenant: usize) -> Tenant {
+    Tenant::named(format!("tenant-{tenant}"))
+}
+
+fn todo(id: i32) -> Todo {
+    serde_json::from_value(json!({"id": id, "value": format!("todo {id}"), "checked": false}))
+        .expect("todo is valid")
+}
+
+fn check() -> TodoUpdateRequest {
+    TodoUpdateRequest {
+        value: None,
+        checked: Some(true),
+        priority: None,
+        tags: None,
+        due_at: None,
+    }
+}
+
+/// Current todo store, recording changes to an audit log like the handlers do.
+pub struct CurrentStore {
+    store: TodoStore,
+    audit: AuditLog,
+}
+
+impl Default for CurrentStore {
+    fn default() -> Self {
+        Self {
+            store: TodoStore::default(),
+            audit: AuditLog::in_memory(NonZeroUsize::MIN),
+        }
+    }
+}
+
+impl Store for CurrentStore {
+    fn create(&self, tenant: usize, id: i32) -> bool {
+        self.store
+            .create(
+                &self::tenant(tenant),
+                todo(id),
+                &self.audit,
+                &Actor::system(),
+            )
+            .is_ok()
+    }
+
+    fn get(&self, tenant: usize, id: i32) -> Option<String> {
+        self.store
+            .get(&self::tenant(tenant), TodoId(id), None)
+            .ok()
+            .map(|todo| todo.value)
+    }
+
+    fn check(&self, tenant: usize, id: i32) -> bool {
+        self.store
+            .update(
+                &self::tenant(tenant),
+                TodoId(id),
+                check(),
+                &self.audit,
+                &Actor::system(),
+            )
+            .is_ok()
+    }
+
+    fn delete(&self, tenant: usize, id: i32) -> bool {
+        self.store
+            .delete(
+                &self::tenant(tenant),
+                TodoId(id),
+                &self.audit,
+                &Actor::system(),
+            )
+            .is_ok()
+    }
+
+    fn restore(&self, tenant: usize, id: i32) -> bool {
+        self.store
+            .restore(
+                &self::tenant(tenant),
+                TodoId(id),
+                &self.audit,
+                &Actor::system(),
+            )
+            .is_ok()
+    }
+}
+
+/// Todo store before the redesign: a single mutex over the todo records of every tenant,
+/// scanned linearly by the handlers.
+///
+/// The operations are the bodies of the handlers of the time, with the responses left out.
+pub struct BaselineStore {
+    todos: Mutex<HashMap<Tenant, Vec<TodoRecord>>>,
+    audit: AuditLog,
+}
+
+impl Default for BaselineStore {
+    fn default() -> Self {
+        Self {
+            todos: Mutex::default(),
+            audit: AuditLog::in_memory(NonZeroUsize::MIN),
+        }
+    }
+}
+
+impl Store for BaselineStore {
+    fn create(&self, tenant: usize, id: i32) -> bool {
+        let tenant = self::tenant(tenant);
+        let mut lists = self.todos.lock().unwrap();
+        let records = lists.entry(tenant.clone()).or_default();
+        let now = Utc::now();
+        let todo = &todo(id).created(now);
+
+        if records
+            .iter()
+            .map(TodoRecord::current)
+            .any(|existing| existing.id == todo.id)
+        {
+            return false;
+        }
+        records.push(TodoRecord::new(todo.clone(), now));
+        self.audit.record(
+            &Actor::system(),
+            &tenant,
+            Operation::Create,
+            None,
+            Some(todo),
+        );
+        true
+    }
+
+    fn get(&self, tenant: usize, id: i32) -> Option<String> {
+        let lists = self.todos.lock().unwrap();
+        let id = TodoId(id);
+
+        lists
+            .get(&self::tenant(tenant))
+            .into_iter()
+            .flatten()
+            .map(TodoRecord::current)
+            .find(|todo| todo.id == id && !todo.is_deleted())
+            .map(|todo| todo.value.clone())
+    }
+
+    fn check(&self, tenant: usize, id: i32) -> bool {
+        let tenant = self::tenant(tenant);
+        let mut lists = self.todos.lock().unwrap();
+        let id = TodoId(id);
+
+        lists
+            .get_mut(&tenant)
+            .into_iter()
+            .flatten()
+            .find(|record| record.current().id == id && !record.current().is_deleted())
+            .map(|record| {
+                let now = Utc::now();
+                let (before, after) =
+                    record.change(now, |existing_todo| existing_todo.update(check(), now));
+                self.audit.record(
+                    &Actor::system(),
+                    &tenant,
+                    Operation::Update,
+                    Some(&before),
+                    Some(after),
+                );
+            })
+            .is_some()
+    }
+
+    fn delete(&self, tenant: usize, id: i32) -> bool {
+        let tenant = self::tenant(tenant);
+        let mut lists = self.todos.lock().unwrap();
+        let id = TodoId(id);
+
+        lists
+            .get_mut(&tenant)
+            .into_iter()
+            .flatten()
+            .find(|record| record.current().id == id && !record.current().is_deleted())
+            .map(|record| {
+                let now = Utc::now();
+                let (before, after) = record.change(now, |todo| todo.deleted_at = Some(now));
+                self.audit.record(
+                    &Actor::system(),
+                    &tenant,
+                    Operation::Delete,
+                    Some(&before),
+                    Some(after),
+                );
+            })
+            .is_some()
+    }
+
+    fn restore(&self, tenant: usize, id: i32) -> bool {
+        let tenant = self::tenant(tenant);
+        let mut lists = self.todos.lock().unwrap();
+        let id = TodoId(id);
+
+        lists
+            .get_mut(&tenant)
+            .into_iter()
+            .flatten()
+            .find(|record| record.current().id == id && record.current().is_deleted())
+            .map(|record| {
+                let now = Utc::now();
+                let (before, after) = record.change(now, |todo| {
+                    todo.deleted_at = None;
+                    todo.updated_at = Some(now);
+                });
+                self.audit.record(
+                    &Actor::system(),
+                    &tenant,
+                    Operation::Restore,
+                    Some(&before),
+                    Some(after),
+                );
+            })
+            .is_some()
+    }
+}
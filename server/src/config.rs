@@ -0,0 +1,275 @@
+use std::{
+    env,
+    num::{NonZeroU64, NonZeroUsize},
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{
+    auth::{ApiKeys, ClientSubjects},
+    rate_limit::{Quota, RouteQuotas, TrustedProxies},
+    security::{self, AllowedOrigins, List},
+    tls::ClientAuth,
+    version::Deprecation,
+};
+
+/// Runtime configuration of the server.
+///
+/// Every setting is read from a `TODO_*` environment variable and falls back to a default
+/// suitable for local development when the variable is not set.
+#[derive(Clone, Debug)]
+pub(super) struct Config {
+    /// Accepted api keys as comma separated `<key id>:<tenant>:<key>` entries,
+    /// `TODO_API_KEYS`.
+    pub(super) api_keys: ApiKeys,
+    /// Format of log output, `TODO_LOG_FORMAT=text|json`.
+    pub(super) log_format: LogFormat,
+    /// Where finished spans are exported to, `TODO_OTEL_EXPORTER=none|otlp|stdout|file`.
+    pub(super) trace_exporter: TraceExporter,
+    /// OTLP gRPC collector endpoint, `TODO_OTEL_ENDPOINT`.
+    pub(super) otlp_endpoint: String,
+    /// File spans are appended to by the `file` exporter, `TODO_OTEL_FILE`.
+    pub(super) trace_file: PathBuf,
+    /// File the todo store is restored from on startup and saved to on shutdown,
+    /// `TODO_SNAPSHOT_PATH`. Snapshots are disabled when not set.
+    pub(super) snapshot_path: Option<PathBuf>,
+    /// Encoding of the snapshot file, `TODO_SNAPSHOT_FORMAT=json|msgpack`.
+    pub(super) snapshot_format: SnapshotFormat,
+    /// Interval of periodic snapshots in seconds, at least 1, `TODO_SNAPSHOT_INTERVAL`.
+    /// Snapshots are only taken on shutdown when not set.
+    pub(super) snapshot_interval: Option<Duration>,
+    /// Time in seconds deleted todos are kept in the trash before they are purged,
+    /// `TODO_TRASH_RETENTION`.
+    pub(super) trash_retention: Duration,
+    /// Interval in seconds of purging expired todos from the trash, at least 1,
+    /// `TODO_TRASH_PURGE_INTERVAL`.
+    pub(super) trash_purge_interval: Duration,
+    /// JSON lines file the audit trail is appended to, `TODO_AUDIT_PATH`. The audit trail is
+    /// only kept in memory when not set.
+    pub(super) audit_path: Option<PathBuf>,
+    /// Size in bytes the audit file is rotated at, `TODO_AUDIT_MAX_BYTES`.
+    pub(super) audit_max_bytes: u64,
+    /// Number of rotated audit files kept, `TODO_AUDIT_MAX_FILES`.
+    pub(super) audit_max_files: usize,
+    /// Number of most recent audit entries kept in memory and listed by `GET /audit`,
+    /// `TODO_AUDIT_CAPACITY`.
+    pub(super) audit_capacity: NonZeroUsize,
+    /// Default quota of every route as `<requests>/<seconds>` or `off`, `TODO_RATE_LIMIT`.
+    pub(super) rate_limit: Quota,
+    /// Quotas of individual routes as comma separated `<METHOD> <route>=<quota>` entries,
+    /// `TODO_RATE_LIMIT_ROUTES`.
+    pub(super) route_rate_limits: RouteQuotas,
+    /// Addresses of reverse proxies whose `X-Forwarded-For` header names the client of the
+    /// rate limit, `TODO_TRUSTED_PROXIES`.
+    pub(super) trusted_proxies: TrustedProxies,
+    /// PEM certificate chain served over TLS, `TODO_TLS_CERT`. The server only speaks
+    /// plaintext HTTP unless both the certificate and the key are set.
+    pub(super) tls_cert: Option<PathBuf>,
+    /// PEM private key of the TLS certificate, `TODO_TLS_KEY`.
+    pub(super) tls_key: Option<PathBuf>,
+    /// Interval in seconds of checking the certificate and key files for changes, at least 1,
+    /// `TODO_TLS_RELOAD_INTERVAL`.
+    pub(super) tls_reload_interval: Duration,
+    /// PEM bundle of the certificate authorities client certificates are verified against,
+    /// `TODO_TLS_CLIENT_CA`. Client certificates are not requested when not set.
+    pub(super) tls_client_ca: Option<PathBuf>,
+    /// Whether clients have to present a certificate, `TODO_TLS_CLIENT_AUTH=optional|required`.
+    pub(super) tls_client_auth: ClientAuth,
+    /// Principals of client certificates as comma separated `<key id>:<tenant>:<common name>`
+    /// entries, `TODO_TLS_CLIENT_SUBJECTS`.
+    pub(super) client_subjects: ClientSubjects,
+    /// Origins allowed to make cross-origin requests as `*` or comma separated list,
+    /// `TODO_CORS_ALLOWED_ORIGINS`. Cross-origin requests are not allowed when not set.
+    pub(super) cors_allowed_origins: AllowedOrigins,
+    /// Comma separated methods cross-origin requests may use, `TODO_CORS_ALLOWED_METHODS`.
+    pub(super) cors_allowed_methods: List,
+    /// Comma separated headers cross-origin requests may send, `TODO_CORS_ALLOWED_HEADERS`.
+    pub(super) cors_allowed_headers: List,
+    /// Time in seconds browsers may cache preflight responses, `TODO_CORS_MAX_AGE`.
+    pub(super) cors_max_age: usize,
+    /// Maximum size in bytes of JSON request bodies, `TODO_JSON_LIMIT`.
+    pub(super) json_limit: usize,
+    /// Maximum size in bytes of todo import request bodies, `TODO_IMPORT_LIMIT`.
+    pub(super) import_limit: usize,
+    /// Maximum nesting depth of GraphQL operations, `TODO_GRAPHQL_MAX_DEPTH`.
+    pub(super) graphql_max_depth: usize,
+    /// Maximum complexity of GraphQL operations, `TODO_GRAPHQL_MAX_COMPLEXITY`.
+    pub(super) graphql_max_complexity: usize,
+    /// Maximum number of operations of a GraphQL batch, `TODO_GRAPHQL_MAX_BATCH`.
+    pub(super) graphql_max_batch: usize,
+    /// Port the gRPC server listens on, `TODO_GRPC_PORT`.
+    pub(super) grpc_port: u16,
+    /// Time in seconds in-flight requests are given to finish on shutdown,
+    /// `TODO_SHUTDOWN_TIMEOUT`.
+    pub(super) shutdown_timeout: u64,
+    /// Deprecation of the `v1` todo routes announced on their responses, from the RFC 3339
+    /// times `TODO_V1_DEPRECATED_AT` and `TODO_V1_SUNSET_AT`.
+    pub(super) v1_deprecation: Deprecation,
+}
+
+impl Config {
+    pub(super) fn from_env() -> Self {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Configuration of the tests with the variables of `vars`, given as name and value, and
+    /// the defaults for the rest regardless of the environment the tests run in.
+    #[cfg(test)]
+    pub(super) fn with_vars(vars: &[(&str, &str)]) -> Self {
+        Self::from_vars(|name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| String::from(*value))
+        })
+    }
+
+    /// Configuration from the variables `lookup` finds by name.
+    fn from_vars(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        Self {
+            api_keys: var(&lookup, "TODO_API_KEYS").unwrap_or_default(),
+            log_format: var(&lookup, "TODO_LOG_FORMAT").unwrap_or_default(),
+            trace_exporter: var(&lookup, "TODO_OTEL_EXPORTER").unwrap_or_default(),
+            otlp_endpoint: var(&lookup, "TODO_OTEL_ENDPOINT")
+                .unwrap_or_else(|| String::from("http://localhost:4317")),
+            trace_file: var(&lookup, "TODO_OTEL_FILE")
+                .unwrap_or_else(|| PathBuf::from("traces.jsonl")),
+            snapshot_path: var(&lookup, "TODO_SNAPSHOT_PATH"),
+            snapshot_format: var(&lookup, "TODO_SNAPSHOT_FORMAT").unwrap_or_default(),
+            snapshot_interval: seconds(&lookup, "TODO_SNAPSHOT_INTERVAL"),
+            trash_retention: Duration::from_secs(
+                var(&lookup, "TODO_TRASH_RETENTION").unwrap_or(7 * 24 * 60 * 60),
+            ),
+            trash_purge_interval: seconds(&lookup, "TODO_TRASH_PURGE_INTERVAL")
+                .unwrap_or(Duration::from_secs(60)),
+            audit_path: var(&lookup, "TODO_AUDIT_PATH"),
+            audit_max_bytes: var(&lookup, "TODO_AUDIT_MAX_BYTES").unwrap_or(10 * 1024 * 1024),
+            audit_max_files: var(&lookup, "TODO_AUDIT_MAX_FILES").unwrap_or(5),
+            audit_capacity: var(&lookup, "TODO_AUDIT_CAPACITY")
+                .unwrap_or(NonZeroUsize::new(10_000).unwrap()),
+            rate_limit: var(&lookup, "TODO_RATE_LIMIT").unwrap_or(Quota::Limited {
+                requests: 1000,
+                period: Duration::from_secs(60),
+            }),
+            route_rate_limits: var(&lookup, "TODO_RATE_LIMIT_ROUTES").unwrap_or_default(),
+            trusted_proxies: var(&lookup, "TODO_TRUSTED_PROXIES").unwrap_or_default(),
+            tls_cert: var(&lookup, "TODO_TLS_CERT"),
+            tls_key: var(&lookup, "TODO_TLS_KEY"),
+            tls_reload_interval: seconds(&lookup, "TODO_TLS_RELOAD_INTERVAL")
+                .unwrap_or(Duration::from_secs(60)),
+            tls_client_ca: var(&lookup, "TODO_TLS_CLIENT_CA"),
+            tls_client_auth: var(&lookup, "TODO_TLS_CLIENT_AUTH").unwrap_or_default(),
+            client_subjects: var(&lookup, "TODO_TLS_CLIENT_SUBJECTS").unwrap_or_default(),
+            cors_allowed_origins: var(&lookup, "TODO_CORS_ALLOWED_ORIGINS").unwrap_or_default(),
+            cors_allowed_methods: var(&lookup, "TODO_CORS_ALLOWED_METHODS")
+                .unwrap_or_else(security::default_cors_methods),
+            cors_allowed_headers: var(&lookup, "TODO_CORS_ALLOWED_HEADERS")
+                .unwrap_or_else(security::default_cors_headers),
+            cors_max_age: var(&lookup, "TODO_CORS_MAX_AGE").unwrap_or(3600),
+            json_limit: var(&lookup, "TODO_JSON_LIMIT").unwrap_or(64 * 1024),
+            import_limit: var(&lookup, "TODO_IMPORT_LIMIT").unwrap_or(10 * 1024 * 1024),
+            graphql_max_depth: var(&lookup, "TODO_GRAPHQL_MAX_DEPTH").unwrap_or(16),
+            graphql_max_complexity: var(&lookup, "TODO_GRAPHQL_MAX_COMPLEXITY").unwrap_or(300),
+            graphql_max_batch: var(&lookup, "TODO_GRAPHQL_MAX_BATCH").unwrap_or(10),
+            grpc_port: var(&lookup, "TODO_GRPC_PORT").unwrap_or(50051),
+            shutdown_timeout: var(&lookup, "TODO_SHUTDOWN_TIMEOUT").unwrap_or(30),
+            v1_deprecation: Deprecation {
+                deprecated_at: var(&lookup, "TODO_V1_DEPRECATED_AT")
+                    .unwrap_or(Deprecation::V1.deprecated_at),
+                sunset_at: var(&lookup, "TODO_V1_SUNSET_AT").unwrap_or(Deprecation::V1.sunset_at),
+            },
+        }
+    }
+}
+
+/// Output format of the log lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum LogFormat {
+    /// Human readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(format!("unknown log format: {other}")),
+        }
+    }
+}
+
+/// Destination of exported OpenTelemetry spans.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum TraceExporter {
+    /// Spans are not exported.
+    #[default]
+    None,
+    /// Spans are sent to an OTLP collector over gRPC.
+    Otlp,
+    /// Spans are written to stdout as JSON lines.
+    Stdout,
+    /// Spans are appended to a file as JSON lines.
+    File,
+}
+
+impl FromStr for TraceExporter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "otlp" => Ok(Self::Otlp),
+            "stdout" => Ok(Self::Stdout),
+            "file" => Ok(Self::File),
+            other => Err(format!("unknown trace exporter: {other}")),
+        }
+    }
+}
+
+/// Encoding of store snapshots.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum SnapshotFormat {
+    /// Human readable JSON.
+    #[default]
+    Json,
+    /// Compact binary MessagePack.
+    MessagePack,
+}
+
+impl FromStr for SnapshotFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "msgpack" => Ok(Self::MessagePack),
+            other => Err(format!("unknown snapshot format: {other}")),
+        }
+    }
+}
+
+/// Look up and parse variable `name`, panicking with a descriptive message when it is set but
+/// cannot be parsed.
+fn var<T>(lookup: &impl Fn(&str) -> Option<String>, name: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    lookup(name).map(|value| {
+        value
+            .parse()
+            .unwrap_or_else(|error| panic!("invalid value for {name}: {error}"))
+    })
+}
+
+/// Look up variable `name` as a non-zero number of seconds, panicking like [`var`] when it is
+/// zero as intervals of zero seconds cannot be timed.
+fn seconds(lookup: &impl Fn(&str) -> Option<String>, name: &str) -> Option<Duration> {
+    var::<NonZeroU64>(lookup, name).map(|seconds| Duration::from_secs(seconds.get()))
+}
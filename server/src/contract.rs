@@ -0,0 +1,611 @@
+//! Contract tests checking the api against the OpenAPI document of every version in
+//! `docs/<version>/openapi.json`.
+//!
+//! The api is served in-process and every documented response of every operation is
+//! provoked. Each response has to be documented for its operation, send the documented
+//! headers and have a body matching the schema of its media type. Objects are checked
+//! strictly: properties the schema does not declare fail, and so do declared properties
+//! missing from the body, as the server always sends optional ones as `null`.
+
+use std::{collections::BTreeSet, num::NonZeroUsize, time::Duration};
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::{
+        header::{self, HeaderMap},
+        Method, StatusCode,
+    },
+    test::{self, TestRequest},
+    web::Data,
+};
+use chrono::DateTime;
+use serde_json::{json, Value};
+
+use crate::{
+    audit::AuditLog, config::Config, rate_limit::Quota, todo::TodoStore, version::ApiVersion, Api,
+    API_KEY_NAME,
+};
+
+const API_KEY: &str = "utoipa-rocks";
+
+/// OpenAPI document the api is checked against.
+struct Document(Value);
+
+impl Document {
+    /// Document of `version` in `docs/`.
+    fn load(version: ApiVersion) -> Self {
+        let document = match version {
+            ApiVersion::V1 => include_str!("../../docs/v1/openapi.json"),
+            ApiVersion::V2 => include_str!("../../docs/v2/openapi.json"),
+        };
+        Self(serde_json::from_str(document).expect("document is not JSON"))
+    }
+
+    /// Schema referred to by `schema`, or `schema` itself when it is no reference.
+    fn resolve<'a>(&'a self, schema: &'a Value) -> Result<&'a Value, String> {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.0.pointer(pointer))
+                .ok_or_else(|| format!("unresolved reference `{reference}`")),
+            None => Ok(schema),
+        }
+    }
+
+    /// Check `value` found `at` against `schema`.
+    fn check(&self, schema: &Value, value: &Value, at: &str) -> Result<(), String> {
+        let schema = self.resolve(schema)?;
+        if value.is_null() && schema.get("nullable") == Some(&Value::Bool(true)) {
+            return Ok(());
+        }
+        for schema in schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            self.check(schema, value, at)?;
+        }
+        if let Some(schemas) = schema.get("oneOf").and_then(Value::as_array) {
+            let matching = schemas
+                .iter()
+                .filter(|schema| self.check(schema, value, at).is_ok())
+                .count();
+            if matching != 1 {
+                return Err(format!("{at}: {value} matches {matching} oneOf schemas"));
+            }
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            if !values.contains(value) {
+                return Err(format!(
+                    "{at}: {value} is not one of {}",
+                    Value::from(values.clone())
+                ));
+            }
+        }
+
+        let Some(schema_type) = schema.get("type").and_then(Value::as_str) else {
+            return Ok(());
+        };
+        match (schema_type, value) {
+            ("object", Value::Object(fields)) => {
+                let properties = schema.get("properties").and_then(Value::as_object);
+                for (name, value) in fields {
+                    let property = properties
+                        .and_then(|properties| properties.get(name))
+                        .ok_or_else(|| format!("{at}: unknown field `{name}`"))?;
+                    self.check(property, value, &format!("{at}.{name}"))?;
+                }
+                match properties
+                    .into_iter()
+                    .flat_map(|properties| properties.keys())
+                    .find(|name| !fields.contains_key(*name))
+                {
+                    Some(name) => Err(format!("{at}: missing field `{name}`")),
+                    None => Ok(()),
+                }
+            }
+            ("array", Value::Array(items)) => {
+                items.iter().enumerate().try_for_each(|(index, item)| {
+                    self.check(&schema["items"], item, &format!("{at}[{index}]"))
+                })
+            }
+            ("string", Value::String(string)) => match schema.get("format").and_then(Value::as_str)
+            {
+                Some("date-time") => DateTime::parse_from_rfc3339(string)
+                    .map(drop)
+                    .map_err(|error| format!("{at}: `{string}` is no date-time, {error}")),
+                _ => Ok(()),
+            },
+            ("integer", Value::Number(number)) => {
+                let integer = number
+                    .as_i64()
+                    .map(i128::from)
+                    .or_else(|| number.as_u64().map(i128::from))
+                    .ok_or_else(|| format!("{at}: {number} is no integer"))?;
+                let minimum = schema.get("minimum").and_then(Value::as_f64);
+                if minimum.is_some_and(|minimum| (integer as f64) < minimum) {
+                    return Err(format!("{at}: {integer} is less than the minimum"));
+                }
+                match schema.get("format").and_then(Value::as_str) {
+                    Some("int32") if i32::try_from(integer).is_err() => {
+                        Err(format!("{at}: {integer} is out of int32 range"))
+                    }
+                    _ => Ok(()),
+                }
+            }
+            ("number", Value::Number(_)) | ("boolean", Value::Bool(_)) => Ok(()),
+            (schema_type, value) => Err(format!("{at}: expected {schema_type}, got {value}")),
+        }
+    }
+
+    /// Check a response with `status`, `headers` and `body` of operation `method` `route`.
+    fn check_response(
+        &self,
+        method: &Method,
+        route: &str,
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<(), String> {
+        let operation = self.0["paths"]
+            .get(route)
+            .and_then(|path_item| path_item.get(method.as_str().to_lowercase()))
+            .ok_or("undocumented operation")?;
+        let response = operation["responses"]
+            .get(status.as_str())
+            .ok_or("undocumented status")?;
+
+        let declared_headers = response.get("headers").and_then(Value::as_object);
+        for name in declared_headers
+            .into_iter()
+            .flat_map(|headers| headers.keys())
+        {
+            if !headers.contains_key(name.as_str()) {
+                return Err(format!("missing header `{name}`"));
+            }
+        }
+
+        let Some(content) = response.get("content").and_then(Value::as_object) else {
+            return match body.is_empty() {
+                true => Ok(()),
+                false => Err(format!(
+                    "undocumented body {}",
+                    String::from_utf8_lossy(body)
+                )),
+            };
+        };
+        let media_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(str::trim)
+            .ok_or("missing content type")?;
+        let media = content
+            .get(media_type)
+            .ok_or_else(|| format!("undocumented content type `{media_type}`"))?;
+        let value = match media_type {
+            "application/json" => serde_json::from_slice(body)
+                .map_err(|error| format!("body is not JSON, {error}"))?,
+            "application/cbor" => {
+                ciborium::from_reader(body).map_err(|error| format!("body is not CBOR, {error}"))?
+            }
+            "application/msgpack" => rmp_serde::from_slice(body)
+                .map_err(|error| format!("body is not MessagePack, {error}"))?,
+            "text/csv" | "application/x-ndjson" => Value::String(
+                String::from_utf8(body.to_vec())
+                    .map_err(|error| format!("body is not UTF-8, {error}"))?,
+            ),
+            _ => return Err(format!("no decoder for content type `{media_type}`")),
+        };
+
+        self.check(&media["schema"], &value, "body")
+    }
+}
+
+/// Calls made to the api and the failed checks of their responses.
+struct Harness {
+    version: ApiVersion,
+    document: Document,
+    /// `<METHOD> <route> <status>` of the documented responses received so far.
+    received: BTreeSet<String>,
+    failures: Vec<String>,
+}
+
+impl Harness {
+    fn new(version: ApiVersion) -> Self {
+        Self {
+            version,
+            document: Document::load(version),
+            received: BTreeSet::new(),
+            failures: Vec::new(),
+        }
+    }
+
+    /// Send `request` to `app` expecting `status`, and check the response against the
+    /// document.
+    async fn expect<S, B>(&mut self, app: &S, request: TestRequest, status: StatusCode)
+    where
+        S: Service<Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+        B: MessageBody,
+    {
+        let request = request.to_request();
+        let method = request.method().clone();
+        let uri = request.uri().clone();
+        let response = test::call_service(app, request).await;
+        let route = response
+            .request()
+            .match_pattern()
+            .unwrap_or_else(|| uri.path().to_owned());
+        let received = response.status();
+        let headers = response.headers().clone();
+        let body = test::read_body(response).await;
+
+        let failure = |reason: String| format!("{method} {uri} {received}: {reason}");
+        if received != status {
+            self.failures
+                .push(failure(format!("expected status {status}")));
+        }
+        match self
+            .document
+            .check_response(&method, &route, received, &headers, &body)
+        {
+            Ok(()) => {
+                self.received
+                    .insert(format!("{method} {route} {}", received.as_str()));
+            }
+            Err(reason) => self.failures.push(failure(reason)),
+        }
+    }
+
+    /// Fail with every failed check and every documented response which was not received.
+    fn finish(mut self) {
+        for (route, path_item) in self.document.0["paths"].as_object().into_iter().flatten() {
+            for (method, operation) in path_item.as_object().into_iter().flatten() {
+                for status in operation["responses"]
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(status, _)| status)
+                {
+                    let response = format!("{} {route} {status}", method.to_uppercase());
+                    if !self.received.contains(&response) {
+                        self.failures.push(format!("{response}: never received"));
+                    }
+                }
+            }
+        }
+
+        assert!(
+            self.failures.is_empty(),
+            "api does not conform to docs/{}/openapi.json:\n{}",
+            self.version.name(),
+            self.failures.join("\n")
+        );
+    }
+}
+
+/// Quota the conformance runs never use up, so limited operations send the quota headers
+/// their responses declare without rejecting requests.
+const UNEXHAUSTED: Quota = Quota::Limited {
+    requests: 10_000,
+    period: Duration::from_secs(3600),
+};
+
+/// Api with small body limits and `rate_limit` as the quota of every route, configured the
+/// same whatever the environment of the test run.
+fn api(rate_limit: Quota, spec_generated: bool) -> Api {
+    let mut config =
+        Config::with_vars(&[("TODO_JSON_LIMIT", "1024"), ("TODO_IMPORT_LIMIT", "1024")]);
+    config.rate_limit = rate_limit;
+
+    Api::new(
+        config,
+        Data::new(TodoStore::default()),
+        Data::new(AuditLog::in_memory(NonZeroUsize::new(100).unwrap())),
+        spec_generated,
+        false,
+    )
+}
+
+#[test]
+fn documents_are_up_to_date() {
+    for version in ApiVersion::ALL {
+        let generated = serde_json::to_value(version.openapi()).unwrap();
+        assert!(
+            generated == Document::load(version).0,
+            "docs/{}/openapi.json differs from the document generated by the server, run the \
+             server to update it",
+            version.name()
+        );
+    }
+}
+
+/// Schemas the generated client replaces with its own types in `docs/type-mappings.json` have
+/// to be declared with the type and format of the table, and todo ids have to refer to them.
+#[test]
+fn type_mappings_match_documents() {
+    let mappings: Value =
+        serde_json::from_str(include_str!("../../docs/type-mappings.json")).unwrap();
+    for version in ApiVersion::ALL {
+        let document = Document::load(version);
+        for replacement in mappings["replacements"].as_array().unwrap() {
+            let name = replacement["schema"].as_str().unwrap();
+            let schema = document
+                .0
+                .pointer(&format!("/components/schemas/{name}"))
+                .unwrap_or_else(|| panic!("{}: schema `{name}` is missing", version.name()));
+            assert_eq!(
+                (&schema["type"], schema.get("format")),
+                (&replacement["type"], replacement.get("format")),
+                "{}: schema `{name}` differs from the type mapping",
+                version.name()
+            );
+        }
+
+        let id_parameters = document.0["paths"]
+            .as_object()
+            .unwrap()
+            .values()
+            .flat_map(|path_item| path_item.as_object().unwrap().values())
+            .flat_map(|operation| operation["parameters"].as_array().into_iter().flatten())
+            .filter(|parameter| parameter["in"] == "path" && parameter["name"] == "id");
+        for parameter in id_parameters {
+            assert_eq!(
+                parameter["schema"],
+                json!({"$ref": "#/components/schemas/TodoId"}),
+                "{}: todo id parameter is no `TodoId`",
+                version.name()
+            );
+        }
+    }
+}
+
+#[actix_web::test]
+async fn api_conforms_to_v1_document() {
+    check_conformance(ApiVersion::V1).await;
+}
+
+#[actix_web::test]
+async fn api_conforms_to_v2_document() {
+    check_conformance(ApiVersion::V2).await;
+}
+
+/// Provoke every documented response of the todo routes of `version` and the unversioned
+/// routes.
+async fn check_conformance(version: ApiVersion) {
+    let mut harness = Harness::new(version);
+    let todo_path = |path: &str| format!("{}/todo{path}", version.prefix());
+    let app = test::init_service(api(UNEXHAUSTED, true).app()).await;
+    let key = (API_KEY_NAME, API_KEY);
+    let with_key = |request: TestRequest| request.insert_header(key);
+    let too_large = "a".repeat(2048);
+
+    // Todos.
+    let todo = json!({"id": 1, "value": "Buy milk", "checked": false, "tags": ["shopping"]});
+    let create = || with_key(TestRequest::post().uri(&todo_path("")));
+    harness
+        .expect(&app, create().set_json(&todo), StatusCode::CREATED)
+        .await;
+    harness
+        .expect(&app, create().set_json(&todo), StatusCode::CONFLICT)
+        .await;
+    harness
+        .expect(
+            &app,
+            create().set_json(json!({"id": 2, "value": "Buy bread"})),
+            StatusCode::BAD_REQUEST,
+        )
+        .await;
+    harness
+        .expect(
+            &app,
+            create().set_json(json!({"id": 2, "value": too_large, "checked": false})),
+            StatusCode::PAYLOAD_TOO_LARGE,
+        )
+        .await;
+
+    let get = |uri: &str| with_key(TestRequest::get().uri(uri));
+    harness
+        .expect(&app, get(&todo_path("")), StatusCode::OK)
+        .await;
+    harness
+        .expect(&app, get(&todo_path("/1")), StatusCode::OK)
+        .await;
+    harness
+        .expect(
+            &app,
+            get(&todo_path("/1?at=yesterday")),
+            StatusCode::BAD_REQUEST,
+        )
+        .await;
+    harness
+        .expect(&app, get(&todo_path("/2")), StatusCode::NOT_FOUND)
+        .await;
+    // Binary representations are checked against the schemas by decoding them.
+    for media_type in ["application/cbor", "application/msgpack"] {
+        let accept = |request: TestRequest| request.insert_header((header::ACCEPT, media_type));
+        harness
+            .expect(&app, accept(get(&todo_path(""))), StatusCode::OK)
+            .await;
+        harness
+            .expect(&app, accept(get(&todo_path("/2"))), StatusCode::NOT_FOUND)
+            .await;
+    }
+
+    let update = |uri: &str| with_key(TestRequest::put().uri(uri));
+    harness
+        .expect(
+            &app,
+            update(&todo_path("/1")).set_json(json!({"checked": true})),
+            StatusCode::OK,
+        )
+        .await;
+    harness
+        .expect(
+            &app,
+            update(&todo_path("/1")).set_json(json!({"checked": "yes"})),
+            StatusCode::BAD_REQUEST,
+        )
+        .await;
+    harness
+        .expect(
+            &app,
+            update(&todo_path("/2")).set_json(json!({"checked": true})),
+            StatusCode::NOT_FOUND,
+        )
+        .await;
+    harness
+        .expect(
+            &app,
+            update(&todo_path("/1")).set_json(json!({"value": too_large})),
+            StatusCode::PAYLOAD_TOO_LARGE,
+        )
+        .await;
+
+    // Revisions.
+    harness
+        .expect(&app, get(&todo_path("/1/revisions")), StatusCode::OK)
+        .await;
+    harness
+        .expect(&app, get(&todo_path("/2/revisions")), StatusCode::NOT_FOUND)
+        .await;
+    let revert = |uri: &str| with_key(TestRequest::post().uri(uri));
+    harness
+        .expect(&app, revert(&todo_path("/1/revert/1")), StatusCode::OK)
+        .await;
+    harness
+        .expect(
+            &app,
+            revert(&todo_path("/1/revert/99")),
+            StatusCode::NOT_FOUND,
+        )
+        .await;
+
+    // Search, export and import.
+    harness
+        .expect(&app, get(&todo_path("/search?value=milk")), StatusCode::OK)
+        .await;
+    harness
+        .expect(&app, get(&todo_path("/export")), StatusCode::OK)
+        .await;
+    harness
+        .expect(&app, get(&todo_path("/export?format=csv")), StatusCode::OK)
+        .await;
+    let import = |body: String| {
+        with_key(TestRequest::post().uri(&todo_path("/import")))
+            .insert_header((header::CONTENT_TYPE, "application/x-ndjson"))
+            .set_payload(body)
+    };
+    let imported = json!({"id": 2, "value": "Buy bread", "checked": false}).to_string();
+    harness
+        .expect(&app, import(imported.clone()), StatusCode::OK)
+        .await;
+    harness
+        .expect(&app, import(imported), StatusCode::CONFLICT)
+        .await;
+    harness
+        .expect(&app, import(String::from("{")), StatusCode::BAD_REQUEST)
+        .await;
+    harness
+        .expect(
+            &app,
+            import(too_large.clone()),
+            StatusCode::PAYLOAD_TOO_LARGE,
+        )
+        .await;
+
+    // Trash.
+    harness
+        .expect(
+            &app,
+            TestRequest::delete().uri(&todo_path("/1")),
+            StatusCode::UNAUTHORIZED,
+        )
+        .await;
+    let delete = || with_key(TestRequest::delete().uri(&todo_path("/1")));
+    harness.expect(&app, delete(), StatusCode::OK).await;
+    harness.expect(&app, delete(), StatusCode::NOT_FOUND).await;
+    harness
+        .expect(&app, get(&todo_path("/trash")), StatusCode::OK)
+        .await;
+    harness
+        .expect(
+            &app,
+            TestRequest::post().uri(&todo_path("/1/restore")),
+            StatusCode::UNAUTHORIZED,
+        )
+        .await;
+    let restore = || with_key(TestRequest::post().uri(&todo_path("/1/restore")));
+    harness.expect(&app, restore(), StatusCode::OK).await;
+    harness.expect(&app, restore(), StatusCode::NOT_FOUND).await;
+
+    // Audit trail and operations.
+    harness.expect(&app, get("/audit"), StatusCode::OK).await;
+    harness
+        .expect(
+            &app,
+            TestRequest::get().uri("/audit"),
+            StatusCode::UNAUTHORIZED,
+        )
+        .await;
+    harness.expect(&app, get("/healthz"), StatusCode::OK).await;
+    harness.expect(&app, get("/readyz"), StatusCode::OK).await;
+    harness.expect(&app, get("/version"), StatusCode::OK).await;
+
+    let unready = test::init_service(api(UNEXHAUSTED, false).app()).await;
+    harness
+        .expect(&unready, get("/readyz"), StatusCode::SERVICE_UNAVAILABLE)
+        .await;
+
+    // Every operation requiring an api key rejects an incorrect one.
+    let document = Document::load(version);
+    for (route, path_item) in document.0["paths"].as_object().into_iter().flatten() {
+        for (method, operation) in path_item.as_object().into_iter().flatten() {
+            if operation["security"].is_null() {
+                continue;
+            }
+            let method = Method::from_bytes(method.to_uppercase().as_bytes()).unwrap();
+            let uri = route.replace("{id}", "1").replace("{rev}", "1");
+            let request = TestRequest::default()
+                .method(method)
+                .uri(&uri)
+                .insert_header((API_KEY_NAME, "incorrect"));
+            harness
+                .expect(&app, request, StatusCode::UNAUTHORIZED)
+                .await;
+        }
+    }
+
+    // Every limited operation is rejected once its quota of one request is used up.
+    let limited = test::init_service(
+        api(
+            Quota::Limited {
+                requests: 1,
+                period: Duration::from_secs(3600),
+            },
+            true,
+        )
+        .app(),
+    )
+    .await;
+    for (route, path_item) in document.0["paths"].as_object().into_iter().flatten() {
+        for (method, operation) in path_item.as_object().into_iter().flatten() {
+            if operation["responses"].get("429").is_none() {
+                continue;
+            }
+            let method = Method::from_bytes(method.to_uppercase().as_bytes()).unwrap();
+            let uri = route.replace("{id}", "1").replace("{rev}", "1");
+            let request = || TestRequest::default().method(method.clone()).uri(&uri);
+
+            test::call_service(&limited, request().to_request()).await;
+            harness
+                .expect(&limited, request(), StatusCode::TOO_MANY_REQUESTS)
+                .await;
+        }
+    }
+
+    harness.finish();
+}
@@ -0,0 +1,63 @@
+//! Entry points of the fuzz targets in `fuzz/`, which cannot reach the private modules.
+
+use std::num::NonZeroUsize;
+
+use actix_web::web::Query;
+
+use crate::{
+    audit::{Actor, AuditLog},
+    auth::Tenant,
+    todo::{SearchTodos, Todo, TodoId, TodoStore, TodoUpdateRequest},
+};
+
+/// Store with a todo `1` of the default api key's tenant to run the parsed requests against.
+fn store(audit: &AuditLog) -> TodoStore {
+    let store = TodoStore::default();
+    let todo = serde_json::from_str(r#"{"id": 1, "value": "buy milk", "checked": false}"#)
+        .expect("todo is valid");
+    store
+        .create(&Tenant::default_key(), todo, audit, &Actor::system())
+        .expect("store is empty");
+    store
+}
+
+/// Parse `data` as a `Todo` body and check it serializes back to the same todo.
+pub fn todo_body(data: &[u8]) {
+    let Ok(todo) = serde_json::from_slice::<Todo>(data) else {
+        return;
+    };
+    let json = serde_json::to_value(&todo).expect("todo serializes");
+    let reparsed: Todo = serde_json::from_value(json.clone()).expect("serialized todo parses");
+    assert_eq!(
+        serde_json::to_value(reparsed).expect("todo serializes"),
+        json
+    );
+}
+
+/// Parse `data` as a `TodoUpdateRequest` body and apply it to a stored todo.
+pub fn todo_update_body(data: &[u8]) {
+    let Ok(update) = serde_json::from_slice::<TodoUpdateRequest>(data) else {
+        return;
+    };
+    let audit = AuditLog::in_memory(NonZeroUsize::MIN);
+    let updated = store(&audit)
+        .update(
+            &Tenant::default_key(),
+            TodoId(1),
+            update,
+            &audit,
+            &Actor::system(),
+        )
+        .expect("todo is stored");
+    assert_eq!(updated.id(), TodoId(1));
+}
+
+/// Parse `query` as the query string of the search endpoint and run the search.
+pub fn search_query(query: &str) {
+    let Ok(search) = Query::<SearchTodos>::from_query(query) else {
+        return;
+    };
+    let audit = AuditLog::in_memory(NonZeroUsize::MIN);
+    let hits = search.search(&store(&audit), &Tenant::default_key());
+    assert!(hits.iter().all(|hit| hit.todo.id() == TodoId(1)));
+}
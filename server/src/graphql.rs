@@ -0,0 +1,771 @@
+//! GraphQL interface of the todo store, served next to the REST api from the same
+//! [`TodoStore`], api keys and audit trail.
+
+use actix_web::{
+    get, post,
+    web::{BytesMut, Data, Payload, ServiceConfig},
+    HttpRequest, HttpResponse, Responder,
+};
+use async_graphql::{
+    http::{receive_batch_body, GraphiQLSource, MultipartOptions},
+    BatchRequest, Context, Enum, ErrorExtensions, InputObject, MaybeUndefined, Object, Schema,
+    Subscription,
+};
+use async_graphql_actix_web::{GraphQLResponse, GraphQLSubscription};
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt, TryStreamExt};
+
+use crate::{
+    audit::{self, Actor, AuditEntry, AuditLog},
+    auth::{self, ApiKeys, Principal},
+    config::Config,
+    search::{Highlight, SearchOptions},
+    security::{payload_too_large, BodyLimits},
+    telemetry::RequestId,
+    todo::{self, ErrorResponse, RevisionAt, SearchHit, Todo, TodoStore, TodoUpdateRequest},
+    RequireApiKey, API_KEY_NAME,
+};
+
+/// Schema of the GraphQL interface.
+pub(super) type TodoSchema = Schema<Query, Mutation, Subscription>;
+
+/// Schema resolving from `store` and recording mutations to `audit`, rejecting operations
+/// nested deeper or selecting more fields than `config` allows.
+pub(super) fn schema(store: Data<TodoStore>, audit: Data<AuditLog>, config: &Config) -> TodoSchema {
+    Schema::build(Query, Mutation, Subscription)
+        .data(store)
+        .data(audit)
+        .limit_depth(config.graphql_max_depth)
+        .limit_complexity(config.graphql_max_complexity)
+        .finish()
+}
+
+/// Maximum number of operations of a batch request.
+#[derive(Clone, Copy)]
+struct BatchLimit(usize);
+
+pub(super) fn configure(schema: TodoSchema, max_batch: usize) -> impl FnOnce(&mut ServiceConfig) {
+    move |config: &mut ServiceConfig| {
+        config
+            .app_data(Data::new(schema))
+            .app_data(Data::new(BatchLimit(max_batch)))
+            .service(graphql)
+            .service(graphql_ws)
+            .service(graphiql);
+    }
+}
+
+/// Caller of an operation, resolved once per request or subscription connection.
+#[derive(Clone)]
+struct Caller {
+    /// Authenticated principal, or the reason the caller could not be authenticated.
+    principal: Result<Principal, &'static str>,
+    request_id: RequestId,
+}
+
+impl Caller {
+    fn of(req: &HttpRequest, request_id: RequestId) -> Self {
+        Self {
+            principal: auth::authenticate(req),
+            request_id,
+        }
+    }
+
+    /// Principal of the caller, failing without valid credentials like [`RequireApiKey`].
+    ///
+    /// [`RequireApiKey`]: crate::RequireApiKey
+    fn authenticated(&self) -> Result<Principal, ErrorResponse> {
+        self.principal
+            .clone()
+            .map_err(|reason| ErrorResponse::Unauthorized(String::from(reason)))
+    }
+
+    fn actor(&self, principal: &Principal) -> Actor {
+        Actor::new(principal, &self.request_id)
+    }
+}
+
+/// Execute GraphQL queries and mutations, also in batches.
+///
+/// Callers are authenticated by the `todo_apikey` header like on the REST api, requests
+/// without valid credentials are rejected with 401 before any operation runs. Request body
+/// is limited like JSON bodies of the REST api, batches over the [`BatchLimit`] are rejected
+/// as the whole batch is a single request to the rate limit.
+#[post("/graphql", wrap = "RequireApiKey")]
+#[tracing::instrument(skip_all)]
+async fn graphql(
+    req: HttpRequest,
+    mut payload: Payload,
+    request_id: RequestId,
+    schema: Data<TodoSchema>,
+    batch_limit: Data<BatchLimit>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let limit = req
+        .app_data::<Data<BodyLimits>>()
+        .map_or(usize::MAX, |limits| limits.json);
+
+    let mut body = BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk?;
+        if body.len() + chunk.len() > limit {
+            return Ok(payload_too_large(limit));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    let content_type = req
+        .headers()
+        .get(actix_web::http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
+    let request =
+        match receive_batch_body(content_type, body.as_ref(), MultipartOptions::default()).await {
+            Ok(request) => request,
+            Err(error) => {
+                return Ok(
+                    HttpResponse::BadRequest().json(ErrorResponse::BadRequest(error.to_string()))
+                );
+            }
+        };
+    let BatchLimit(max_batch) = **batch_limit;
+    if let BatchRequest::Batch(requests) = &request {
+        if requests.len() > max_batch {
+            return Ok(
+                HttpResponse::BadRequest().json(ErrorResponse::BadRequest(format!(
+                    "batch of {} operations exceeds the limit of {max_batch}",
+                    requests.len()
+                ))),
+            );
+        }
+    }
+
+    let response = schema
+        .execute_batch(request.data(Caller::of(&req, request_id)))
+        .await;
+    Ok(GraphQLResponse::from(response)
+        .respond_to(&req)
+        .map_into_boxed_body())
+}
+
+/// Serve GraphQL subscriptions over WebSocket.
+///
+/// Callers are authenticated by the `todo_apikey` header of the upgrade request or, as
+/// browsers cannot set it, by the `todo_apikey` field of the connection init payload.
+#[get("/graphql/ws")]
+#[tracing::instrument(skip_all)]
+async fn graphql_ws(
+    req: HttpRequest,
+    payload: Payload,
+    request_id: RequestId,
+    schema: Data<TodoSchema>,
+) -> actix_web::Result<HttpResponse> {
+    let api_keys = req.app_data::<Data<ApiKeys>>().cloned();
+    let mut data = async_graphql::Data::default();
+    data.insert(Caller::of(&req, request_id.clone()));
+
+    GraphQLSubscription::new(Schema::clone(&schema))
+        .with_data(data)
+        .on_connection_init(move |init| async move {
+            let mut data = async_graphql::Data::default();
+            if let Some(key) = init.get(API_KEY_NAME).and_then(|key| key.as_str()) {
+                let principal = api_keys
+                    .as_deref()
+                    .and_then(|api_keys| api_keys.authenticate(key.as_bytes()))
+                    .cloned()
+                    .ok_or("incorrect api key");
+                data.insert(Caller {
+                    principal,
+                    request_id,
+                });
+            }
+            Ok(data)
+        })
+        .start(&req, payload)
+}
+
+/// GraphiQL IDE for exploring the GraphQL interface.
+#[get("/graphiql")]
+async fn graphiql() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            GraphiQLSource::build()
+                .endpoint("/graphql")
+                .subscription_endpoint("/graphql/ws")
+                .title("Todo GraphQL")
+                .finish(),
+        )
+}
+
+/// Error with the name of the `ErrorResponse` variant in upper snake case as `code`
+/// extension, e.g. `NOT_FOUND`.
+impl From<ErrorResponse> for async_graphql::Error {
+    fn from(error: ErrorResponse) -> Self {
+        let (code, message) = match error {
+            ErrorResponse::BadRequest(message) => ("BAD_REQUEST", message),
+            ErrorResponse::NotFound(message) => ("NOT_FOUND", message),
+            ErrorResponse::Conflict(message) => ("CONFLICT", message),
+            ErrorResponse::Unauthorized(message) => ("UNAUTHORIZED", message),
+            ErrorResponse::PayloadTooLarge(message) => ("PAYLOAD_TOO_LARGE", message),
+            ErrorResponse::TooManyRequests(message) => ("TOO_MANY_REQUESTS", message),
+        };
+
+        async_graphql::Error::new(message).extend_with(|_, extensions| extensions.set("code", code))
+    }
+}
+
+/// Priority of a todo.
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "todo::Priority")]
+enum Priority {
+    /// Can wait until everything else is done.
+    Low,
+    /// Default priority of a task.
+    Normal,
+    /// Should be done before tasks of normal priority.
+    High,
+    /// Needs to be done right away.
+    Urgent,
+}
+
+/// Kind of change made to a todo.
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "audit::Operation")]
+enum Operation {
+    /// Todo was created.
+    Create,
+    /// Todo was updated.
+    Update,
+    /// Todo was moved to the trash.
+    Delete,
+    /// Todo was restored from the trash.
+    Restore,
+    /// Todo was reverted to an earlier revision.
+    Revert,
+    /// Todo was created or overwritten by an import.
+    Import,
+    /// Todo was removed from the trash for good after its retention expired.
+    Purge,
+}
+
+/// Task to do.
+#[Object]
+impl Todo {
+    /// Unique id for the todo item.
+    #[graphql(name = "id")]
+    async fn unique_id(&self) -> i32 {
+        self.id.0
+    }
+
+    /// Description of the task to do.
+    async fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Mark is the task done or not.
+    async fn checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Priority of the task.
+    async fn priority(&self) -> Priority {
+        self.priority.into()
+    }
+
+    /// Free form labels of the task.
+    async fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Optional deadline of the task.
+    async fn due_at(&self) -> Option<DateTime<Utc>> {
+        self.due_at
+    }
+
+    /// Time the todo was created at.
+    async fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    /// Time the todo was last modified at.
+    async fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    /// Time the todo was last checked done at.
+    async fn completed_at(&self) -> Option<DateTime<Utc>> {
+        self.completed_at
+    }
+
+    /// Time the todo was moved to the trash at.
+    async fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+}
+
+/// `Todo` found by search.
+#[Object]
+impl SearchHit {
+    /// Relevance of the todo to the search, higher is better.
+    async fn score(&self) -> f64 {
+        self.score
+    }
+
+    /// Words of the todo's value matching the search, in order.
+    async fn highlights(&self) -> &[Highlight] {
+        &self.highlights
+    }
+
+    /// Found todo.
+    async fn todo(&self) -> &Todo {
+        &self.todo
+    }
+}
+
+/// Range of a matched word in a todo value, in bytes.
+#[Object]
+impl Highlight {
+    /// Byte offset of the first byte of the match.
+    async fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Byte offset one past the last byte of the match.
+    async fn end(&self) -> usize {
+        self.end
+    }
+}
+
+/// Change made to a todo, as recorded in the audit trail.
+#[Object(name = "TodoChange")]
+impl AuditEntry {
+    /// Sequence number of the change in the audit trail.
+    async fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Time the change was made at.
+    async fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    /// Kind of the change.
+    async fn operation(&self) -> Operation {
+        self.operation.into()
+    }
+
+    /// Id of the changed todo.
+    async fn todo_id(&self) -> i32 {
+        self.todo_id.0
+    }
+
+    /// Id of the api key the change was made with.
+    async fn actor(&self) -> &str {
+        &self.actor
+    }
+
+    /// State of the todo before the change, missing when the todo was created.
+    async fn before(&self) -> Option<&Todo> {
+        self.before.as_ref()
+    }
+
+    /// State of the todo after the change, missing when the todo was removed.
+    async fn after(&self) -> Option<&Todo> {
+        self.after.as_ref()
+    }
+}
+
+/// New todo to create.
+#[derive(InputObject)]
+struct TodoInput {
+    /// Unique id for the todo item.
+    id: i32,
+    /// Description of the task to do.
+    value: String,
+    /// Mark is the task done or not.
+    #[graphql(default)]
+    checked: bool,
+    /// Priority of the task, defaults to `NORMAL`.
+    priority: Option<Priority>,
+    /// Free form labels of the task.
+    #[graphql(default)]
+    tags: Vec<String>,
+    /// Optional deadline of the task.
+    due_at: Option<DateTime<Utc>>,
+}
+
+impl From<TodoInput> for Todo {
+    fn from(todo: TodoInput) -> Self {
+        Self {
+            id: todo::TodoId(todo.id),
+            value: todo.value,
+            checked: todo.checked,
+            priority: todo.priority.map(Into::into).unwrap_or_default(),
+            tags: todo.tags,
+            due_at: todo.due_at,
+            created_at: None,
+            updated_at: None,
+            completed_at: None,
+            deleted_at: None,
+        }
+    }
+}
+
+/// Changes to an existing todo, fields left out are kept as they are.
+#[derive(InputObject)]
+struct TodoUpdateInput {
+    /// New value for the task.
+    value: Option<String>,
+    /// New check status of the task.
+    checked: Option<bool>,
+    /// New priority of the task.
+    priority: Option<Priority>,
+    /// New tags replacing the current tags of the task.
+    tags: Option<Vec<String>>,
+    /// New deadline of the task, `null` removes the deadline.
+    due_at: MaybeUndefined<DateTime<Utc>>,
+}
+
+impl From<TodoUpdateInput> for TodoUpdateRequest {
+    fn from(update: TodoUpdateInput) -> Self {
+        Self {
+            value: update.value,
+            checked: update.checked,
+            priority: update.priority.map(Into::into),
+            tags: update.tags,
+            due_at: match update.due_at {
+                MaybeUndefined::Undefined => None,
+                MaybeUndefined::Null => Some(None),
+                MaybeUndefined::Value(due_at) => Some(Some(due_at)),
+            },
+        }
+    }
+}
+
+pub(super) struct Query;
+
+#[Object]
+impl Query {
+    /// Todos of the caller's tenant in order of their id.
+    async fn todos(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] include_deleted: bool,
+    ) -> async_graphql::Result<Vec<Todo>> {
+        let principal = ctx.data_unchecked::<Caller>().authenticated()?;
+        Ok(ctx
+            .data_unchecked::<Data<TodoStore>>()
+            .list(&principal.tenant, include_deleted))
+    }
+
+    /// Todo by id, `null` when the caller's tenant has no such todo or it is in the trash.
+    ///
+    /// The todo is returned as it was at revision number or RFC 3339 timestamp `at` when
+    /// given.
+    async fn todo(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+        at: Option<String>,
+    ) -> async_graphql::Result<Option<Todo>> {
+        let principal = ctx.data_unchecked::<Caller>().authenticated()?;
+        let at = at
+            .as_deref()
+            .map(str::parse::<RevisionAt>)
+            .transpose()
+            .map_err(ErrorResponse::BadRequest)?;
+
+        match ctx
+            .data_unchecked::<Data<TodoStore>>()
+            .get(&principal.tenant, todo::TodoId(id), at)
+        {
+            Ok(todo) => Ok(Some(todo)),
+            Err(ErrorResponse::NotFound(_)) => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Full-text search of the todos of the caller's tenant, best match first.
+    async fn search(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Words that should be found from the todo's value.")] value: String,
+        #[graphql(default)] include_deleted: bool,
+        #[graphql(desc = "Also match other forms of the words.", default)] stem: bool,
+        #[graphql(desc = "Also match words within a typo or two.", default = true)] fuzzy: bool,
+    ) -> async_graphql::Result<Vec<SearchHit>> {
+        let principal = ctx.data_unchecked::<Caller>().authenticated()?;
+        Ok(ctx.data_unchecked::<Data<TodoStore>>().search(
+            &principal.tenant,
+            &value,
+            include_deleted,
+            SearchOptions { stem, fuzzy },
+        ))
+    }
+}
+
+pub(super) struct Mutation;
+
+#[Object]
+impl Mutation {
+    /// Create a new todo, failing with `CONFLICT` when its id is taken.
+    async fn create_todo(&self, ctx: &Context<'_>, todo: TodoInput) -> async_graphql::Result<Todo> {
+        let caller = ctx.data_unchecked::<Caller>();
+        let principal = caller.authenticated()?;
+
+        Ok(ctx.data_unchecked::<Data<TodoStore>>().create(
+            &principal.tenant,
+            todo.into(),
+            ctx.data_unchecked::<Data<AuditLog>>(),
+            &caller.actor(&principal),
+        )?)
+    }
+
+    /// Update an existing todo, failing with `NOT_FOUND` when it does not exist.
+    async fn update_todo(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+        update: TodoUpdateInput,
+    ) -> async_graphql::Result<Todo> {
+        let caller = ctx.data_unchecked::<Caller>();
+        let principal = caller.authenticated()?;
+
+        Ok(ctx.data_unchecked::<Data<TodoStore>>().update(
+            &principal.tenant,
+            todo::TodoId(id),
+            update.into(),
+            ctx.data_unchecked::<Data<AuditLog>>(),
+            &caller.actor(&principal),
+        )?)
+    }
+
+    /// Move a todo to the trash.
+    async fn delete_todo(&self, ctx: &Context<'_>, id: i32) -> async_graphql::Result<bool> {
+        let caller = ctx.data_unchecked::<Caller>();
+        let principal = caller.authenticated()?;
+
+        ctx.data_unchecked::<Data<TodoStore>>().delete(
+            &principal.tenant,
+            todo::TodoId(id),
+            ctx.data_unchecked::<Data<AuditLog>>(),
+            &caller.actor(&principal),
+        )?;
+        Ok(true)
+    }
+}
+
+pub(super) struct Subscription;
+
+#[Subscription]
+impl Subscription {
+    /// Changes made to the todos of the caller's tenant from now on.
+    ///
+    /// A subscriber falling too far behind receives a `DATA_LOSS` error ending the stream and
+    /// has to query the todos again.
+    async fn todo_changes(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<impl Stream<Item = async_graphql::Result<AuditEntry>>> {
+        let principal = ctx.data_unchecked::<Caller>().authenticated()?;
+
+        Ok(ctx
+            .data_unchecked::<Data<AuditLog>>()
+            .changes(principal.tenant)
+            .map_err(|missed| {
+                async_graphql::Error::new(format!("missed {missed} changes, query again"))
+                    .extend_with(|_, extensions| extensions.set("code", "DATA_LOSS"))
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use actix_web::{http::StatusCode, test, App};
+    use async_graphql::{Request, Value};
+    use serde_json::json;
+
+    use super::*;
+    use crate::auth::Tenant;
+
+    fn caller(principal: Result<Principal, &'static str>) -> Caller {
+        Caller {
+            principal,
+            request_id: RequestId::given_or_generated(None),
+        }
+    }
+
+    fn authenticated() -> Caller {
+        caller(Ok(Principal {
+            key_id: String::from("default"),
+            tenant: Tenant::default_key(),
+        }))
+    }
+
+    async fn execute(schema: &TodoSchema, caller: Caller, query: &str) -> async_graphql::Response {
+        schema.execute(Request::new(query).data(caller)).await
+    }
+
+    fn todo_schema(vars: &[(&str, &str)]) -> TodoSchema {
+        schema(
+            Data::new(TodoStore::default()),
+            Data::new(AuditLog::in_memory(NonZeroUsize::new(100).unwrap())),
+            &Config::with_vars(vars),
+        )
+    }
+
+    fn error_code(response: &async_graphql::Response) -> Option<Value> {
+        response.errors[0]
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("code"))
+            .cloned()
+    }
+
+    #[actix_web::test]
+    async fn mutations_are_streamed_to_subscribers() {
+        let store = Data::new(TodoStore::default());
+        let schema = schema(
+            store.clone(),
+            Data::new(AuditLog::in_memory(NonZeroUsize::new(100).unwrap())),
+            &Config::with_vars(&[]),
+        );
+        let mut changes = schema.execute_stream(
+            Request::new("subscription { todoChanges { operation todoId after { checked } } }")
+                .data(authenticated()),
+        );
+        // Subscriptions start resolving when first polled.
+        assert!(futures::poll!(changes.next()).is_pending());
+
+        let response = execute(
+            &schema,
+            authenticated(),
+            r#"mutation { createTodo(todo: {id: 1, value: "Buy milk", priority: HIGH}) { id } }"#,
+        )
+        .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let response = execute(
+            &schema,
+            authenticated(),
+            "mutation { updateTodo(id: 1, update: {checked: true}) { checked } }",
+        )
+        .await;
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({"updateTodo": {"checked": true}})
+        );
+
+        let change = changes.next().await.unwrap().data.into_json().unwrap();
+        assert_eq!(change["todoChanges"]["operation"], "CREATE");
+        let change = changes.next().await.unwrap().data.into_json().unwrap();
+        assert_eq!(change["todoChanges"]["operation"], "UPDATE");
+        assert_eq!(change["todoChanges"]["after"]["checked"], true);
+
+        let response = execute(
+            &schema,
+            authenticated(),
+            "{ todos { id priority } todo(id: 2) { id } }",
+        )
+        .await;
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({"todos": [{"id": 1, "priority": "HIGH"}], "todo": null})
+        );
+        let response = execute(
+            &schema,
+            caller(Err("incorrect api key")),
+            "{ todos { id } }",
+        )
+        .await;
+        assert_eq!(error_code(&response), Some(Value::from("UNAUTHORIZED")));
+
+        let response = execute(
+            &schema,
+            caller(Err("missing api key")),
+            "mutation { deleteTodo(id: 1) }",
+        )
+        .await;
+        assert_eq!(error_code(&response), Some(Value::from("UNAUTHORIZED")));
+        let response = execute(&schema, authenticated(), "mutation { deleteTodo(id: 1) }").await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let response = execute(&schema, authenticated(), "mutation { deleteTodo(id: 1) }").await;
+        assert_eq!(error_code(&response), Some(Value::from("NOT_FOUND")));
+        assert_eq!(store.list(&Tenant::default_key(), false).len(), 0);
+    }
+
+    #[actix_web::test]
+    async fn introspection_is_within_default_limits() {
+        let response = todo_schema(&[])
+            // As deep as the introspection query of GraphiQL.
+            .execute(
+                "{ __schema { types { fields { args { type { ofType { ofType { ofType { ofType { \
+                 ofType { ofType { ofType { ofType { name } } } } } } } } } } } } } }",
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+    }
+
+    #[actix_web::test]
+    async fn deep_and_complex_operations_are_rejected() {
+        let schema = todo_schema(&[
+            ("TODO_GRAPHQL_MAX_DEPTH", "2"),
+            ("TODO_GRAPHQL_MAX_COMPLEXITY", "3"),
+        ]);
+
+        let response = execute(&schema, authenticated(), "{ todos { id value } }").await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let response = execute(&schema, authenticated(), "{ __schema { types { name } } }").await;
+        assert_eq!(response.errors[0].message, "Query is nested too deep.");
+        let response = execute(&schema, authenticated(), "{ todos { id value checked } }").await;
+        assert_eq!(response.errors[0].message, "Query is too complex.");
+    }
+
+    #[actix_web::test]
+    async fn requests_without_api_key_are_rejected() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(ApiKeys::default()))
+                .configure(configure(todo_schema(&[]), 2)),
+        )
+        .await;
+        let request = test::TestRequest::post()
+            .uri("/graphql")
+            .set_json(json!({"query": "{ todos { id } }"}))
+            .to_request();
+
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn batches_over_the_limit_are_rejected() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(ApiKeys::default()))
+                .configure(configure(todo_schema(&[]), 2)),
+        )
+        .await;
+        let batch = |size| {
+            test::TestRequest::post()
+                .uri("/graphql")
+                .insert_header((API_KEY_NAME, "utoipa-rocks"))
+                .set_json(vec![json!({"query": "{ todos { id } }"}); size])
+                .to_request()
+        };
+
+        let response = test::call_service(&app, batch(2)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body.as_array().map(Vec::len), Some(2));
+
+        let response = test::call_service(&app, batch(3)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(
+            body,
+            json!({"BadRequest": "batch of 3 operations exceeds the limit of 2"})
+        );
+    }
+}
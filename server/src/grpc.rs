@@ -0,0 +1,705 @@
+//! gRPC interface of the todo store defined in `proto/todo.proto`, served next to the REST
+//! api from the same [`TodoStore`], api keys and audit trail.
+
+use std::{fs, future::Future, io, net::TcpListener, pin::Pin, sync::Arc, time::Duration};
+
+use actix_web::{rt, web::Data};
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use tokio::sync::watch;
+use tonic::{
+    metadata::MetadataMap,
+    transport::{server::TcpIncoming, Certificate, Identity, Server, ServerTlsConfig},
+    Request, Response, Status,
+};
+
+use crate::{
+    audit::{Actor, AuditEntry, AuditLog, Operation},
+    auth::{ApiKeys, ClientSubjects, Principal},
+    config::Config,
+    rate_limit::RateLimiter,
+    search::{Highlight, SearchOptions},
+    telemetry::{RequestId, REQUEST_ID_HEADER},
+    tls::{self, ClientAuth},
+    todo::{
+        ErrorResponse, Priority, RevisionAt, SearchHit, Todo, TodoId, TodoStore, TodoUpdateRequest,
+    },
+    API_KEY_NAME,
+};
+
+mod proto {
+    tonic::include_proto!("todo.v1");
+}
+
+use proto::todo_service_server::{TodoService, TodoServiceServer, SERVICE_NAME};
+
+/// Implementation of `TodoService`.
+pub(super) struct Todos {
+    pub(super) store: Data<TodoStore>,
+    pub(super) audit: Data<AuditLog>,
+    pub(super) api_keys: Data<ApiKeys>,
+    pub(super) client_subjects: ClientSubjects,
+    pub(super) rate_limiter: Data<RateLimiter>,
+    pub(super) shutdown: Shutdown,
+}
+
+impl Todos {
+    /// Authenticate the caller by the `todo_apikey` metadata of the request, or without it by
+    /// the verified client certificate like `auth::authenticate`.
+    ///
+    /// Returns the reason when the caller could not be authenticated.
+    fn authenticate<T>(&self, request: &Request<T>) -> Result<Principal, &'static str> {
+        if let Some(key) = request.metadata().get(API_KEY_NAME) {
+            return self
+                .api_keys
+                .authenticate(key.as_bytes())
+                .cloned()
+                .ok_or("incorrect api key");
+        }
+
+        request
+            .peer_certs()
+            .and_then(|certificates| {
+                certificates.first().and_then(|certificate| {
+                    tls::certificate_principal(&self.client_subjects, certificate).cloned()
+                })
+            })
+            .ok_or("missing api key")
+    }
+
+    /// Principal of the caller of `method`, failing when the caller exceeds the quota of the
+    /// method like `RateLimit` and without valid api key or client certificate like
+    /// `RequireApiKey`.
+    // Calls fail with `Status` like the generated service methods.
+    #[allow(clippy::result_large_err)]
+    fn principal<T>(&self, request: &Request<T>, method: &str) -> Result<Principal, Status> {
+        let principal = self.authenticate(request);
+        self.rate_limiter.check_grpc(
+            &format!("/{SERVICE_NAME}/{method}"),
+            principal
+                .as_ref()
+                .ok()
+                .map(|principal| principal.key_id.as_str()),
+            request.remote_addr().map(|address| address.ip()),
+            request
+                .metadata()
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok()),
+        )?;
+
+        principal.map_err(|reason| ErrorResponse::Unauthorized(String::from(reason)).into())
+    }
+}
+
+/// Who makes the call, identified by the `x-request-id` metadata when given.
+fn actor(principal: &Principal, metadata: &MetadataMap) -> Actor {
+    let request_id = metadata
+        .get(REQUEST_ID_HEADER.as_str())
+        .and_then(|value| value.to_str().ok());
+    Actor::new(principal, &RequestId::given_or_generated(request_id))
+}
+
+/// TLS configuration of the gRPC server with the certificate, key and client certificate
+/// authorities of the REST api, `None` when TLS is not configured.
+///
+/// The certificate is only read once, a renewed certificate is served over gRPC after a
+/// restart.
+pub(super) fn tls_config(config: &Config) -> io::Result<Option<ServerTlsConfig>> {
+    let (Some(cert_path), Some(key_path)) = (&config.tls_cert, &config.tls_key) else {
+        return Ok(None);
+    };
+
+    let identity = Identity::from_pem(fs::read(cert_path)?, fs::read(key_path)?);
+    let tls = ServerTlsConfig::new().identity(identity);
+    Ok(Some(match &config.tls_client_ca {
+        Some(ca_path) => tls
+            .client_ca_root(Certificate::from_pem(fs::read(ca_path)?))
+            .client_auth_optional(config.tls_client_auth == ClientAuth::Optional),
+        None => tls,
+    }))
+}
+
+/// Signal stopping the gRPC server and ending the `Watch` streams, which would otherwise keep
+/// the server from shutting down until the timeout.
+#[derive(Clone)]
+pub(super) struct Shutdown(Arc<watch::Sender<bool>>);
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self(Arc::new(watch::channel(false).0))
+    }
+}
+
+impl Shutdown {
+    fn trigger(&self) {
+        self.0.send_replace(true);
+    }
+
+    /// Completes once the signal is triggered.
+    fn triggered(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut triggered = self.0.subscribe();
+        async move {
+            // The sender outlives the receiver, held by `self` at least as long as the server.
+            let _ = triggered.wait_for(|triggered| *triggered).await;
+        }
+    }
+}
+
+/// gRPC server serving in a background task.
+pub(super) struct GrpcServer {
+    shutdown: Shutdown,
+    task: rt::task::JoinHandle<()>,
+}
+
+impl GrpcServer {
+    /// Stop accepting calls, end the `Watch` streams and give the calls in flight `timeout` to
+    /// finish before they are cancelled.
+    pub(super) async fn shutdown(self, timeout: Duration) {
+        let Self { shutdown, mut task } = self;
+        shutdown.trigger();
+        if rt::time::timeout(timeout, &mut task).await.is_err() {
+            tracing::warn!("gRPC calls did not finish within the shutdown timeout");
+            task.abort();
+        }
+    }
+}
+
+/// Serve `todos` on `listener` in a background task, over TLS when `tls` is given.
+pub(super) fn spawn(
+    listener: TcpListener,
+    todos: Todos,
+    tls: Option<ServerTlsConfig>,
+) -> io::Result<GrpcServer> {
+    listener.set_nonblocking(true)?;
+    let listener = tokio::net::TcpListener::from_std(listener)?;
+    let incoming = TcpIncoming::from_listener(listener, true, None).map_err(io::Error::other)?;
+    let mut server = Server::builder();
+    if let Some(tls) = tls {
+        server = server.tls_config(tls).map_err(io::Error::other)?;
+    }
+    let shutdown = todos.shutdown.clone();
+    let triggered = shutdown.triggered();
+
+    let task = rt::spawn(async move {
+        let result = server
+            .add_service(TodoServiceServer::new(todos))
+            .serve_with_incoming_shutdown(incoming, triggered)
+            .await;
+        if let Err(error) = result {
+            tracing::error!(%error, "gRPC server failed");
+        }
+    });
+
+    Ok(GrpcServer { shutdown, task })
+}
+
+#[tonic::async_trait]
+impl TodoService for Todos {
+    #[tracing::instrument(skip_all)]
+    async fn list(
+        &self,
+        request: Request<proto::ListRequest>,
+    ) -> Result<Response<proto::ListResponse>, Status> {
+        let principal = self.principal(&request, "List")?;
+        let todos = self
+            .store
+            .list(&principal.tenant, request.get_ref().include_deleted);
+
+        Ok(Response::new(proto::ListResponse {
+            todos: todos.into_iter().map(proto::Todo::from).collect(),
+        }))
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn create(
+        &self,
+        request: Request<proto::CreateRequest>,
+    ) -> Result<Response<proto::Todo>, Status> {
+        let principal = self.principal(&request, "Create")?;
+        let actor = actor(&principal, request.metadata());
+        let todo = request
+            .into_inner()
+            .todo
+            .ok_or_else(|| ErrorResponse::BadRequest(String::from("missing todo")))?;
+
+        let todo = self
+            .store
+            .create(&principal.tenant, todo.try_into()?, &self.audit, &actor)?;
+        Ok(Response::new(todo.into()))
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn get(
+        &self,
+        request: Request<proto::GetRequest>,
+    ) -> Result<Response<proto::Todo>, Status> {
+        let principal = self.principal(&request, "Get")?;
+        let request = request.into_inner();
+        let at = request
+            .at
+            .as_deref()
+            .map(str::parse::<RevisionAt>)
+            .transpose()
+            .map_err(ErrorResponse::BadRequest)?;
+
+        let todo = self.store.get(&principal.tenant, TodoId(request.id), at)?;
+        Ok(Response::new(todo.into()))
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn update(
+        &self,
+        request: Request<proto::UpdateRequest>,
+    ) -> Result<Response<proto::Todo>, Status> {
+        let principal = self.principal(&request, "Update")?;
+        let actor = actor(&principal, request.metadata());
+        let request = request.into_inner();
+        let id = TodoId(request.id);
+
+        let todo = self.store.update(
+            &principal.tenant,
+            id,
+            request.try_into()?,
+            &self.audit,
+            &actor,
+        )?;
+        Ok(Response::new(todo.into()))
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn delete(
+        &self,
+        request: Request<proto::DeleteRequest>,
+    ) -> Result<Response<proto::DeleteResponse>, Status> {
+        let principal = self.principal(&request, "Delete")?;
+        let actor = actor(&principal, request.metadata());
+
+        self.store.delete(
+            &principal.tenant,
+            TodoId(request.get_ref().id),
+            &self.audit,
+            &actor,
+        )?;
+        Ok(Response::new(proto::DeleteResponse {}))
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn search(
+        &self,
+        request: Request<proto::SearchRequest>,
+    ) -> Result<Response<proto::SearchResponse>, Status> {
+        let principal = self.principal(&request, "Search")?;
+        let request = request.into_inner();
+        let options = SearchOptions {
+            stem: request.stem.unwrap_or_default(),
+            fuzzy: request.fuzzy.unwrap_or(true),
+        };
+
+        let hits = self.store.search(
+            &principal.tenant,
+            &request.value,
+            request.include_deleted,
+            options,
+        );
+        Ok(Response::new(proto::SearchResponse {
+            hits: hits.into_iter().map(proto::SearchHit::from).collect(),
+        }))
+    }
+
+    type WatchStream = Pin<Box<dyn Stream<Item = Result<proto::WatchEvent, Status>> + Send>>;
+
+    /// Streams the audit entries of the caller's tenant. A watcher falling too far behind is
+    /// ended with `DATA_LOSS` and has to list the todos again, watchers still connected on
+    /// shutdown are ended with `UNAVAILABLE`.
+    #[tracing::instrument(skip_all)]
+    async fn watch(
+        &self,
+        request: Request<proto::WatchRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let tenant = self.principal(&request, "Watch")?.tenant;
+        let events = self
+            .audit
+            .changes(tenant)
+            .map_ok(proto::WatchEvent::from)
+            .map_err(|missed| Status::data_loss(format!("missed {missed} changes, list again")))
+            .take_until(self.shutdown.triggered())
+            .chain(stream::once(async {
+                Err(Status::unavailable("server is shutting down"))
+            }));
+
+        Ok(Response::new(Box::pin(events)))
+    }
+}
+
+/// Map to the status code closest to the HTTP status of the error.
+impl From<ErrorResponse> for Status {
+    fn from(error: ErrorResponse) -> Self {
+        match error {
+            ErrorResponse::BadRequest(message) => Status::invalid_argument(message),
+            ErrorResponse::NotFound(message) => Status::not_found(message),
+            ErrorResponse::Conflict(message) => Status::already_exists(message),
+            ErrorResponse::Unauthorized(message) => Status::unauthenticated(message),
+            ErrorResponse::PayloadTooLarge(message) | ErrorResponse::TooManyRequests(message) => {
+                Status::resource_exhausted(message)
+            }
+        }
+    }
+}
+
+fn timestamp(time: DateTime<Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: time.timestamp(),
+        nanos: time.timestamp_subsec_nanos() as i32,
+    }
+}
+
+fn date_time(timestamp: prost_types::Timestamp) -> Result<DateTime<Utc>, ErrorResponse> {
+    u32::try_from(timestamp.nanos)
+        .ok()
+        .and_then(|nanos| DateTime::from_timestamp(timestamp.seconds, nanos))
+        .ok_or_else(|| ErrorResponse::BadRequest(format!("invalid timestamp {timestamp}")))
+}
+
+impl From<Priority> for proto::Priority {
+    fn from(priority: Priority) -> Self {
+        match priority {
+            Priority::Low => Self::Low,
+            Priority::Normal => Self::Normal,
+            Priority::High => Self::High,
+            Priority::Urgent => Self::Urgent,
+        }
+    }
+}
+
+/// Parse priority sent as `i32`, unspecified is the default priority.
+fn priority(value: i32) -> Result<Priority, ErrorResponse> {
+    match proto::Priority::try_from(value) {
+        Ok(proto::Priority::Unspecified) => Ok(Priority::default()),
+        Ok(proto::Priority::Low) => Ok(Priority::Low),
+        Ok(proto::Priority::Normal) => Ok(Priority::Normal),
+        Ok(proto::Priority::High) => Ok(Priority::High),
+        Ok(proto::Priority::Urgent) => Ok(Priority::Urgent),
+        Err(_) => Err(ErrorResponse::BadRequest(format!(
+            "unknown priority {value}"
+        ))),
+    }
+}
+
+impl From<Todo> for proto::Todo {
+    fn from(todo: Todo) -> Self {
+        Self {
+            id: todo.id.0,
+            value: todo.value,
+            checked: todo.checked,
+            priority: proto::Priority::from(todo.priority).into(),
+            tags: todo.tags,
+            due_at: todo.due_at.map(timestamp),
+            created_at: todo.created_at.map(timestamp),
+            updated_at: todo.updated_at.map(timestamp),
+            completed_at: todo.completed_at.map(timestamp),
+            deleted_at: todo.deleted_at.map(timestamp),
+        }
+    }
+}
+
+/// Server maintained timestamps are ignored like in REST requests.
+impl TryFrom<proto::Todo> for Todo {
+    type Error = ErrorResponse;
+
+    fn try_from(todo: proto::Todo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: TodoId(todo.id),
+            value: todo.value,
+            checked: todo.checked,
+            priority: priority(todo.priority)?,
+            tags: todo.tags,
+            due_at: todo.due_at.map(date_time).transpose()?,
+            created_at: None,
+            updated_at: None,
+            completed_at: None,
+            deleted_at: None,
+        })
+    }
+}
+
+impl TryFrom<proto::UpdateRequest> for TodoUpdateRequest {
+    type Error = ErrorResponse;
+
+    fn try_from(update: proto::UpdateRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            value: update.value,
+            checked: update.checked,
+            priority: update.priority.map(priority).transpose()?,
+            tags: update.tags.map(|tags| tags.tags),
+            due_at: match (update.clear_due_at, update.due_at) {
+                (true, _) => Some(None),
+                (false, due_at) => due_at.map(date_time).transpose()?.map(Some),
+            },
+        })
+    }
+}
+
+impl From<Highlight> for proto::Highlight {
+    fn from(highlight: Highlight) -> Self {
+        Self {
+            start: highlight.start as u64,
+            end: highlight.end as u64,
+        }
+    }
+}
+
+impl From<SearchHit> for proto::SearchHit {
+    fn from(hit: SearchHit) -> Self {
+        Self {
+            score: hit.score,
+            highlights: hit.highlights.into_iter().map(Into::into).collect(),
+            todo: Some(hit.todo.into()),
+        }
+    }
+}
+
+impl From<Operation> for proto::Operation {
+    fn from(operation: Operation) -> Self {
+        match operation {
+            Operation::Create => Self::Create,
+            Operation::Update => Self::Update,
+            Operation::Delete => Self::Delete,
+            Operation::Restore => Self::Restore,
+            Operation::Revert => Self::Revert,
+            Operation::Import => Self::Import,
+            Operation::Purge => Self::Purge,
+        }
+    }
+}
+
+impl From<AuditEntry> for proto::WatchEvent {
+    fn from(entry: AuditEntry) -> Self {
+        Self {
+            id: entry.id,
+            timestamp: Some(timestamp(entry.timestamp)),
+            operation: proto::Operation::from(entry.operation).into(),
+            todo_id: entry.todo_id.0,
+            actor: entry.actor,
+            before: entry.before.map(Into::into),
+            after: entry.after.map(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use actix_web::{http::StatusCode, test, App};
+    use tonic::{metadata::MetadataValue, transport::Channel, Code};
+
+    use super::{
+        proto::{self, todo_service_client::TodoServiceClient},
+        *,
+    };
+    use crate::{
+        audit,
+        rate_limit::{Quota, RouteQuotas, TrustedProxies},
+        todo,
+        version::Deprecation,
+    };
+
+    const API_KEY: &str = "utoipa-rocks";
+
+    fn with_api_key<T>(message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request
+            .metadata_mut()
+            .insert(API_KEY_NAME, MetadataValue::from_static(API_KEY));
+        request
+    }
+
+    fn todos(rate_limit: Quota) -> Todos {
+        Todos {
+            store: Data::new(TodoStore::default()),
+            audit: Data::new(AuditLog::in_memory(NonZeroUsize::new(100).unwrap())),
+            api_keys: Data::new(ApiKeys::default()),
+            client_subjects: ClientSubjects::default(),
+            rate_limiter: Data::new(RateLimiter::new(
+                rate_limit,
+                RouteQuotas::default(),
+                TrustedProxies::default(),
+            )),
+            shutdown: Shutdown::default(),
+        }
+    }
+
+    /// Serve the gRPC interface of `todos` on a free local port and connect to it.
+    async fn grpc_client(todos: Todos) -> (TodoServiceClient<Channel>, GrpcServer) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = spawn(listener, todos, None).unwrap();
+
+        let client = TodoServiceClient::connect(format!("http://{address}"))
+            .await
+            .unwrap();
+        (client, server)
+    }
+
+    #[actix_web::test]
+    async fn rest_and_grpc_share_store() {
+        let todos = todos(Quota::Unlimited);
+        let rest = test::init_service(
+            App::new()
+                .app_data(todos.api_keys.clone())
+                .configure(todo::configure(
+                    todos.store.clone(),
+                    Deprecation {
+                        deprecated_at: DateTime::UNIX_EPOCH,
+                        sunset_at: DateTime::UNIX_EPOCH,
+                    },
+                ))
+                .configure(audit::configure(todos.audit.clone())),
+        )
+        .await;
+        let (mut grpc, _server) = grpc_client(todos).await;
+
+        let response = test::TestRequest::post()
+            .uri("/todo")
+            .insert_header((API_KEY_NAME, API_KEY))
+            .set_json(serde_json::json!({"id": 1, "value": "Buy milk", "checked": false}))
+            .send_request(&rest)
+            .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let todo = grpc
+            .get(with_api_key(proto::GetRequest { id: 1, at: None }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(todo.value, "Buy milk");
+        assert_eq!(todo.priority(), proto::Priority::Normal);
+        let anonymous = grpc
+            .get(proto::GetRequest { id: 1, at: None })
+            .await
+            .unwrap_err();
+        assert_eq!(anonymous.code(), Code::Unauthenticated);
+
+        let mut changes = grpc
+            .watch(with_api_key(proto::WatchRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        let response = test::TestRequest::put()
+            .uri("/todo/1")
+            .insert_header((API_KEY_NAME, API_KEY))
+            .set_json(serde_json::json!({"checked": true}))
+            .send_request(&rest)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let change = changes.next().await.unwrap().unwrap();
+        assert_eq!(change.operation(), proto::Operation::Update);
+        assert_eq!(change.todo_id, 1);
+        assert!(change.after.is_some_and(|todo| todo.checked));
+
+        let conflict = grpc
+            .create(with_api_key(proto::CreateRequest {
+                todo: Some(proto::Todo {
+                    id: 1,
+                    value: String::from("Buy bread"),
+                    ..proto::Todo::default()
+                }),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(conflict.code(), Code::AlreadyExists);
+
+        let hits = grpc
+            .search(with_api_key(proto::SearchRequest {
+                value: String::from("milk"),
+                ..proto::SearchRequest::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .hits;
+        assert_eq!(hits.len(), 1);
+
+        let unauthenticated = grpc
+            .delete(proto::DeleteRequest { id: 1 })
+            .await
+            .unwrap_err();
+        assert_eq!(unauthenticated.code(), Code::Unauthenticated);
+        grpc.delete(with_api_key(proto::DeleteRequest { id: 1 }))
+            .await
+            .unwrap();
+        let change = changes.next().await.unwrap().unwrap();
+        assert_eq!(change.operation(), proto::Operation::Delete);
+
+        let response = test::TestRequest::get()
+            .uri("/todo/1")
+            .insert_header((API_KEY_NAME, API_KEY))
+            .send_request(&rest)
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn calls_are_rate_limited() {
+        let (mut grpc, _server) = grpc_client(todos(Quota::Limited {
+            requests: 2,
+            period: Duration::from_secs(60),
+        }))
+        .await;
+
+        for _ in 0..2 {
+            grpc.list(with_api_key(proto::ListRequest::default()))
+                .await
+                .unwrap();
+        }
+        let limited = grpc
+            .list(with_api_key(proto::ListRequest::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(limited.code(), Code::ResourceExhausted);
+        assert_eq!(limited.metadata().get("ratelimit-remaining").unwrap(), "0");
+        assert_eq!(limited.metadata().get("retry-after").unwrap(), "30");
+
+        // Methods have their own quota.
+        grpc.search(with_api_key(proto::SearchRequest::default()))
+            .await
+            .unwrap();
+        // Callers without api key are limited by their address.
+        let unauthenticated = grpc.list(proto::ListRequest::default()).await.unwrap_err();
+        assert_eq!(unauthenticated.code(), Code::Unauthenticated);
+    }
+
+    #[actix_web::test]
+    async fn shutdown_ends_watch_streams() {
+        let (mut grpc, server) = grpc_client(todos(Quota::Unlimited)).await;
+        let mut changes = grpc
+            .watch(with_api_key(proto::WatchRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+
+        // The watch stream never finishes by itself, it must not hold up the shutdown.
+        let started = std::time::Instant::now();
+        server.shutdown(Duration::from_secs(10)).await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let status = changes.next().await.unwrap().unwrap_err();
+        assert_eq!(status.code(), Code::Unavailable);
+        assert!(grpc
+            .list(with_api_key(proto::ListRequest::default()))
+            .await
+            .is_err());
+    }
+
+    #[actix_web::test]
+    async fn shutdown_without_calls_in_flight_is_immediate() {
+        let (mut grpc, server) = grpc_client(todos(Quota::Unlimited)).await;
+        grpc.list(with_api_key(proto::ListRequest::default()))
+            .await
+            .unwrap();
+        drop(grpc);
+
+        let started = std::time::Instant::now();
+        server.shutdown(Duration::from_secs(10)).await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
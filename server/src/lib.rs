@@ -0,0 +1,475 @@
+use std::{
+    future::{self, Ready},
+    io,
+    net::{Ipv4Addr, TcpListener},
+    time::Duration,
+};
+
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceFactory, ServiceRequest, ServiceResponse, Transform},
+    middleware::Compress,
+    web::Data,
+    App, HttpResponse, HttpServer,
+};
+use futures::future::LocalBoxFuture;
+use utoipa::{
+    openapi::{
+        security::{ApiKey, ApiKeyValue, SecurityRequirement, SecurityScheme},
+        ContentBuilder, Ref, ResponseBuilder,
+    },
+    Modify, OpenApi,
+};
+use utoipa_rapidoc::RapiDoc;
+use utoipa_redoc::{Redoc, Servable};
+use utoipa_swagger_ui::{SwaggerUi, Url};
+
+use crate::{
+    audit::{AuditFile, AuditLog},
+    auth::ApiKeys,
+    config::Config,
+    graphql::TodoSchema,
+    metrics::{Metrics, RecordMetrics},
+    ops::OpsState,
+    rate_limit::{RateLimit, RateLimitAddon, RateLimiter},
+    representation::{Negotiate, RepresentationAddon},
+    security::{BodyLimits, SecurityHeaders},
+    snapshot::Snapshots,
+    telemetry::{RequestIdAddon, RequestTracing},
+    todo::{ErrorResponse, TodoStore},
+    version::ApiVersion,
+};
+
+mod audit;
+mod auth;
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench;
+mod config;
+#[cfg(test)]
+mod contract;
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzz;
+mod graphql;
+mod grpc;
+mod metrics;
+mod ops;
+mod rate_limit;
+mod representation;
+mod search;
+mod security;
+mod snapshot;
+pub mod store;
+mod telemetry;
+mod tls;
+mod todo;
+mod version;
+
+const API_KEY_NAME: &str = "todo_apikey";
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        todo::get_todos,
+        todo::create_todo,
+        todo::delete_todo,
+        todo::get_todo_by_id,
+        todo::update_todo,
+        todo::get_trash,
+        todo::restore_todo,
+        todo::get_revisions,
+        todo::revert_todo,
+        todo::search_todos,
+        todo::export_todos,
+        todo::import_todos,
+        audit::get_audit,
+        ops::healthz,
+        ops::readyz,
+        ops::version
+    ),
+    components(
+        schemas(todo::Todo, todo::TodoId, todo::TodoUpdateRequest, todo::Priority, todo::ErrorResponse),
+        schemas(todo::Revision, todo::SearchHit, search::Highlight),
+        schemas(todo::TransferFormat, todo::ConflictPolicy, todo::ImportReport),
+        schemas(audit::Operation, audit::AuditEntry, audit::AuditPage),
+        schemas(ops::Health, ops::Readiness, ops::VersionInfo)
+    ),
+    tags(
+        (name = "todo", description = "Todo management endpoints."),
+        (name = "audit", description = "Audit trail of todo mutations."),
+        (name = "ops", description = "Health, readiness and build information endpoints.")
+    ),
+    modifiers(
+        &SecurityAddon,
+        &RateLimitAddon,
+        &RepresentationAddon,
+        &RequestIdAddon
+    )
+)]
+struct ApiDoc;
+
+/// Operations of the first version which differ from the current version, replacing them in
+/// the `v1` document.
+#[derive(OpenApi)]
+#[openapi(
+    paths(todo::search_todos_v1),
+    components(schemas(todo::Todo, todo::ErrorResponse)),
+    modifiers(
+        &SecurityAddon,
+        &RateLimitAddon,
+        &RepresentationAddon,
+        &RequestIdAddon
+    )
+)]
+struct ApiDocV1;
+
+/// Registers the `api_key` security scheme and requires it on every todo operation, as todo
+/// lists belong to the tenant of an api key.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.as_mut().unwrap(); // we can unwrap safely since there already is components registered.
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("todo_apikey"))),
+        );
+
+        let unauthorized = ResponseBuilder::new()
+            .description("Missing or incorrect api key")
+            .content(
+                "application/json",
+                ContentBuilder::new()
+                    .schema(Ref::from_schema_name("ErrorResponse"))
+                    .example(Some(
+                        serde_json::to_value(ErrorResponse::Unauthorized(String::from(
+                            "missing api key",
+                        )))
+                        .unwrap(),
+                    ))
+                    .build(),
+            )
+            .build();
+        for operation in openapi
+            .paths
+            .paths
+            .values_mut()
+            .flat_map(|path_item| path_item.operations.values_mut())
+            .filter(|operation| {
+                operation
+                    .tags
+                    .as_ref()
+                    .is_some_and(|tags| tags.iter().any(|tag| tag == "todo"))
+            })
+        {
+            operation.security.get_or_insert_with(|| {
+                vec![SecurityRequirement::new("api_key", Vec::<String>::new())]
+            });
+            operation
+                .responses
+                .responses
+                .entry(String::from("401"))
+                .or_insert_with(|| unauthorized.clone().into());
+        }
+    }
+}
+
+/// State shared by the workers serving the api.
+#[derive(Clone)]
+struct Api {
+    config: Config,
+    /// OpenAPI document of every version, the current one first.
+    documents: Vec<(ApiVersion, utoipa::openapi::OpenApi)>,
+    store: Data<TodoStore>,
+    audit: Data<AuditLog>,
+    metrics: Data<Metrics>,
+    rate_limiter: Data<RateLimiter>,
+    api_keys: Data<ApiKeys>,
+    ops_state: Data<OpsState>,
+    schema: TodoSchema,
+    /// Whether the api is served over TLS.
+    hsts: bool,
+}
+
+impl Api {
+    fn new(
+        config: Config,
+        store: Data<TodoStore>,
+        audit: Data<AuditLog>,
+        spec_generated: bool,
+        hsts: bool,
+    ) -> Self {
+        Self {
+            metrics: Data::new(Metrics::default()),
+            rate_limiter: Data::new(RateLimiter::new(
+                config.rate_limit,
+                config.route_rate_limits.clone(),
+                config.trusted_proxies.clone(),
+            )),
+            api_keys: Data::new(config.api_keys.clone()),
+            ops_state: Data::new(OpsState {
+                spec_generated,
+                api_version: ApiDoc::openapi().info.version,
+            }),
+            schema: graphql::schema(store.clone(), audit.clone(), &config),
+            documents: ApiVersion::ALL
+                .into_iter()
+                .map(|version| (version, version.openapi()))
+                .collect(),
+            config,
+            store,
+            audit,
+            hsts,
+        }
+    }
+
+    /// Application serving the api, called on each worker thread.
+    fn app(
+        &self,
+    ) -> App<
+        impl ServiceFactory<
+            ServiceRequest,
+            Config = (),
+            Response = ServiceResponse<impl MessageBody>,
+            Error = actix_web::Error,
+            InitError = (),
+        >,
+    > {
+        let body_limits = BodyLimits {
+            json: self.config.json_limit,
+            import: self.config.import_limit,
+        };
+        let (_, current) = &self.documents[0];
+        // Swagger UI lists the document of every version and serves them for the other UIs.
+        let urls = self
+            .documents
+            .iter()
+            .map(|(version, openapi)| {
+                (
+                    Url::new(version.name(), version.document_path()),
+                    openapi.clone(),
+                )
+            })
+            .collect();
+
+        App::new()
+            .wrap(RateLimit(self.rate_limiter.clone()))
+            .wrap(security::cors(&self.config))
+            .wrap(SecurityHeaders { hsts: self.hsts })
+            .wrap(Negotiate)
+            .wrap(Compress::default())
+            .wrap(RecordMetrics(self.metrics.clone()))
+            .wrap(RequestTracing)
+            .app_data(self.api_keys.clone())
+            .app_data(Data::new(body_limits))
+            .app_data(body_limits.json_config())
+            .configure(todo::configure(
+                self.store.clone(),
+                self.config.v1_deprecation,
+            ))
+            .configure(audit::configure(self.audit.clone()))
+            .configure(metrics::configure(self.metrics.clone()))
+            .configure(ops::configure(self.ops_state.clone()))
+            .configure(graphql::configure(
+                self.schema.clone(),
+                self.config.graphql_max_batch,
+            ))
+            .service(Redoc::with_url("/redoc", current.clone()))
+            .service(SwaggerUi::new("/swagger-ui/{_:.*}").urls(urls))
+            // There is no need to create RapiDoc::with_openapi because the OpenApi is served
+            // via SwaggerUi instead we only make rapidoc to point to the existing doc.
+            .service(RapiDoc::new(ApiVersion::V2.document_path()).path("/rapidoc"))
+        // Alternative to above
+        // .service(RapiDoc::with_openapi("/api-docs/openapi2.json", openapi.clone()).path("/rapidoc"))
+    }
+}
+
+/// Configure the server from the environment and serve the api until it is shut down.
+pub async fn run() -> io::Result<()> {
+    let config = Config::from_env();
+    let _telemetry = telemetry::init(&config)?;
+
+    let snapshots = config
+        .snapshot_path
+        .clone()
+        .map(|path| Data::new(Snapshots::new(path, config.snapshot_format)));
+    let store = match &snapshots {
+        Some(snapshots) => Data::new(TodoStore::from(snapshots.load()?)),
+        None => Data::new(TodoStore::default()),
+    };
+    let audit = Data::new(match config.audit_path.clone() {
+        Some(path) => AuditLog::open(
+            AuditFile::open(path, config.audit_max_bytes, config.audit_max_files)?,
+            config.audit_capacity,
+        )?,
+        None => AuditLog::in_memory(config.audit_capacity),
+    });
+    // Generate the OpenAPI doc of every version on startup.
+    let spec_generated = ApiVersion::ALL.into_iter().all(|version| {
+        let directory = format!("../docs/{}", version.name());
+        let written = std::fs::create_dir_all(&directory).and_then(|()| {
+            std::fs::write(
+                format!("{directory}/openapi.json"),
+                version.openapi().to_pretty_json().unwrap(),
+            )
+        });
+        match written {
+            Ok(()) => true,
+            Err(error) => {
+                tracing::error!(%error, version = version.name(), "failed to write OpenAPI document");
+                false
+            }
+        }
+    });
+
+    let periodic_snapshots = snapshots
+        .clone()
+        .zip(config.snapshot_interval)
+        .map(|(snapshots, interval)| snapshot::spawn_periodic(snapshots, store.clone(), interval));
+    let trash_purge = todo::spawn_purge(
+        store.clone(),
+        audit.clone(),
+        config.trash_retention,
+        config.trash_purge_interval,
+    );
+
+    let tls = tls::server_config(&config)?;
+    let certificate_reload = tls
+        .as_ref()
+        .map(|(_, certificate)| tls::spawn_reload(certificate.clone(), config.tls_reload_interval));
+
+    let api = Api::new(
+        config.clone(),
+        store.clone(),
+        audit.clone(),
+        spec_generated,
+        tls.is_some(),
+    );
+
+    let grpc = grpc::spawn(
+        TcpListener::bind((Ipv4Addr::UNSPECIFIED, config.grpc_port))?,
+        grpc::Todos {
+            store: store.clone(),
+            audit: audit.clone(),
+            api_keys: api.api_keys.clone(),
+            client_subjects: config.client_subjects.clone(),
+            rate_limiter: api.rate_limiter.clone(),
+            shutdown: grpc::Shutdown::default(),
+        },
+        grpc::tls_config(&config)?,
+    )?;
+
+    // This factory closure is called on each worker thread independently.
+    let server = HttpServer::new(move || api.app())
+        .on_connect(tls::on_connect(config.client_subjects.clone()))
+        .shutdown_timeout(config.shutdown_timeout);
+    let address = (Ipv4Addr::UNSPECIFIED, 8080);
+    let server = match tls {
+        Some((server_config, _)) => server.bind_rustls_0_23(address, server_config)?,
+        None => server.bind(address)?,
+    };
+    let result = server.run().await;
+
+    grpc.shutdown(Duration::from_secs(config.shutdown_timeout))
+        .await;
+    trash_purge.abort();
+    if let Some(handle) = certificate_reload {
+        handle.abort();
+    }
+    // In-flight requests are drained by now, take the final snapshot of the store.
+    if let Some(handle) = periodic_snapshots {
+        handle.abort();
+    }
+    if let Some(snapshots) = snapshots {
+        snapshots.save_store(&store);
+    }
+    audit.close();
+
+    result
+}
+
+/// Require api key middleware will actually require valid api key
+struct RequireApiKey;
+
+impl<S> Transform<S, ServiceRequest> for RequireApiKey
+where
+    S: Service<
+        ServiceRequest,
+        Response = ServiceResponse<actix_web::body::BoxBody>,
+        Error = actix_web::Error,
+    >,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<actix_web::body::BoxBody>;
+    type Error = actix_web::Error;
+    type Transform = ApiKeyMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        future::ready(Ok(ApiKeyMiddleware { service }))
+    }
+}
+
+struct ApiKeyMiddleware<S> {
+    service: S,
+}
+
+impl<S> Service<ServiceRequest> for ApiKeyMiddleware<S>
+where
+    S: Service<
+        ServiceRequest,
+        Response = ServiceResponse<actix_web::body::BoxBody>,
+        Error = actix_web::Error,
+    >,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<actix_web::body::BoxBody>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, actix_web::Error>>;
+
+    fn poll_ready(
+        &self,
+        ctx: &mut core::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let response = |req: ServiceRequest, response: HttpResponse| -> Self::Future {
+            Box::pin(async { Ok(req.into_response(response)) })
+        };
+
+        match auth::authenticate(req.request()) {
+            Ok(principal) => {
+                tracing::debug!(
+                    key_id = principal.key_id,
+                    tenant = %principal.tenant,
+                    "credentials accepted"
+                );
+            }
+            Err(reason) => {
+                let client = req
+                    .connection_info()
+                    .realip_remote_addr()
+                    .unwrap_or("unknown")
+                    .to_owned();
+                tracing::warn!(reason, client, "api key check failed");
+                return response(
+                    req,
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::Unauthorized(String::from(reason))),
+                );
+            }
+        }
+
+        let future = self.service.call(req);
+
+        Box::pin(async move {
+            let response = future.await?;
+
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    future::{self, Ready},
+    time::Instant,
+};
+
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    get,
+    web::{Data, ServiceConfig},
+    HttpResponse, Responder,
+};
+use futures::future::LocalBoxFuture;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+
+use crate::todo::TodoStore;
+
+/// Prometheus metrics of the server.
+pub(super) struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    latency: HistogramVec,
+    store_size: IntGauge,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        let registry = Registry::new();
+        let requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "Number of handled HTTP requests."),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let latency = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Latency of handled HTTP requests in seconds.",
+            ),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let store_size = IntGauge::new("todo_store_size", "Number of todos in the store.").unwrap();
+
+        registry.register(Box::new(requests.clone())).unwrap();
+        registry.register(Box::new(latency.clone())).unwrap();
+        registry.register(Box::new(store_size.clone())).unwrap();
+
+        Self {
+            registry,
+            requests,
+            latency,
+            store_size,
+        }
+    }
+}
+
+impl Metrics {
+    fn observe(&self, method: &str, route: &str, status: u16, seconds: f64) {
+        let status = status.to_string();
+        let labels = [method, route, status.as_str()];
+        self.requests.with_label_values(&labels).inc();
+        self.latency.with_label_values(&labels).observe(seconds);
+    }
+}
+
+pub(super) fn configure(metrics: Data<Metrics>) -> impl FnOnce(&mut ServiceConfig) {
+    |config: &mut ServiceConfig| {
+        config.app_data(metrics).service(export_metrics);
+    }
+}
+
+/// Export metrics in Prometheus text format.
+#[get("/metrics")]
+async fn export_metrics(metrics: Data<Metrics>, todo_store: Data<TodoStore>) -> impl Responder {
+    metrics.store_size.set(todo_store.len() as i64);
+
+    let mut buffer = Vec::new();
+    let encoder = TextEncoder::new();
+    encoder
+        .encode(&metrics.registry.gather(), &mut buffer)
+        .unwrap();
+
+    HttpResponse::Ok()
+        .content_type(encoder.format_type())
+        .body(buffer)
+}
+
+/// Record metrics middleware counts requests and measures their latency per route and status.
+pub(super) struct RecordMetrics(pub(super) Data<Metrics>);
+
+impl<S, B> Transform<S, ServiceRequest> for RecordMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = RecordMetricsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        future::ready(Ok(RecordMetricsMiddleware {
+            service,
+            metrics: self.0.clone(),
+        }))
+    }
+}
+
+pub(super) struct RecordMetricsMiddleware<S> {
+    service: S,
+    metrics: Data<Metrics>,
+}
+
+impl<S, B> Service<ServiceRequest> for RecordMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, actix_web::Error>>;
+
+    fn poll_ready(
+        &self,
+        ctx: &mut core::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let method = req.method().to_string();
+        let metrics = self.metrics.clone();
+        let start = Instant::now();
+        let future = self.service.call(req);
+
+        Box::pin(async move {
+            let response = future.await?;
+            // Label by route pattern rather than path to keep the label cardinality bounded.
+            let route = response
+                .request()
+                .match_pattern()
+                .unwrap_or_else(|| String::from("unmatched"));
+            metrics.observe(
+                &method,
+                &route,
+                response.status().as_u16(),
+                start.elapsed().as_secs_f64(),
+            );
+
+            Ok(response)
+        })
+    }
+}
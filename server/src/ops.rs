@@ -0,0 +1,131 @@
+use actix_web::{
+    get,
+    web::{Data, ServiceConfig},
+    HttpResponse, Responder,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::todo::TodoStore;
+
+/// State the operational endpoints report on.
+pub(super) struct OpsState {
+    /// Whether the OpenAPI document was written on startup.
+    pub(super) spec_generated: bool,
+    /// `info.version` of the served OpenAPI document.
+    pub(super) api_version: String,
+}
+
+pub(super) fn configure(state: Data<OpsState>) -> impl FnOnce(&mut ServiceConfig) {
+    |config: &mut ServiceConfig| {
+        config
+            .app_data(state)
+            .service(healthz)
+            .service(readyz)
+            .service(version);
+    }
+}
+
+/// Liveness of the server.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub(super) struct Health {
+    /// Always `ok` when the server is able to respond.
+    #[schema(example = "ok")]
+    status: String,
+}
+
+/// Readiness of the server and its dependencies.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub(super) struct Readiness {
+    /// Whether the server is ready to serve traffic.
+    ready: bool,
+    /// Whether the todo storage backend is usable.
+    storage: bool,
+    /// Whether the OpenAPI document was generated on startup.
+    openapi_spec: bool,
+}
+
+/// Build information of the running server.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub(super) struct VersionInfo {
+    /// Version of the server crate.
+    #[schema(example = "0.1.0")]
+    version: String,
+    /// Git commit the server was built from.
+    #[schema(example = "f4b6665")]
+    git_sha: String,
+    /// Commit time of the git commit in RFC 3339 format.
+    #[schema(example = "2023-11-18T12:00:00Z")]
+    commit_time: String,
+    /// Time the server was built in RFC 3339 format.
+    #[schema(example = "2023-11-20T08:30:00Z")]
+    build_time: String,
+    /// Version of the served API from `info.version` of the OpenAPI document.
+    #[schema(example = "0.1.0")]
+    api_version: String,
+}
+
+/// Check liveness of the server.
+///
+/// Returns 200 whenever the server is able to handle requests.
+#[utoipa::path(
+    tag = "ops",
+    responses(
+        (status = 200, description = "Server is alive", body = Health)
+    )
+)]
+#[get("/healthz")]
+pub(super) async fn healthz() -> impl Responder {
+    HttpResponse::Ok().json(Health {
+        status: String::from("ok"),
+    })
+}
+
+/// Check readiness of the server.
+///
+/// Returns 200 when the todo storage is usable and the OpenAPI document was generated on
+/// startup, 503 otherwise.
+#[utoipa::path(
+    tag = "ops",
+    responses(
+        (status = 200, description = "Server is ready to serve traffic", body = Readiness),
+        (status = 503, description = "Server is not ready to serve traffic", body = Readiness)
+    )
+)]
+#[get("/readyz")]
+pub(super) async fn readyz(state: Data<OpsState>, todo_store: Data<TodoStore>) -> impl Responder {
+    let storage = todo_store.is_available();
+    let openapi_spec = state.spec_generated;
+    let readiness = Readiness {
+        ready: storage && openapi_spec,
+        storage,
+        openapi_spec,
+    };
+
+    if readiness.ready {
+        HttpResponse::Ok().json(readiness)
+    } else {
+        HttpResponse::ServiceUnavailable().json(readiness)
+    }
+}
+
+/// Get build information.
+///
+/// Returns the server version, the git commit it was built from and its commit time, the
+/// time it was built and the API version.
+#[utoipa::path(
+    tag = "ops",
+    responses(
+        (status = 200, description = "Build information of the server", body = VersionInfo)
+    )
+)]
+#[get("/version")]
+pub(super) async fn version(state: Data<OpsState>) -> impl Responder {
+    HttpResponse::Ok().json(VersionInfo {
+        version: String::from(env!("CARGO_PKG_VERSION")),
+        git_sha: String::from(env!("GIT_SHA")),
+        commit_time: String::from(env!("COMMIT_TIME")),
+        build_time: String::from(env!("BUILD_TIME")),
+        api_version: state.api_version.clone(),
+    })
+}
@@ -0,0 +1,718 @@
+use std::{
+    collections::HashMap,
+    future::{self, Ready},
+    net::IpAddr,
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderMap, HeaderName, HeaderValue},
+    web::Data,
+    HttpResponse,
+};
+use futures::future::LocalBoxFuture;
+use tonic::Status;
+use utoipa::{
+    openapi::{
+        header::HeaderBuilder, ContentBuilder, ObjectBuilder, OpenApi, Ref, RefOr, ResponseBuilder,
+        SchemaType,
+    },
+    Modify,
+};
+
+use crate::{auth, todo::ErrorResponse, version::ApiVersion};
+
+const RATELIMIT_LIMIT: &str = "ratelimit-limit";
+const RATELIMIT_REMAINING: &str = "ratelimit-remaining";
+const RATELIMIT_RESET: &str = "ratelimit-reset";
+const RATELIMIT_POLICY: &str = "ratelimit-policy";
+const RETRY_AFTER: &str = "retry-after";
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// Operational endpoints which are never limited, so probes and metric scrapes are not
+/// rejected when their clients share an address with busy clients.
+const UNLIMITED_PATHS: [&str; 4] = ["/healthz", "/readyz", "/version", "/metrics"];
+
+/// Buckets are swept once there are this many, dropping the ones that refilled completely.
+const SWEEP_THRESHOLD: usize = 10_000;
+
+/// Number of requests a client may make per period.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Quota {
+    /// Requests are not limited.
+    Unlimited,
+    /// At most `requests` requests per `period`, all of which may be made in a burst.
+    Limited { requests: u32, period: Duration },
+}
+
+/// Parse `off` or `<requests>/<seconds>`.
+impl FromStr for Quota {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "off" {
+            return Ok(Self::Unlimited);
+        }
+
+        match s.split_once('/').map(|(requests, seconds)| {
+            (
+                requests.trim().parse::<u32>(),
+                seconds.trim().parse::<u64>(),
+            )
+        }) {
+            Some((Ok(requests), Ok(seconds))) if requests > 0 && seconds > 0 => Ok(Self::Limited {
+                requests,
+                period: Duration::from_secs(seconds),
+            }),
+            _ => Err(format!(
+                "expected quota `off` or `<requests>/<seconds>`, got `{s}`"
+            )),
+        }
+    }
+}
+
+/// Quotas of individual routes overriding the default quota, keyed by `<METHOD> <route>`.
+///
+/// Routes are named without their version prefix, so every version of a route and the
+/// unversioned `v1` route share a quota.
+#[derive(Clone, Debug, Default)]
+pub(super) struct RouteQuotas(HashMap<String, Quota>);
+
+/// Parse comma separated list of `<METHOD> <route>=<quota>` entries.
+impl FromStr for RouteQuotas {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(|entry| {
+                let (route, quota) = entry.trim().split_once('=').ok_or_else(|| {
+                    format!("expected route quota `<METHOD> <route>=<quota>`, got `{entry}`")
+                })?;
+                let (method, path) = route
+                    .trim()
+                    .split_once(' ')
+                    .ok_or_else(|| format!("expected route `<METHOD> <route>`, got `{route}`"))?;
+
+                Ok((
+                    format!(
+                        "{} {}",
+                        method.to_uppercase(),
+                        ApiVersion::unversioned(path.trim())
+                    ),
+                    quota.parse()?,
+                ))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// Addresses of reverse proxies whose `X-Forwarded-For` header is trusted to name the client.
+#[derive(Clone, Debug, Default)]
+pub(super) struct TrustedProxies(Vec<IpAddr>);
+
+impl TrustedProxies {
+    /// Address of the client of a connection from `peer` with `X-Forwarded-For` header
+    /// `forwarded_for`.
+    ///
+    /// Forwarded addresses are only used when `peer` is a trusted proxy. Each proxy appends
+    /// the address it received the request from, so the client is the last address which is
+    /// not a trusted proxy itself, anything before it could have been sent by the client.
+    fn client(&self, peer: IpAddr, forwarded_for: Option<&str>) -> IpAddr {
+        if !self.0.contains(&peer) {
+            return peer;
+        }
+        forwarded_for
+            .into_iter()
+            .flat_map(|addresses| addresses.rsplit(','))
+            .map_while(|address| address.trim().parse::<IpAddr>().ok())
+            .find(|address| !self.0.contains(address))
+            .unwrap_or(peer)
+    }
+}
+
+/// Parse comma separated list of IP addresses.
+impl FromStr for TrustedProxies {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .map(|address| {
+                address
+                    .parse()
+                    .map_err(|_| format!("expected trusted proxy IP address, got `{address}`"))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// Token bucket of a single client on a single route.
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Outcome of a rate limit check.
+#[derive(Debug, PartialEq)]
+struct Decision {
+    requests: u32,
+    period: Duration,
+    /// Requests left after this one, `None` when the request was rejected.
+    remaining: Option<u32>,
+    /// Seconds until the next request is allowed.
+    retry_after: u64,
+    /// Seconds until the bucket is full again.
+    reset: u64,
+}
+
+impl Decision {
+    /// Names and values of the headers reporting the decision, with `Retry-After` when the
+    /// request was rejected.
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = vec![
+            (RATELIMIT_LIMIT, self.requests.to_string()),
+            (
+                RATELIMIT_REMAINING,
+                self.remaining.unwrap_or_default().to_string(),
+            ),
+            (RATELIMIT_RESET, self.reset.to_string()),
+            (
+                RATELIMIT_POLICY,
+                format!("{};w={}", self.requests, self.period.as_secs()),
+            ),
+        ];
+        if self.remaining.is_none() {
+            headers.push((RETRY_AFTER, self.retry_after.to_string()));
+        }
+        headers
+    }
+
+    fn insert_headers(&self, headers: &mut HeaderMap) {
+        for (name, value) in self.headers() {
+            if let Ok(value) = HeaderValue::try_from(value) {
+                headers.insert(HeaderName::from_static(name), value);
+            }
+        }
+    }
+
+    /// Message of the error rejecting the request.
+    fn message(&self) -> String {
+        format!("rate limit exceeded, retry after {} s", self.retry_after)
+    }
+}
+
+/// Token bucket rate limiter keeping a bucket per route and client.
+///
+/// Buckets hold up to `requests` tokens and are refilled continuously at `requests` tokens
+/// per `period`, every request takes one token.
+pub(super) struct RateLimiter {
+    default: Quota,
+    routes: RouteQuotas,
+    trusted_proxies: TrustedProxies,
+    buckets: Mutex<HashMap<(String, String), Bucket>>,
+}
+
+impl RateLimiter {
+    pub(super) fn new(
+        default: Quota,
+        routes: RouteQuotas,
+        trusted_proxies: TrustedProxies,
+    ) -> Self {
+        Self {
+            default,
+            routes,
+            trusted_proxies,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token of `client` on `route`, `None` when the route is not limited.
+    fn check(&self, route: &str, client: &str) -> Option<Decision> {
+        self.check_at(route, client, Instant::now())
+    }
+
+    /// Client of a request made with the api key or client certificate `key_id`, or else from
+    /// `peer` with `X-Forwarded-For` header `forwarded_for`.
+    fn client(
+        &self,
+        key_id: Option<&str>,
+        peer: Option<IpAddr>,
+        forwarded_for: Option<&str>,
+    ) -> String {
+        match (key_id, peer) {
+            (Some(key_id), _) => format!("key:{key_id}"),
+            (None, Some(peer)) => {
+                format!("ip:{}", self.trusted_proxies.client(peer, forwarded_for))
+            }
+            (None, None) => String::from("ip:unknown"),
+        }
+    }
+
+    /// Take a token of the caller of gRPC method `path`, e.g. `/todo.v1.TodoService/List`,
+    /// failing with `RESOURCE_EXHAUSTED` and the `RateLimit-*` and `Retry-After` headers as
+    /// metadata when the quota is exceeded.
+    ///
+    /// gRPC calls are HTTP/2 `POST` requests, so the quota of a method is configured for route
+    /// `POST <path>`. Callers are told apart like REST clients.
+    // Calls fail with `Status` like the generated service methods.
+    #[allow(clippy::result_large_err)]
+    pub(super) fn check_grpc(
+        &self,
+        path: &str,
+        key_id: Option<&str>,
+        peer: Option<IpAddr>,
+        forwarded_for: Option<&str>,
+    ) -> Result<(), Status> {
+        let route = format!("POST {path}");
+        let client = self.client(key_id, peer, forwarded_for);
+        let Some(decision) = self.check(&route, &client) else {
+            return Ok(());
+        };
+        if decision.remaining.is_some() {
+            return Ok(());
+        }
+
+        tracing::warn!(route, client, "rate limit exceeded");
+        let mut status = Status::resource_exhausted(decision.message());
+        for (name, value) in decision.headers() {
+            if let Ok(value) = value.parse() {
+                status.metadata_mut().insert(name, value);
+            }
+        }
+        Err(status)
+    }
+
+    fn check_at(&self, route: &str, client: &str, now: Instant) -> Option<Decision> {
+        let Quota::Limited { requests, period } =
+            self.routes.0.get(route).copied().unwrap_or(self.default)
+        else {
+            return None;
+        };
+        let capacity = f64::from(requests);
+        let rate = capacity / period.as_secs_f64();
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        if buckets.len() >= SWEEP_THRESHOLD {
+            buckets.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.refilled_at).as_secs_f64() * rate
+                    < capacity
+            });
+        }
+        let bucket = buckets
+            .entry((route.to_owned(), client.to_owned()))
+            .or_insert(Bucket {
+                tokens: capacity,
+                refilled_at: now,
+            });
+        bucket.tokens = (bucket.tokens
+            + now.duration_since(bucket.refilled_at).as_secs_f64() * rate)
+            .min(capacity);
+        bucket.refilled_at = now;
+
+        let remaining = if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Some(bucket.tokens.floor() as u32)
+        } else {
+            None
+        };
+
+        Some(Decision {
+            requests,
+            period,
+            remaining,
+            retry_after: ((1.0 - bucket.tokens).max(0.0) / rate).ceil() as u64,
+            reset: ((capacity - bucket.tokens) / rate).ceil() as u64,
+        })
+    }
+}
+
+/// Rate limit middleware rejects requests exceeding the quota of their route with 429 and
+/// reports the quota in `RateLimit-*` headers.
+///
+/// Clients are told apart by the id of their api key or client certificate, or by their
+/// address when they did not authenticate. The address is the peer address of the connection,
+/// or the one forwarded by a [trusted proxy][TrustedProxies].
+pub(super) struct RateLimit(pub(super) Data<RateLimiter>);
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = RateLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        future::ready(Ok(RateLimitMiddleware {
+            service,
+            limiter: self.0.clone(),
+        }))
+    }
+}
+
+pub(super) struct RateLimitMiddleware<S> {
+    service: S,
+    limiter: Data<RateLimiter>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, actix_web::Error>>;
+
+    fn poll_ready(
+        &self,
+        ctx: &mut core::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let pattern = req.match_pattern();
+        if pattern
+            .as_deref()
+            .is_some_and(|pattern| UNLIMITED_PATHS.contains(&pattern))
+        {
+            let future = self.service.call(req);
+            return Box::pin(async move { Ok(future.await?.map_into_left_body()) });
+        }
+        // The versions of a route share a bucket, clients cannot multiply their quota by
+        // spreading requests over them.
+        let route = format!(
+            "{} {}",
+            req.method(),
+            pattern
+                .as_deref()
+                .map_or("unmatched", ApiVersion::unversioned)
+        );
+        let principal = auth::authenticate(req.request()).ok();
+        let client = self.limiter.client(
+            principal
+                .as_ref()
+                .map(|principal| principal.key_id.as_str()),
+            req.peer_addr().map(|peer| peer.ip()),
+            req.headers()
+                .get(X_FORWARDED_FOR)
+                .and_then(|value| value.to_str().ok()),
+        );
+
+        let Some(decision) = self.limiter.check(&route, &client) else {
+            let future = self.service.call(req);
+            return Box::pin(async move { Ok(future.await?.map_into_left_body()) });
+        };
+
+        if decision.remaining.is_none() {
+            tracing::warn!(route, client, "rate limit exceeded");
+            let mut response = HttpResponse::TooManyRequests()
+                .json(ErrorResponse::TooManyRequests(decision.message()));
+            decision.insert_headers(response.headers_mut());
+            return Box::pin(async move { Ok(req.into_response(response).map_into_right_body()) });
+        }
+
+        let future = self.service.call(req);
+        Box::pin(async move {
+            let mut response = future.await?;
+            decision.insert_headers(response.headers_mut());
+
+            Ok(response.map_into_left_body())
+        })
+    }
+}
+
+/// Documents the 429 response of the rate limiter and the headers reporting the quota on every
+/// response of the limited operations.
+pub(super) struct RateLimitAddon;
+
+impl Modify for RateLimitAddon {
+    fn modify(&self, openapi: &mut OpenApi) {
+        let header = |schema_type, description: &str| {
+            HeaderBuilder::new()
+                .schema(ObjectBuilder::new().schema_type(schema_type))
+                .description(Some(description))
+                .build()
+        };
+        let quota_headers = [
+            (
+                "RateLimit-Limit",
+                header(SchemaType::Integer, "Requests allowed per quota period."),
+            ),
+            (
+                "RateLimit-Remaining",
+                header(
+                    SchemaType::Integer,
+                    "Requests left in the current quota period.",
+                ),
+            ),
+            (
+                "RateLimit-Reset",
+                header(
+                    SchemaType::Integer,
+                    "Seconds until the quota is fully available again.",
+                ),
+            ),
+            (
+                "RateLimit-Policy",
+                header(
+                    SchemaType::String,
+                    "Quota of the route, e.g. `100;w=60` for 100 requests per 60 s.",
+                ),
+            ),
+        ];
+        let exceeded = ResponseBuilder::new()
+            .description("Rate limit of the route exceeded")
+            .header(
+                "Retry-After",
+                header(
+                    SchemaType::Integer,
+                    "Seconds until the next request is allowed.",
+                ),
+            )
+            .content(
+                "application/json",
+                ContentBuilder::new()
+                    .schema(Ref::from_schema_name("ErrorResponse"))
+                    .example(Some(
+                        serde_json::to_value(ErrorResponse::TooManyRequests(String::from(
+                            "rate limit exceeded, retry after 1 s",
+                        )))
+                        .unwrap(),
+                    ))
+                    .build(),
+            )
+            .build();
+
+        for operation in openapi
+            .paths
+            .paths
+            .iter_mut()
+            .filter(|(path, _)| !UNLIMITED_PATHS.contains(&path.as_str()))
+            .flat_map(|(_, path_item)| path_item.operations.values_mut())
+        {
+            let responses = &mut operation.responses.responses;
+            responses.insert(String::from("429"), exceeded.clone().into());
+            for response in responses.values_mut() {
+                if let RefOr::T(response) = response {
+                    response.headers.extend(
+                        quota_headers
+                            .iter()
+                            .map(|(name, header)| (String::from(*name), header.clone())),
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        http::StatusCode,
+        test::{call_service, init_service, TestRequest},
+        web, App,
+    };
+
+    use super::*;
+
+    const ROUTE: &str = "GET /todo";
+
+    fn limiter(requests: u32, seconds: u64) -> RateLimiter {
+        RateLimiter::new(
+            Quota::Limited {
+                requests,
+                period: Duration::from_secs(seconds),
+            },
+            RouteQuotas::default(),
+            TrustedProxies::default(),
+        )
+    }
+
+    #[test]
+    fn bucket_is_exhausted_and_refilled() {
+        let limiter = limiter(2, 60);
+        let start = Instant::now();
+        let remaining = |seconds| {
+            limiter
+                .check_at(ROUTE, "key:a", start + Duration::from_secs(seconds))
+                .unwrap()
+                .remaining
+        };
+
+        assert_eq!(remaining(0), Some(1));
+        assert_eq!(remaining(0), Some(0));
+        assert_eq!(remaining(0), None);
+        // Half the period refills one of the two tokens.
+        assert_eq!(remaining(30), Some(0));
+        assert_eq!(remaining(30), None);
+        // Other clients and routes have buckets of their own.
+        assert_eq!(
+            limiter.check_at(ROUTE, "key:b", start).unwrap().remaining,
+            Some(1)
+        );
+        assert_eq!(
+            limiter
+                .check_at("POST /v2/todo", "key:a", start)
+                .unwrap()
+                .remaining,
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn decision_headers() {
+        let limiter = limiter(2, 60);
+        let start = Instant::now();
+        let decision = limiter.check_at(ROUTE, "key:a", start).unwrap();
+        assert_eq!(
+            decision,
+            Decision {
+                requests: 2,
+                period: Duration::from_secs(60),
+                remaining: Some(1),
+                retry_after: 0,
+                reset: 30,
+            }
+        );
+        let mut headers = HeaderMap::new();
+        decision.insert_headers(&mut headers);
+        assert_eq!(headers.get(RATELIMIT_LIMIT).unwrap(), "2");
+        assert_eq!(headers.get(RATELIMIT_REMAINING).unwrap(), "1");
+        assert_eq!(headers.get(RATELIMIT_RESET).unwrap(), "30");
+        assert_eq!(headers.get(RATELIMIT_POLICY).unwrap(), "2;w=60");
+        assert!(!headers.contains_key(RETRY_AFTER));
+
+        limiter.check_at(ROUTE, "key:a", start);
+        let rejected = limiter.check_at(ROUTE, "key:a", start).unwrap();
+        let mut headers = HeaderMap::new();
+        rejected.insert_headers(&mut headers);
+        assert_eq!(headers.get(RATELIMIT_REMAINING).unwrap(), "0");
+        assert_eq!(headers.get(RATELIMIT_RESET).unwrap(), "60");
+        assert_eq!(headers.get(RETRY_AFTER).unwrap(), "30");
+    }
+
+    #[test]
+    fn unlimited_routes_are_not_checked() {
+        let limiter = RateLimiter::new(
+            Quota::Unlimited,
+            "GET /v2/todo=1/60".parse().unwrap(),
+            TrustedProxies::default(),
+        );
+        assert!(limiter.check("POST /todo", "key:a").is_none());
+        assert!(limiter.check(ROUTE, "key:a").is_some());
+    }
+
+    #[test]
+    fn quota_is_parsed() {
+        assert_eq!("off".parse(), Ok(Quota::Unlimited));
+        assert_eq!(
+            " 10 / 60 ".parse(),
+            Ok(Quota::Limited {
+                requests: 10,
+                period: Duration::from_secs(60)
+            })
+        );
+        for invalid in ["", "10", "0/60", "10/0", "-1/60", "ten/60"] {
+            assert!(invalid.parse::<Quota>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn route_quotas_are_parsed() {
+        let quotas = "get /v2/todo=10/60, POST /todo/import=off,"
+            .parse::<RouteQuotas>()
+            .unwrap();
+        assert_eq!(
+            quotas.0,
+            HashMap::from([
+                (
+                    String::from("GET /todo"),
+                    Quota::Limited {
+                        requests: 10,
+                        period: Duration::from_secs(60)
+                    }
+                ),
+                (String::from("POST /todo/import"), Quota::Unlimited),
+            ])
+        );
+        assert!("".parse::<RouteQuotas>().unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn malformed_route_quotas_are_rejected() {
+        for invalid in [
+            "GET /v2/todo",
+            "/v2/todo=10/60",
+            "GET /v2/todo=10",
+            "GET /v2/todo=10/60,POST",
+        ] {
+            assert!(invalid.parse::<RouteQuotas>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn forwarded_address_is_only_trusted_from_proxies() {
+        let proxies = "10.0.0.1, 10.0.0.2".parse::<TrustedProxies>().unwrap();
+        let address = |address: &str| address.parse::<IpAddr>().unwrap();
+
+        // Clients connecting directly cannot choose their address.
+        assert_eq!(
+            proxies.client(address("192.0.2.1"), Some("198.51.100.1")),
+            address("192.0.2.1")
+        );
+        // The address a trusted proxy received the request from is the client, addresses the
+        // client sent along are ignored.
+        assert_eq!(
+            proxies.client(address("10.0.0.1"), Some("198.51.100.1, 192.0.2.1")),
+            address("192.0.2.1")
+        );
+        assert_eq!(
+            proxies.client(address("10.0.0.1"), Some("192.0.2.1, 10.0.0.2")),
+            address("192.0.2.1")
+        );
+        assert_eq!(
+            proxies.client(address("10.0.0.1"), None),
+            address("10.0.0.1")
+        );
+        assert_eq!(
+            proxies.client(address("10.0.0.1"), Some("garbage")),
+            address("10.0.0.1")
+        );
+        assert!("10.0.0.1,proxy".parse::<TrustedProxies>().is_err());
+    }
+
+    #[actix_web::test]
+    async fn versions_share_a_bucket() {
+        let app = init_service(
+            App::new()
+                .wrap(RateLimit(Data::new(limiter(2, 60))))
+                .route("/v1/todo", web::get().to(HttpResponse::Ok))
+                .route("/v2/todo", web::get().to(HttpResponse::Ok))
+                .route("/todo", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+        let get = |uri| TestRequest::get().uri(uri).to_request();
+
+        let response = call_service(&app, get("/v1/todo")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = call_service(&app, get("/v2/todo")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = call_service(&app, get("/todo")).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
@@ -0,0 +1,426 @@
+//! Alternative binary representations of JSON responses negotiated through `Accept` header.
+
+use std::future::{self, Ready};
+
+use actix_web::{
+    body::{self, EitherBody, MessageBody},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{self, HeaderValue},
+    web::Bytes,
+};
+use futures::future::LocalBoxFuture;
+use utoipa::{
+    openapi::{header::HeaderBuilder, ObjectBuilder, OpenApi, RefOr, SchemaType},
+    Modify,
+};
+
+/// Representation of a response body.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Representation {
+    Json,
+    Cbor,
+    MessagePack,
+}
+
+impl Representation {
+    /// Binary representations every JSON response is also available in.
+    const BINARY: [Self; 2] = [Self::Cbor, Self::MessagePack];
+
+    fn media_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Cbor => "application/cbor",
+            Self::MessagePack => "application/msgpack",
+        }
+    }
+
+    /// Representation of a media range of `Accept` header. The wildcard ranges stand for JSON,
+    /// the representation of the handlers.
+    fn from_media_range(media_range: &str) -> Option<Self> {
+        match media_range.to_ascii_lowercase().as_str() {
+            "application/json" | "application/*" | "*/*" => Some(Self::Json),
+            "application/cbor" => Some(Self::Cbor),
+            "application/msgpack" | "application/x-msgpack" => Some(Self::MessagePack),
+            _ => None,
+        }
+    }
+
+    /// Representation with the highest quality in `Accept` header value, the first one listed
+    /// among equals.
+    ///
+    /// Defaults to JSON when none of the representations is acceptable instead of failing with
+    /// 406, which would break clients sending an unrelated `Accept` header but reading the JSON
+    /// the api has always responded with.
+    fn preferred(accept: &str) -> Self {
+        accept
+            .split(',')
+            .enumerate()
+            .filter_map(|(index, media_range)| {
+                let mut params = media_range.split(';');
+                let representation = Self::from_media_range(params.next()?.trim())?;
+                let quality = params
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |quality| quality.trim().parse::<f32>().ok())?;
+                (quality > 0.0).then_some((representation, quality, index))
+            })
+            .max_by(|(_, a, a_index), (_, b, b_index)| a.total_cmp(b).then(b_index.cmp(a_index)))
+            .map_or(Self::Json, |(representation, ..)| representation)
+    }
+
+    /// Encode JSON document `json` in this representation.
+    fn transcode(self, json: &[u8]) -> Result<Vec<u8>, String> {
+        let value =
+            serde_json::from_slice::<serde_json::Value>(json).map_err(|error| error.to_string())?;
+        let mut encoded = Vec::new();
+        match self {
+            Self::Json => encoded.extend_from_slice(json),
+            Self::Cbor => {
+                ciborium::into_writer(&value, &mut encoded).map_err(|error| error.to_string())?
+            }
+            Self::MessagePack => {
+                rmp_serde::encode::write(&mut encoded, &value).map_err(|error| error.to_string())?
+            }
+        }
+
+        Ok(encoded)
+    }
+}
+
+/// Negotiate middleware re-encodes JSON responses as CBOR or MessagePack when the request
+/// prefers one of them in its `Accept` header.
+///
+/// Handlers keep responding with JSON, so every JSON response including errors of other
+/// middleware is available in the binary representations. Every representation gets an
+/// `ETag` of its own, the responses are buffered in memory anyway.
+pub(super) struct Negotiate;
+
+impl<S, B> Transform<S, ServiceRequest> for Negotiate
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, Bytes>>;
+    type Error = actix_web::Error;
+    type Transform = NegotiateMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        future::ready(Ok(NegotiateMiddleware { service }))
+    }
+}
+
+pub(super) struct NegotiateMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for NegotiateMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, Bytes>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, actix_web::Error>>;
+
+    fn poll_ready(
+        &self,
+        ctx: &mut core::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let representation = req
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .map_or(Representation::Json, Representation::preferred);
+
+        let future = self.service.call(req);
+        Box::pin(async move {
+            let mut response = future.await?;
+            let is_json = response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.split(';').next())
+                .is_some_and(|media_type| media_type.trim() == "application/json");
+            if !is_json {
+                return Ok(response.map_into_left_body());
+            }
+
+            response
+                .headers_mut()
+                .append(header::VARY, HeaderValue::from_static("Accept"));
+
+            let (req, response) = response.into_parts();
+            let (mut response, body) = response.into_parts();
+            let json = body::to_bytes(body).await.map_err(|error| {
+                actix_web::error::ErrorInternalServerError(error.into().to_string())
+            })?;
+            let encoded = match representation {
+                Representation::Json => json,
+                _ => {
+                    let encoded = representation
+                        .transcode(&json)
+                        .map_err(actix_web::error::ErrorInternalServerError)?;
+                    response.headers_mut().insert(
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static(representation.media_type()),
+                    );
+                    Bytes::from(encoded)
+                }
+            };
+            response
+                .headers_mut()
+                .insert(header::ETAG, entity_tag(&encoded));
+
+            Ok(ServiceResponse::new(req, response.set_body(encoded)).map_into_right_body())
+        })
+    }
+}
+
+/// Weak entity tag of `body`, its 64-bit FNV-1a hash. Weak as [`Compress`] may change the
+/// encoding of the body afterwards.
+///
+/// [`Compress`]: actix_web::middleware::Compress
+fn entity_tag(body: &[u8]) -> HeaderValue {
+    let hash = body.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    HeaderValue::try_from(format!("W/\"{hash:016x}\"")).unwrap()
+}
+
+/// Documents the binary representations and `ETag` header next to every JSON response.
+pub(super) struct RepresentationAddon;
+
+impl Modify for RepresentationAddon {
+    fn modify(&self, openapi: &mut OpenApi) {
+        let responses = openapi
+            .paths
+            .paths
+            .values_mut()
+            .flat_map(|path_item| path_item.operations.values_mut())
+            .flat_map(|operation| operation.responses.responses.values_mut())
+            .filter_map(|response| match response {
+                RefOr::T(response) => Some(response),
+                RefOr::Ref(_) => None,
+            });
+
+        for response in responses {
+            let Some(json) = response.content.get(Representation::Json.media_type()) else {
+                continue;
+            };
+            let mut content = json.clone();
+            content.example = None;
+            content.examples.clear();
+            response.headers.insert(
+                String::from("ETag"),
+                HeaderBuilder::new()
+                    .schema(ObjectBuilder::new().schema_type(SchemaType::String))
+                    .description(Some("Weak entity tag of the representation of the body."))
+                    .build(),
+            );
+
+            for representation in Representation::BINARY {
+                response
+                    .content
+                    .insert(String::from(representation.media_type()), content.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        http::{header, StatusCode},
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+    use serde_json::{json, Value};
+
+    use super::{Negotiate, Representation};
+
+    #[test]
+    fn preferred_follows_quality() {
+        use Representation::*;
+
+        assert_eq!(Representation::preferred("application/cbor"), Cbor);
+        assert_eq!(
+            Representation::preferred("application/x-msgpack"),
+            MessagePack
+        );
+        assert_eq!(
+            Representation::preferred("application/json;q=0.5, application/cbor;q=0.8"),
+            Cbor
+        );
+        assert_eq!(
+            Representation::preferred("application/cbor; q=0.2, application/msgpack; q=0.9"),
+            MessagePack
+        );
+        // The first listed wins among equal qualities.
+        assert_eq!(
+            Representation::preferred("application/msgpack, application/cbor"),
+            MessagePack
+        );
+        // Zero quality means not acceptable.
+        assert_eq!(
+            Representation::preferred("application/cbor;q=0, application/msgpack;q=0.1"),
+            MessagePack
+        );
+        assert_eq!(Representation::preferred("Application/CBOR"), Cbor);
+    }
+
+    #[test]
+    fn wildcards_stand_for_json() {
+        use Representation::*;
+
+        assert_eq!(Representation::preferred("*/*"), Json);
+        assert_eq!(Representation::preferred("application/*"), Json);
+        assert_eq!(
+            Representation::preferred("application/cbor;q=0.5, */*;q=0.8"),
+            Json
+        );
+        assert_eq!(
+            Representation::preferred("*/*;q=0.1, application/msgpack"),
+            MessagePack
+        );
+    }
+
+    #[test]
+    fn unacceptable_falls_back_to_json() {
+        use Representation::*;
+
+        assert_eq!(Representation::preferred("text/html"), Json);
+        assert_eq!(Representation::preferred("application/cbor;q=0"), Json);
+        assert_eq!(Representation::preferred("application/cbor;q=high"), Json);
+        assert_eq!(Representation::preferred(""), Json);
+    }
+
+    fn body() -> Value {
+        json!({"id": 1, "value": "buy milk", "checked": false, "tags": ["shop"], "score": 1.5})
+    }
+
+    async fn negotiate(accept: &str) -> (StatusCode, Option<String>, Vec<u8>) {
+        let app = test::init_service(
+            App::new()
+                .wrap(Negotiate)
+                .route(
+                    "/json",
+                    web::get().to(|| async { HttpResponse::Ok().json(body()) }),
+                )
+                .route(
+                    "/csv",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/csv")
+                            .body("id,value\n")
+                    }),
+                ),
+        )
+        .await;
+        let request = TestRequest::get()
+            .uri(if accept == "text/csv" {
+                "/csv"
+            } else {
+                "/json"
+            })
+            .insert_header((header::ACCEPT, accept))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .map(|value| value.to_str().unwrap().to_owned());
+
+        (
+            response.status(),
+            content_type,
+            test::read_body(response).await.to_vec(),
+        )
+    }
+
+    #[actix_web::test]
+    async fn binary_representations_round_trip() {
+        let (status, content_type, encoded) = negotiate("application/cbor").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type.as_deref(), Some("application/cbor"));
+        let decoded: Value = ciborium::from_reader(encoded.as_slice()).unwrap();
+        assert_eq!(decoded, body());
+
+        let (status, content_type, encoded) = negotiate("application/msgpack").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type.as_deref(), Some("application/msgpack"));
+        let decoded: Value = rmp_serde::from_slice(&encoded).unwrap();
+        assert_eq!(decoded, body());
+    }
+
+    #[actix_web::test]
+    async fn unacceptable_responds_with_json_instead_of_406() {
+        let (status, content_type, body) = negotiate("text/html").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type.as_deref(), Some("application/json"));
+        assert_eq!(
+            serde_json::from_slice::<Value>(&body).unwrap(),
+            self::body()
+        );
+    }
+
+    #[actix_web::test]
+    async fn other_content_is_not_reencoded() {
+        let (status, content_type, body) = negotiate("text/csv").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type.as_deref(), Some("text/csv"));
+        assert_eq!(body, b"id,value\n");
+    }
+
+    #[actix_web::test]
+    async fn every_representation_is_tagged() {
+        let app = test::init_service(
+            App::new()
+                .wrap(Negotiate)
+                .route(
+                    "/json",
+                    web::get().to(|| async { HttpResponse::Ok().json(body()) }),
+                )
+                .route(
+                    "/csv",
+                    web::get()
+                        .to(|| async { HttpResponse::Ok().content_type("text/csv").body("") }),
+                ),
+        )
+        .await;
+        let mut entity_tags = Vec::new();
+        for (uri, accept) in [
+            ("/json", "application/json"),
+            ("/json", "*/*"),
+            ("/json", "application/cbor"),
+            ("/csv", "text/csv"),
+        ] {
+            let request = TestRequest::get()
+                .uri(uri)
+                .insert_header((header::ACCEPT, accept))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            entity_tags.push(
+                response
+                    .headers()
+                    .get(header::ETAG)
+                    .map(|value| value.to_str().unwrap().to_owned()),
+            );
+        }
+
+        let json = entity_tags[0].as_deref().unwrap();
+        assert!(json.starts_with("W/\""), "{json}");
+        assert_eq!(entity_tags[1].as_deref(), Some(json));
+        assert_ne!(entity_tags[2].as_deref(), Some(json));
+        assert!(entity_tags[2].is_some());
+        assert_eq!(entity_tags[3], None);
+    }
+}
@@ -0,0 +1,477 @@
+//! Full-text search index of todo values.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::Bound,
+    sync::Arc,
+};
+
+use parking_lot::RwLock;
+use rust_stemmers::{Algorithm, Stemmer};
+use serde::{Deserialize, Serialize};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use unicode_segmentation::UnicodeSegmentation;
+use utoipa::ToSchema;
+
+use crate::{auth::Tenant, todo::TodoId};
+
+/// Relative weight of a query word matching an indexed word exactly.
+const EXACT_WEIGHT: f64 = 1.0;
+/// Relative weight of a query word matching an indexed word with the same stem.
+const STEM_WEIGHT: f64 = 0.8;
+/// Relative weight of a query word matching the beginning of an indexed word.
+const PREFIX_WEIGHT: f64 = 0.6;
+/// Relative weight of a query word matching an indexed word within the typo tolerance.
+const FUZZY_WEIGHT: f64 = 0.5;
+
+/// Range of a matched word in a todo value, in bytes.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) struct Highlight {
+    /// Byte offset of the first byte of the match.
+    #[schema(example = 12)]
+    pub(super) start: usize,
+    /// Byte offset one past the last byte of the match.
+    #[schema(example = 15)]
+    pub(super) end: usize,
+}
+
+/// How query words are matched against the indexed words.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct SearchOptions {
+    /// Match words sharing their stem, e.g. `buying` for `buy`.
+    pub(super) stem: bool,
+    /// Match words within a few typos.
+    pub(super) fuzzy: bool,
+}
+
+/// Todo matching a search.
+#[derive(Clone, Debug)]
+pub(super) struct Match {
+    pub(super) id: TodoId,
+    pub(super) score: f64,
+    pub(super) highlights: Vec<Highlight>,
+}
+
+/// Word of a text, folded for matching.
+struct Token {
+    term: String,
+    highlight: Highlight,
+}
+
+/// Split `text` into words on Unicode word boundaries, case folded and stripped of diacritics.
+fn tokenize(text: &str) -> impl Iterator<Item = Token> + '_ {
+    text.unicode_word_indices().filter_map(|(start, word)| {
+        let term = caseless::default_case_fold_str(word)
+            .nfd()
+            .filter(|c| !is_combining_mark(*c))
+            .collect::<String>();
+        (!term.is_empty()).then(|| Token {
+            term,
+            highlight: Highlight {
+                start,
+                end: start + word.len(),
+            },
+        })
+    })
+}
+
+fn stem(term: &str) -> String {
+    Stemmer::create(Algorithm::English).stem(term).into_owned()
+}
+
+/// Number of typos tolerated in a query word, none for short words where a typo makes
+/// nearly anything match.
+fn max_typos(term: &str) -> usize {
+    match term.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Inverted index of the todos of a single tenant.
+#[derive(Default)]
+struct TermIndex {
+    /// Occurrences of every term by todo id.
+    postings: BTreeMap<String, BTreeMap<TodoId, Vec<Highlight>>>,
+    /// Distinct terms of every todo, to remove them again when the todo changes.
+    documents: HashMap<TodoId, Vec<String>>,
+    /// Terms by their stem.
+    stems: HashMap<String, BTreeSet<String>>,
+    /// Terms by their number of characters, so fuzzy matching only compares a query word with
+    /// the terms of about its length.
+    lengths: BTreeMap<usize, BTreeSet<String>>,
+}
+
+impl TermIndex {
+    fn insert(&mut self, id: TodoId, value: &str) {
+        self.remove(id);
+
+        let mut occurrences = BTreeMap::<String, Vec<Highlight>>::new();
+        for token in tokenize(value) {
+            occurrences
+                .entry(token.term)
+                .or_default()
+                .push(token.highlight);
+        }
+
+        let terms = occurrences.keys().cloned().collect();
+        for (term, highlights) in occurrences {
+            let postings = self.postings.entry(term.clone()).or_default();
+            if postings.is_empty() {
+                self.stems
+                    .entry(stem(&term))
+                    .or_default()
+                    .insert(term.clone());
+                self.lengths
+                    .entry(term.chars().count())
+                    .or_default()
+                    .insert(term);
+            }
+            postings.insert(id, highlights);
+        }
+        self.documents.insert(id, terms);
+    }
+
+    fn remove(&mut self, id: TodoId) {
+        for term in self.documents.remove(&id).unwrap_or_default() {
+            let Some(postings) = self.postings.get_mut(&term) else {
+                continue;
+            };
+            postings.remove(&id);
+            if postings.is_empty() {
+                self.postings.remove(&term);
+                let stem = stem(&term);
+                if let Some(terms) = self.stems.get_mut(&stem) {
+                    terms.remove(&term);
+                    if terms.is_empty() {
+                        self.stems.remove(&stem);
+                    }
+                }
+                let length = term.chars().count();
+                if let Some(terms) = self.lengths.get_mut(&length) {
+                    terms.remove(&term);
+                    if terms.is_empty() {
+                        self.lengths.remove(&length);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Indexed terms matching query `term` with the weight of the best way they match.
+    fn matching_terms(&self, term: &str, options: SearchOptions) -> HashMap<&str, f64> {
+        let mut terms = HashMap::new();
+        let mut add = |matched: &str, weight: f64| {
+            if let Some((matched, _)) = self.postings.get_key_value(matched) {
+                let best = terms.entry(matched.as_str()).or_insert(weight);
+                *best = f64::max(*best, weight);
+            }
+        };
+
+        add(term, EXACT_WEIGHT);
+        if options.stem {
+            for matched in self.stems.get(&stem(term)).into_iter().flatten() {
+                add(matched, STEM_WEIGHT);
+            }
+        }
+        for matched in self
+            .postings
+            .range::<str, _>((Bound::Included(term), Bound::Unbounded))
+            .map(|(matched, _)| matched)
+            .take_while(|matched| matched.starts_with(term))
+        {
+            add(matched, PREFIX_WEIGHT);
+        }
+        let typos = max_typos(term);
+        if options.fuzzy && typos > 0 {
+            // Terms whose length differs by more than the typo tolerance cannot match.
+            let length = term.chars().count();
+            for matched in self
+                .lengths
+                .range(length.saturating_sub(typos)..=length + typos)
+                .flat_map(|(_, terms)| terms)
+                .filter(|matched| strsim::damerau_levenshtein(term, matched) <= typos)
+            {
+                add(matched, FUZZY_WEIGHT);
+            }
+        }
+
+        terms
+    }
+
+    /// Todos containing a match of every word of `query`, best match first.
+    ///
+    /// Every matched word contributes its weight times its inverse document frequency and
+    /// the logarithm of its number of occurrences to the score.
+    fn search(&self, query: &[String], options: SearchOptions) -> Vec<Match> {
+        let documents = self.documents.len() as f64;
+        let mut matches: Option<HashMap<TodoId, Match>> = None;
+
+        for term in query {
+            let mut term_matches = HashMap::<TodoId, Match>::new();
+            for (matched, weight) in self.matching_terms(term, options) {
+                let postings = &self.postings[matched];
+                let idf = (1.0 + documents / postings.len() as f64).ln();
+                for (&id, highlights) in postings {
+                    let score = weight * idf * (1.0 + (highlights.len() as f64).ln());
+                    let term_match = term_matches.entry(id).or_insert(Match {
+                        id,
+                        score,
+                        highlights: Vec::new(),
+                    });
+                    term_match.score = term_match.score.max(score);
+                    term_match.highlights.extend(highlights);
+                }
+            }
+
+            matches = Some(match matches {
+                None => term_matches,
+                Some(mut matches) => {
+                    matches.retain(|id, _| term_matches.contains_key(id));
+                    for (id, found) in matches.iter_mut() {
+                        let term_match = &term_matches[id];
+                        found.score += term_match.score;
+                        found.highlights.extend(&term_match.highlights);
+                    }
+                    matches
+                }
+            });
+        }
+
+        let mut matches = matches
+            .unwrap_or_default()
+            .into_values()
+            .map(|mut found| {
+                found.highlights.sort();
+                found.highlights.dedup();
+                found
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+        matches
+    }
+}
+
+/// Full-text search index over the values of the todos of every tenant.
+///
+/// Every tenant has its own index behind its own lock. The index of a tenant must only be
+/// changed and read while holding the lock of the tenant's todo list, which keeps the two
+/// consistent and the locks always taken in the same order.
+#[derive(Default)]
+pub(super) struct SearchIndex {
+    tenants: RwLock<HashMap<Tenant, Arc<RwLock<TermIndex>>>>,
+}
+
+impl SearchIndex {
+    fn tenant(&self, tenant: &Tenant) -> Arc<RwLock<TermIndex>> {
+        if let Some(index) = self.tenants.read().get(tenant) {
+            return index.clone();
+        }
+
+        self.tenants
+            .write()
+            .entry(tenant.clone())
+            .or_default()
+            .clone()
+    }
+
+    /// Index `value` of todo `id`, replacing what was indexed for it before.
+    pub(super) fn insert(&self, tenant: &Tenant, id: TodoId, value: &str) {
+        self.tenant(tenant).write().insert(id, value);
+    }
+
+    /// Remove todo `id` from the index.
+    pub(super) fn remove(&self, tenant: &Tenant, id: TodoId) {
+        self.tenant(tenant).write().remove(id);
+    }
+
+    /// Replace the index of `tenant` with an index of `todos`, given as id and value.
+    pub(super) fn rebuild<'a>(
+        &self,
+        tenant: &Tenant,
+        todos: impl Iterator<Item = (TodoId, &'a str)>,
+    ) {
+        let mut index = TermIndex::default();
+        for (id, value) in todos {
+            index.insert(id, value);
+        }
+
+        *self.tenant(tenant).write() = index;
+    }
+
+    /// Todos of `tenant` matching every word of `query`, best match first, or `None` when
+    /// `query` contains no words.
+    pub(super) fn search(
+        &self,
+        tenant: &Tenant,
+        query: &str,
+        options: SearchOptions,
+    ) -> Option<Vec<Match>> {
+        let query = tokenize(query).map(|token| token.term).collect::<Vec<_>>();
+        if query.is_empty() {
+            return None;
+        }
+
+        Some(
+            self.tenants
+                .read()
+                .get(tenant)
+                .map(|index| index.read().search(&query, options))
+                .unwrap_or_default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tokenize, Highlight, SearchOptions, TermIndex};
+    use crate::todo::TodoId;
+
+    const EXACT: SearchOptions = SearchOptions {
+        stem: false,
+        fuzzy: false,
+    };
+
+    fn terms(text: &str) -> Vec<String> {
+        tokenize(text).map(|token| token.term).collect()
+    }
+
+    /// Ids of the todos of `index` matching `query`, best match first.
+    fn search(index: &TermIndex, query: &str, options: SearchOptions) -> Vec<i32> {
+        index
+            .search(&terms(query), options)
+            .into_iter()
+            .map(|found| found.id.0)
+            .collect()
+    }
+
+    fn index(values: &[&str]) -> TermIndex {
+        let mut index = TermIndex::default();
+        for (id, value) in values.iter().enumerate() {
+            index.insert(TodoId(id as i32 + 1), value);
+        }
+        index
+    }
+
+    #[test]
+    fn tokenize_splits_words_with_their_byte_ranges() {
+        let tokens = tokenize("Buy milk, then café!").collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.term.as_str())
+                .collect::<Vec<_>>(),
+            ["buy", "milk", "then", "cafe"]
+        );
+        assert_eq!(
+            tokens.last().unwrap().highlight,
+            Highlight { start: 15, end: 20 }
+        );
+        assert!(terms(" ,.!? ").is_empty());
+    }
+
+    #[test]
+    fn tokenize_folds_case_and_diacritics() {
+        assert_eq!(terms("Straße"), terms("strasse"));
+        assert_eq!(terms("CAFÉ"), terms("cafe"));
+        assert_eq!(terms("Ångström"), ["angstrom"]);
+    }
+
+    #[test]
+    fn matches_regardless_of_case_and_diacritics() {
+        let index = index(&["Straße fegen", "CAFÉ besuchen"]);
+
+        assert_eq!(search(&index, "strasse", EXACT), [1]);
+        assert_eq!(search(&index, "STRASSE", EXACT), [1]);
+        assert_eq!(search(&index, "cafe", EXACT), [2]);
+        assert_eq!(search(&index, "Café", EXACT), [2]);
+
+        let index = self::index(&["strasse fegen", "cafe besuchen"]);
+        assert_eq!(search(&index, "Straße", EXACT), [1]);
+        assert_eq!(search(&index, "CAFÉ", EXACT), [2]);
+    }
+
+    #[test]
+    fn every_query_word_has_to_match() {
+        let index = index(&["buy milk", "buy bread", "drink milk"]);
+
+        assert_eq!(search(&index, "milk buy", EXACT), [1]);
+        assert!(search(&index, "milk bread", EXACT).is_empty());
+    }
+
+    #[test]
+    fn matches_prefixes_below_exact_words() {
+        let index = index(&["groceries", "grocer"]);
+
+        assert_eq!(search(&index, "grocer", EXACT), [2, 1]);
+        assert_eq!(search(&index, "groc", EXACT), [1, 2]);
+    }
+
+    #[test]
+    fn stemming_matches_other_forms_when_enabled() {
+        let index = index(&["buying milk", "walked the dog"]);
+        let stem = SearchOptions {
+            stem: true,
+            fuzzy: false,
+        };
+
+        assert!(search(&index, "buys", EXACT).is_empty());
+        assert_eq!(search(&index, "buys", stem), [1]);
+        assert_eq!(search(&index, "walking", stem), [2]);
+    }
+
+    #[test]
+    fn fuzzy_matching_tolerates_typos_of_longer_words() {
+        let index = index(&["buy groceries", "call mom", "fix the bike"]);
+        let fuzzy = SearchOptions {
+            stem: false,
+            fuzzy: true,
+        };
+
+        // Two typos in a word of eight characters.
+        assert_eq!(search(&index, "grocerys", fuzzy), [1]);
+        assert!(search(&index, "grocerys", EXACT).is_empty());
+        // Transposed letters are a single typo.
+        assert_eq!(search(&index, "bkie", fuzzy), [3]);
+        // Words of up to three characters are only matched exactly.
+        assert!(search(&index, "mum", fuzzy).is_empty());
+        // Three typos are too many.
+        assert!(search(&index, "grossaries", fuzzy).is_empty());
+    }
+
+    #[test]
+    fn fuzzy_matching_only_compares_terms_of_similar_length() {
+        let index = index(&["bike", "bikes", "motorbikes"]);
+        let fuzzy = SearchOptions {
+            stem: false,
+            fuzzy: true,
+        };
+
+        assert_eq!(
+            index
+                .lengths
+                .range(3..=5)
+                .flat_map(|(_, terms)| terms)
+                .collect::<Vec<_>>(),
+            ["bike", "bikes"]
+        );
+        assert_eq!(search(&index, "bkie", fuzzy), [1]);
+        assert_eq!(search(&index, "biks", fuzzy), [1, 2]);
+    }
+
+    #[test]
+    fn removed_todos_are_not_found() {
+        let mut index = index(&["buy milk", "buy bread"]);
+
+        index.insert(TodoId(1), "sell milk");
+        assert_eq!(search(&index, "buy", EXACT), [2]);
+        assert_eq!(search(&index, "sell", EXACT), [1]);
+
+        index.remove(TodoId(2));
+        assert!(search(&index, "bread", EXACT).is_empty());
+        assert!(!index.postings.contains_key("bread"));
+        assert!(index.lengths.values().all(|terms| !terms.contains("bread")));
+    }
+}
@@ -0,0 +1,463 @@
+//! Cross-origin access, hardening response headers and request body limits.
+
+use std::{
+    future::{self, Ready},
+    str::FromStr,
+};
+
+use actix_cors::Cors;
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    error::{InternalError, JsonPayloadError},
+    http::header::{self, HeaderName, HeaderValue},
+    web::JsonConfig,
+    HttpResponse,
+};
+use futures::future::LocalBoxFuture;
+
+use crate::{config::Config, todo::ErrorResponse, API_KEY_NAME};
+
+/// Content security policy of the api responses, which never load or embed anything.
+const API_CSP: &str = "default-src 'none'; frame-ancestors 'none'";
+
+/// Content security policy of the documentation UIs. Redoc, RapiDoc and GraphiQL load their
+/// scripts, styles and fonts from CDNs and Redoc and GraphiQL initialize themselves with an
+/// inline script.
+const DOCS_CSP: &str = "default-src 'self'; \
+    script-src 'self' 'unsafe-inline' https://cdn.redoc.ly https://unpkg.com; \
+    style-src 'self' 'unsafe-inline' https://fonts.googleapis.com https://unpkg.com; \
+    font-src 'self' data: https://fonts.gstatic.com; \
+    img-src 'self' data: https:; \
+    worker-src 'self' blob:; \
+    connect-src 'self'; \
+    frame-ancestors 'none'; \
+    base-uri 'self'; \
+    form-action 'self'";
+
+/// Path prefixes of the documentation UIs.
+const DOCS_PATHS: [&str; 4] = ["/swagger-ui", "/redoc", "/rapidoc", "/graphiql"];
+
+/// Comma separated list of values.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct List(pub(super) Vec<String>);
+
+impl List {
+    fn of(values: &[&str]) -> Self {
+        Self(values.iter().map(|value| String::from(*value)).collect())
+    }
+}
+
+impl FromStr for List {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(
+            s.split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+                .collect(),
+        ))
+    }
+}
+
+/// Origins allowed to call the api from a browser.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(super) enum AllowedOrigins {
+    /// Only same-origin requests, browsers block cross-origin ones.
+    #[default]
+    None,
+    /// Every origin.
+    Any,
+    /// The listed `<scheme>://<host>[:<port>]` origins.
+    List(Vec<String>),
+}
+
+/// Parse `*` or comma separated list of origins.
+impl FromStr for AllowedOrigins {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "*" {
+            return Ok(Self::Any);
+        }
+
+        let List(origins) = s.parse()?;
+        match origins.iter().find(|origin| {
+            !(origin.starts_with("http://") || origin.starts_with("https://"))
+                || origin.ends_with('/')
+                || HeaderValue::from_str(origin).is_err()
+        }) {
+            Some(origin) => Err(format!(
+                "expected origin `<scheme>://<host>[:<port>]`, got `{origin}`"
+            )),
+            None if origins.is_empty() => Ok(Self::None),
+            None => Ok(Self::List(origins)),
+        }
+    }
+}
+
+/// Default methods cross-origin requests may use.
+pub(super) fn default_cors_methods() -> List {
+    List::of(&["GET", "POST", "PUT", "DELETE"])
+}
+
+/// Default headers cross-origin requests may send.
+pub(super) fn default_cors_headers() -> List {
+    List::of(&[
+        "accept",
+        "content-type",
+        API_KEY_NAME,
+        "x-request-id",
+        "traceparent",
+        "tracestate",
+    ])
+}
+
+/// CORS middleware answering preflight requests and adding `Access-Control-*` headers for
+/// the configured origins.
+///
+/// Requests from other origins are served without these headers, leaving it to the browser
+/// to block them, so same-origin requests of the documentation UIs keep working.
+pub(super) fn cors(config: &Config) -> Cors {
+    let cors = Cors::default()
+        .block_on_origin_mismatch(false)
+        .allowed_methods(config.cors_allowed_methods.0.iter().map(String::as_str))
+        .allowed_headers(config.cors_allowed_headers.0.iter().map(String::as_str))
+        .expose_headers([
+            "x-request-id",
+            "retry-after",
+            "ratelimit-limit",
+            "ratelimit-remaining",
+            "ratelimit-reset",
+            "ratelimit-policy",
+            "deprecation",
+            "sunset",
+        ])
+        .max_age(config.cors_max_age);
+
+    match &config.cors_allowed_origins {
+        AllowedOrigins::None => cors,
+        AllowedOrigins::Any => cors.allow_any_origin(),
+        AllowedOrigins::List(origins) => origins
+            .iter()
+            .fold(cors, |cors, origin| cors.allowed_origin(origin)),
+    }
+}
+
+/// Maximum sizes of request bodies in bytes.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct BodyLimits {
+    /// Limit of JSON request bodies.
+    pub(super) json: usize,
+    /// Limit of todo imports.
+    pub(super) import: usize,
+}
+
+impl BodyLimits {
+    /// JSON extractor configuration rejecting bodies over the limit with 413 and bodies which
+    /// cannot be parsed with 400, both with an `ErrorResponse` body.
+    pub(super) fn json_config(self) -> JsonConfig {
+        JsonConfig::default()
+            .limit(self.json)
+            .error_handler(|error, _| match error {
+                JsonPayloadError::Overflow { limit }
+                | JsonPayloadError::OverflowKnownLength { limit, .. } => {
+                    InternalError::from_response(error, payload_too_large(limit)).into()
+                }
+                error => {
+                    let response = HttpResponse::BadRequest()
+                        .json(ErrorResponse::BadRequest(error.to_string()));
+                    InternalError::from_response(error, response).into()
+                }
+            })
+    }
+}
+
+/// 413 response of a request body over `limit` bytes.
+pub(super) fn payload_too_large(limit: usize) -> HttpResponse {
+    HttpResponse::PayloadTooLarge().json(ErrorResponse::PayloadTooLarge(format!(
+        "request body exceeds {limit} bytes"
+    )))
+}
+
+/// Security headers middleware adds hardening headers to every response which does not set
+/// them itself.
+///
+/// `Strict-Transport-Security` is only sent when the server is served over TLS.
+pub(super) struct SecurityHeaders {
+    pub(super) hsts: bool,
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SecurityHeaders
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = SecurityHeadersMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        future::ready(Ok(SecurityHeadersMiddleware {
+            service,
+            hsts: self.hsts,
+        }))
+    }
+}
+
+pub(super) struct SecurityHeadersMiddleware<S> {
+    service: S,
+    hsts: bool,
+}
+
+impl<S, B> Service<ServiceRequest> for SecurityHeadersMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, actix_web::Error>>;
+
+    fn poll_ready(
+        &self,
+        ctx: &mut core::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let csp = if DOCS_PATHS
+            .iter()
+            .any(|prefix| req.path().starts_with(prefix))
+        {
+            DOCS_CSP
+        } else {
+            API_CSP
+        };
+        let hsts = self.hsts;
+
+        let future = self.service.call(req);
+        Box::pin(async move {
+            let mut response = future.await?;
+            let headers = response.headers_mut();
+            let mut insert = |name: HeaderName, value: &'static str| {
+                if !headers.contains_key(&name) {
+                    headers.insert(name, HeaderValue::from_static(value));
+                }
+            };
+
+            insert(header::CONTENT_SECURITY_POLICY, csp);
+            insert(header::X_CONTENT_TYPE_OPTIONS, "nosniff");
+            insert(header::X_FRAME_OPTIONS, "DENY");
+            insert(header::REFERRER_POLICY, "no-referrer");
+            insert(
+                HeaderName::from_static("cross-origin-opener-policy"),
+                "same-origin",
+            );
+            if hsts {
+                insert(header::STRICT_TRANSPORT_SECURITY, "max-age=31536000");
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        http::{header, Method, StatusCode},
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    use super::*;
+
+    const ORIGIN: &str = "https://app.example";
+
+    #[test]
+    fn allowed_origins_parse() {
+        assert_eq!("*".parse(), Ok(AllowedOrigins::Any));
+        assert_eq!(" * ".parse(), Ok(AllowedOrigins::Any));
+        assert_eq!("".parse(), Ok(AllowedOrigins::None));
+        assert_eq!(" , ".parse(), Ok(AllowedOrigins::None));
+        assert_eq!(
+            "https://app.example, http://localhost:3000".parse(),
+            Ok(AllowedOrigins::List(vec![
+                String::from("https://app.example"),
+                String::from("http://localhost:3000"),
+            ]))
+        );
+
+        for invalid in [
+            "app.example",
+            "ftp://app.example",
+            "https://app.example/",
+            "https://app.example, *",
+            "https://app\nexample",
+        ] {
+            assert!(
+                invalid.parse::<AllowedOrigins>().is_err(),
+                "{invalid:?} was accepted"
+            );
+        }
+    }
+
+    #[actix_web::test]
+    async fn security_headers_depend_on_path() {
+        let app = test::init_service(
+            App::new()
+                .wrap(SecurityHeaders { hsts: false })
+                .route("/swagger-ui/", web::get().to(HttpResponse::Ok))
+                .route("/v2/todo", web::get().to(HttpResponse::Ok))
+                .route(
+                    "/framed",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .insert_header((header::X_FRAME_OPTIONS, "SAMEORIGIN"))
+                            .finish()
+                    }),
+                ),
+        )
+        .await;
+        let headers = |path: &'static str| {
+            let app = &app;
+            async move {
+                let response =
+                    test::call_service(app, TestRequest::get().uri(path).to_request()).await;
+                assert_eq!(response.status(), StatusCode::OK);
+                response.headers().clone()
+            }
+        };
+
+        let docs = headers("/swagger-ui/").await;
+        assert_eq!(docs.get(header::CONTENT_SECURITY_POLICY).unwrap(), DOCS_CSP);
+
+        let api = headers("/v2/todo").await;
+        assert_eq!(api.get(header::CONTENT_SECURITY_POLICY).unwrap(), API_CSP);
+        assert_eq!(api.get(header::X_CONTENT_TYPE_OPTIONS).unwrap(), "nosniff");
+        assert_eq!(api.get(header::X_FRAME_OPTIONS).unwrap(), "DENY");
+        assert_eq!(api.get(header::REFERRER_POLICY).unwrap(), "no-referrer");
+        assert_eq!(
+            api.get("cross-origin-opener-policy").unwrap(),
+            "same-origin"
+        );
+        assert!(!api.contains_key(header::STRICT_TRANSPORT_SECURITY));
+
+        // Headers set by the handler are kept.
+        let framed = headers("/framed").await;
+        assert_eq!(framed.get(header::X_FRAME_OPTIONS).unwrap(), "SAMEORIGIN");
+    }
+
+    #[actix_web::test]
+    async fn hsts_is_sent_over_tls_only() {
+        let app = test::init_service(
+            App::new()
+                .wrap(SecurityHeaders { hsts: true })
+                .route("/v2/todo", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let response =
+            test::call_service(&app, TestRequest::get().uri("/v2/todo").to_request()).await;
+        assert_eq!(
+            response
+                .headers()
+                .get(header::STRICT_TRANSPORT_SECURITY)
+                .unwrap(),
+            "max-age=31536000"
+        );
+    }
+
+    /// Preflight request of `origin` for a `method` request to the todo list.
+    fn preflight(origin: &str, method: &str) -> TestRequest {
+        TestRequest::default()
+            .method(Method::OPTIONS)
+            .uri("/v2/todo")
+            .insert_header((header::ORIGIN, origin))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, method))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_HEADERS, API_KEY_NAME))
+    }
+
+    #[actix_web::test]
+    async fn cors_answers_preflight_of_allowed_origins() {
+        let config = Config::with_vars(&[("TODO_CORS_ALLOWED_ORIGINS", ORIGIN)]);
+        let app = test::init_service(
+            App::new()
+                .wrap(cors(&config))
+                .route("/v2/todo", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let response = test::call_service(&app, preflight(ORIGIN, "GET").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(
+            headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            ORIGIN
+        );
+        assert_eq!(headers.get(header::ACCESS_CONTROL_MAX_AGE).unwrap(), "3600");
+        let allowed_headers = headers
+            .get(header::ACCESS_CONTROL_ALLOW_HEADERS)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(allowed_headers.contains(API_KEY_NAME), "{allowed_headers}");
+
+        // Actual requests expose the rate limit and request id headers.
+        let request = TestRequest::get()
+            .uri("/v2/todo")
+            .insert_header((header::ORIGIN, ORIGIN))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        let exposed = response
+            .headers()
+            .get(header::ACCESS_CONTROL_EXPOSE_HEADERS)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(exposed.contains("ratelimit-remaining"), "{exposed}");
+        assert!(exposed.contains("x-request-id"), "{exposed}");
+    }
+
+    #[actix_web::test]
+    async fn cors_rejects_preflight_of_other_origins_and_methods() {
+        let config = Config::with_vars(&[("TODO_CORS_ALLOWED_ORIGINS", ORIGIN)]);
+        let app = test::init_service(
+            App::new()
+                .wrap(cors(&config))
+                .route("/v2/todo", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let response =
+            test::call_service(&app, preflight("https://other.example", "GET").to_request()).await;
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let response = test::call_service(&app, preflight(ORIGIN, "PATCH").to_request()).await;
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        // Without configured origins no origin is allowed.
+        let app = test::init_service(
+            App::new()
+                .wrap(cors(&Config::with_vars(&[])))
+                .route("/v2/todo", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+        let response = test::call_service(&app, preflight(ORIGIN, "GET").to_request()).await;
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+}
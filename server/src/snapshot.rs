@@ -0,0 +1,134 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use actix_web::{rt, web::Data};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::Tenant,
+    config::SnapshotFormat,
+    todo::{TodoRecord, TodoStore},
+};
+
+/// Contents of a snapshot file.
+#[derive(Serialize, Deserialize, Default)]
+pub(super) struct Snapshot {
+    /// Todo lists by tenant with the revisions of every todo.
+    #[serde(default)]
+    pub(super) records: BTreeMap<Tenant, Vec<TodoRecord>>,
+}
+
+/// Writes and reads snapshots of the todo store to and from a file.
+///
+/// Snapshots are written to a temporary file next to the target which is then renamed over
+/// the target, so a crash while writing never leaves a partially written snapshot behind.
+pub(super) struct Snapshots {
+    path: PathBuf,
+    format: SnapshotFormat,
+    /// Serializes concurrent writes of the periodic task and the shutdown snapshot.
+    write_lock: Mutex<()>,
+}
+
+impl Snapshots {
+    pub(super) fn new(path: PathBuf, format: SnapshotFormat) -> Self {
+        Self {
+            path,
+            format,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Load the snapshot, returning an empty one if the snapshot file does not exist yet.
+    pub(super) fn load(&self) -> io::Result<Snapshot> {
+        let file = match File::open(&self.path) {
+            Ok(file) => BufReader::new(file),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Snapshot::default()),
+            Err(error) => return Err(error),
+        };
+
+        match self.format {
+            SnapshotFormat::Json => serde_json::from_reader(file).map_err(io::Error::other),
+            SnapshotFormat::MessagePack => rmp_serde::from_read(file).map_err(io::Error::other),
+        }
+    }
+
+    /// Atomically replace the snapshot file with `snapshot`.
+    pub(super) fn save(&self, snapshot: &Snapshot) -> io::Result<()> {
+        let _guard = self.write_lock.lock();
+        let temp_path = temp_path(&self.path);
+
+        let result = (|| {
+            let mut writer = BufWriter::new(File::create(&temp_path)?);
+            match self.format {
+                SnapshotFormat::Json => {
+                    serde_json::to_writer(&mut writer, snapshot).map_err(io::Error::other)?
+                }
+                SnapshotFormat::MessagePack => {
+                    rmp_serde::encode::write_named(&mut writer, snapshot)
+                        .map_err(io::Error::other)?
+                }
+            }
+            writer.flush()?;
+            writer.get_ref().sync_all()?;
+            fs::rename(&temp_path, &self.path)
+        })();
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        result
+    }
+
+    /// Save a snapshot of `store`, logging the outcome.
+    pub(super) fn save_store(&self, store: &TodoStore) {
+        let snapshot = store.snapshot();
+        match self.save(&snapshot) {
+            Ok(()) => tracing::info!(
+                path = %self.path.display(),
+                todos = snapshot.records.values().map(Vec::len).sum::<usize>(),
+                "snapshot written"
+            ),
+            Err(error) => tracing::error!(
+                path = %self.path.display(),
+                %error,
+                "failed to write snapshot"
+            ),
+        }
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_owned();
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
+/// Spawn a background task writing a snapshot of `store` every `interval`.
+pub(super) fn spawn_periodic(
+    snapshots: Data<Snapshots>,
+    store: Data<TodoStore>,
+    interval: Duration,
+) -> rt::task::JoinHandle<()> {
+    rt::spawn(async move {
+        let mut interval = rt::time::interval(interval);
+        // The first tick completes immediately, right after the snapshot was loaded.
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let snapshots = snapshots.clone();
+            let store = store.clone();
+            if let Err(error) = rt::task::spawn_blocking(move || snapshots.save_store(&store)).await
+            {
+                tracing::error!(%error, "snapshot task failed");
+            }
+        }
+    })
+}
@@ -0,0 +1,205 @@
+//! Concurrent in-memory storage backing the todo lists.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    sync::Arc,
+    time::Duration,
+};
+
+use parking_lot::RwLock;
+
+/// Items of a single partition indexed by their id.
+pub type Partition<T, Id = i32> = RwLock<BTreeMap<Id, T>>;
+
+/// Concurrent map of id-indexed partitions, e.g. a todo list per tenant.
+///
+/// The partition map and every partition have their own read/write lock, so readers never
+/// block each other and writers only block accesses to the same partition. The locks do not
+/// poison, a panic while a lock is held leaves the store usable for later requests.
+pub struct PartitionedStore<K, T, Id = i32> {
+    partitions: RwLock<HashMap<K, Arc<Partition<T, Id>>>>,
+}
+
+impl<K, T, Id> Default for PartitionedStore<K, T, Id> {
+    fn default() -> Self {
+        Self {
+            partitions: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K, T, Id> PartitionedStore<K, T, Id>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Partition of `key`, `None` if nothing was ever stored to it.
+    pub fn get(&self, key: &K) -> Option<Arc<Partition<T, Id>>> {
+        self.partitions.read().get(key).cloned()
+    }
+
+    /// Partition of `key`, creating an empty one if it does not exist yet.
+    pub fn get_or_create(&self, key: &K) -> Arc<Partition<T, Id>> {
+        if let Some(partition) = self.get(key) {
+            return partition;
+        }
+
+        self.partitions
+            .write()
+            .entry(key.clone())
+            .or_default()
+            .clone()
+    }
+
+    /// Every partition with its key.
+    pub fn partitions(&self) -> Vec<(K, Arc<Partition<T, Id>>)> {
+        self.partitions
+            .read()
+            .iter()
+            .map(|(key, partition)| (key.clone(), partition.clone()))
+            .collect()
+    }
+
+    /// Number of items across all partitions.
+    pub fn len(&self) -> usize {
+        self.partitions()
+            .iter()
+            .map(|(_, partition)| partition.read().len())
+            .sum()
+    }
+
+    /// Whether the store holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the partition map can be read within `timeout`, i.e. no writer is stuck
+    /// holding it.
+    pub fn is_responsive(&self, timeout: Duration) -> bool {
+        self.partitions.try_read_for(timeout).is_some()
+    }
+}
+
+impl<K, T, Id> FromIterator<(K, BTreeMap<Id, T>)> for PartitionedStore<K, T, Id>
+where
+    K: Eq + Hash,
+{
+    fn from_iter<I: IntoIterator<Item = (K, BTreeMap<Id, T>)>>(iter: I) -> Self {
+        Self {
+            partitions: RwLock::new(
+                iter.into_iter()
+                    .map(|(key, items)| (key, Arc::new(RwLock::new(items))))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, thread, time::Duration};
+
+    use super::PartitionedStore;
+
+    #[test]
+    fn get_or_create_returns_the_same_partition() {
+        let store = PartitionedStore::<&str, &str>::default();
+        assert!(store.get(&"a").is_none());
+
+        store.get_or_create(&"a").write().insert(1, "one");
+        store.get_or_create(&"a").write().insert(2, "two");
+
+        let partition = store.get(&"a").expect("partition was created");
+        assert_eq!(
+            partition.read().values().copied().collect::<Vec<_>>(),
+            ["one", "two"]
+        );
+        assert_eq!(store.partitions().len(), 1);
+    }
+
+    #[test]
+    fn partitions_are_isolated() {
+        let store = PartitionedStore::<&str, &str>::default();
+        store.get_or_create(&"a").write().insert(1, "a one");
+        store.get_or_create(&"b").write().insert(1, "b one");
+        store.get_or_create(&"b").write().insert(2, "b two");
+
+        assert_eq!(store.get(&"a").unwrap().read().get(&1), Some(&"a one"));
+        assert_eq!(store.get(&"a").unwrap().read().get(&2), None);
+        assert_eq!(store.get(&"b").unwrap().read().get(&1), Some(&"b one"));
+
+        let mut keys = store
+            .partitions()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        assert_eq!(keys, ["a", "b"]);
+    }
+
+    #[test]
+    fn len_counts_items_across_partitions() {
+        let store = PartitionedStore::<&str, &str>::default();
+        assert!(store.is_empty());
+
+        store.get_or_create(&"a").write().insert(1, "one");
+        store
+            .get_or_create(&"b")
+            .write()
+            .extend([(1, "one"), (2, "two")]);
+        // An empty partition adds no items.
+        store.get_or_create(&"c");
+
+        assert_eq!(store.len(), 3);
+        assert!(!store.is_empty());
+    }
+
+    #[test]
+    fn collects_from_partitions() {
+        let store = [
+            ("a", BTreeMap::from([(2, "two"), (1, "one")])),
+            ("b", BTreeMap::from([(3, "three")])),
+        ]
+        .into_iter()
+        .collect::<PartitionedStore<_, _>>();
+
+        assert_eq!(store.len(), 3);
+        assert_eq!(
+            store
+                .get(&"a")
+                .unwrap()
+                .read()
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(store.get(&"b").unwrap().read().get(&3), Some(&"three"));
+    }
+
+    #[test]
+    fn writer_of_one_partition_does_not_block_others() {
+        let store = PartitionedStore::<&str, &str>::default();
+        let a = store.get_or_create(&"a");
+        let _write = a.write();
+
+        // Created while `a` is locked for writing.
+        thread::scope(|scope| {
+            scope
+                .spawn(|| store.get_or_create(&"b").write().insert(1, "one"))
+                .join()
+                .unwrap();
+        });
+
+        assert!(store.is_responsive(Duration::from_millis(10)));
+        assert_eq!(store.get(&"b").unwrap().read().get(&1), Some(&"one"));
+    }
+
+    #[test]
+    fn held_partition_map_is_not_responsive() {
+        let store = PartitionedStore::<&str, &str>::default();
+        let _write = store.partitions.write();
+
+        assert!(!store.is_responsive(Duration::from_millis(10)));
+    }
+}
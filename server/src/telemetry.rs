@@ -0,0 +1,357 @@
+use std::{
+    fmt,
+    fs::OpenOptions,
+    future::{self, Ready},
+    io::{self, Write},
+    time::{Instant, SystemTime},
+};
+
+use actix_web::{
+    body::MessageBody,
+    dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderMap, HeaderName, HeaderValue},
+    FromRequest, HttpMessage, HttpRequest,
+};
+use futures::future::{BoxFuture, LocalBoxFuture};
+use opentelemetry::{
+    global,
+    propagation::Extractor,
+    trace::{TraceError, TracerProvider as _},
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    export::trace::{ExportResult, SpanData, SpanExporter},
+    propagation::TraceContextPropagator,
+    runtime,
+    trace::TracerProvider,
+    Resource,
+};
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+use utoipa::{
+    openapi::{header::HeaderBuilder, ObjectBuilder, OpenApi, RefOr, SchemaType},
+    Modify,
+};
+
+use crate::config::{Config, LogFormat, TraceExporter};
+
+/// Header used to correlate a request across services and log lines.
+pub(super) const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Documents the `X-Request-Id` header [`RequestTracing`] sets on every response.
+pub(super) struct RequestIdAddon;
+
+impl Modify for RequestIdAddon {
+    fn modify(&self, openapi: &mut OpenApi) {
+        let header = HeaderBuilder::new()
+            .schema(ObjectBuilder::new().schema_type(SchemaType::String))
+            .description(Some(
+                "Id of the request, taken from its `X-Request-Id` header or generated.",
+            ))
+            .build();
+        for response in openapi
+            .paths
+            .paths
+            .values_mut()
+            .flat_map(|path_item| path_item.operations.values_mut())
+            .flat_map(|operation| operation.responses.responses.values_mut())
+        {
+            if let RefOr::T(response) = response {
+                response
+                    .headers
+                    .insert(String::from("X-Request-Id"), header.clone());
+            }
+        }
+    }
+}
+
+/// Install the global `tracing` subscriber and OpenTelemetry tracer.
+///
+/// Log level is controlled with `RUST_LOG` (defaults to `info`) and the output format with
+/// [`Config::log_format`]. Records emitted with the `log` crate, e.g. by actix-web itself,
+/// are forwarded to the same subscriber by the `tracing-log` bridge `init` installs. Spans are
+/// exported according to [`Config::trace_exporter`]; the returned guard flushes them when
+/// dropped.
+pub(super) fn init(config: &Config) -> io::Result<TelemetryGuard> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let provider = tracer_provider(config)?;
+    let otel_layer = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+    });
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let fmt_layer = match config.log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .boxed(),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer)
+        .with(otel_layer)
+        .init();
+
+    Ok(TelemetryGuard(provider))
+}
+
+fn tracer_provider(config: &Config) -> io::Result<Option<TracerProvider>> {
+    let builder = TracerProvider::builder().with_resource(Resource::new([KeyValue::new(
+        "service.name",
+        env!("CARGO_PKG_NAME"),
+    )]));
+
+    let builder = match config.trace_exporter {
+        TraceExporter::None => return Ok(None),
+        TraceExporter::Otlp => {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(&config.otlp_endpoint)
+                .build()
+                .map_err(io::Error::other)?;
+            builder.with_batch_exporter(exporter, runtime::TokioCurrentThread)
+        }
+        TraceExporter::Stdout => builder.with_batch_exporter(
+            JsonLinesExporter::new(io::stdout()),
+            runtime::TokioCurrentThread,
+        ),
+        TraceExporter::File => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&config.trace_file)?;
+            builder.with_batch_exporter(JsonLinesExporter::new(file), runtime::TokioCurrentThread)
+        }
+    };
+
+    Ok(Some(builder.build()))
+}
+
+/// Flushes and shuts down span export when dropped.
+pub(super) struct TelemetryGuard(Option<TracerProvider>);
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.0.take() {
+            if let Err(error) = provider.shutdown() {
+                tracing::error!(%error, "failed to shut down tracer provider");
+            }
+        }
+    }
+}
+
+/// Span exporter writing one JSON object per finished span, used to inspect traces without
+/// running a collector.
+struct JsonLinesExporter {
+    writer: Box<dyn Write + Send + Sync>,
+}
+
+impl JsonLinesExporter {
+    fn new(writer: impl Write + Send + Sync + 'static) -> Self {
+        Self {
+            writer: Box::new(writer),
+        }
+    }
+}
+
+impl fmt::Debug for JsonLinesExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("JsonLinesExporter")
+    }
+}
+
+impl SpanExporter for JsonLinesExporter {
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+        let writer = &mut self.writer;
+        let result = batch.iter().try_for_each(|span| {
+            let attributes = span
+                .attributes
+                .iter()
+                .map(|kv| (kv.key.to_string(), kv.value.to_string().into()))
+                .collect::<serde_json::Map<_, _>>();
+            let line = serde_json::json!({
+                "trace_id": span.span_context.trace_id().to_string(),
+                "span_id": span.span_context.span_id().to_string(),
+                "parent_span_id": span.parent_span_id.to_string(),
+                "name": span.name,
+                "kind": format!("{:?}", span.span_kind),
+                "start_time": unix_nanos(span.start_time),
+                "end_time": unix_nanos(span.end_time),
+                "attributes": attributes,
+            });
+            writeln!(writer, "{line}")
+        });
+
+        Box::pin(future::ready(
+            result
+                .and_then(|_| writer.flush())
+                .map_err(|error| TraceError::Other(Box::new(error))),
+        ))
+    }
+}
+
+fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+}
+
+/// Reads W3C trace context headers from actix-web header map.
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
+
+/// Id of the request currently being served.
+///
+/// Taken from the incoming `X-Request-Id` header or generated when the header is missing.
+/// It is stored in the request extensions by [`RequestTracing`].
+#[derive(Clone, Debug)]
+pub(super) struct RequestId(String);
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl RequestId {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        Self::given_or_generated(
+            headers
+                .get(&REQUEST_ID_HEADER)
+                .and_then(|value| value.to_str().ok()),
+        )
+    }
+
+    /// Request id given by the caller, or a new one when it is missing or invalid.
+    pub(super) fn given_or_generated(value: Option<&str>) -> Self {
+        value
+            .filter(|value| !value.is_empty() && value.len() <= 128)
+            .map(|value| Self(value.to_owned()))
+            .unwrap_or_else(Self::generate)
+    }
+
+    fn generate() -> Self {
+        Self(uuid::Uuid::new_v4().to_string())
+    }
+}
+
+/// Resolves the request id stored by [`RequestTracing`], generating a new one for requests
+/// not wrapped by it.
+impl FromRequest for RequestId {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        future::ready(Ok(req
+            .extensions()
+            .get::<Self>()
+            .cloned()
+            .unwrap_or_else(Self::generate)))
+    }
+}
+
+/// Request tracing middleware wraps every request in a `request` span carrying the request id,
+/// logs the outcome of the request and echoes the request id back in the response.
+///
+/// The span continues the trace given in the W3C `traceparent` and `tracestate` request headers,
+/// if any, so that it is exported as a child of the calling client's span.
+pub(super) struct RequestTracing;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestTracing
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = RequestTracingMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        future::ready(Ok(RequestTracingMiddleware { service }))
+    }
+}
+
+pub(super) struct RequestTracingMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestTracingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, actix_web::Error>>;
+
+    fn poll_ready(
+        &self,
+        ctx: &mut core::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let request_id = RequestId::from_headers(req.headers());
+        let span = tracing::info_span!(
+            "request",
+            request_id = %request_id,
+            method = %req.method(),
+            path = %req.path(),
+            route = tracing::field::Empty,
+            status = tracing::field::Empty,
+            otel.kind = "server",
+        );
+        let parent = global::get_text_map_propagator(|propagator| {
+            propagator.extract(&HeaderExtractor(req.headers()))
+        });
+        span.set_parent(parent);
+        req.extensions_mut().insert(request_id.clone());
+
+        let start = Instant::now();
+        let future = span.in_scope(|| self.service.call(req));
+
+        Box::pin(
+            async move {
+                let mut response = future.await?;
+                let status = response.status();
+                let span = tracing::Span::current();
+                span.record("status", status.as_u16());
+                if let Some(route) = response.request().match_pattern() {
+                    span.record("route", route);
+                }
+                if let Ok(value) = HeaderValue::from_str(&request_id.0) {
+                    response.headers_mut().insert(REQUEST_ID_HEADER, value);
+                }
+
+                let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+                if status.is_server_error() {
+                    tracing::error!(elapsed_ms, "request failed");
+                } else {
+                    tracing::info!(elapsed_ms, "request completed");
+                }
+
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
@@ -0,0 +1,286 @@
+//! TLS termination with certificate hot reload and client certificate authentication.
+
+use std::{
+    any::Any,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use actix_tls::accept::rustls_0_23::TlsStream;
+use actix_web::{
+    dev::Extensions,
+    rt::{self, net::TcpStream},
+};
+use parking_lot::{Mutex, RwLock};
+use rustls::{
+    crypto::{ring, CryptoProvider},
+    pki_types::{CertificateDer, PrivateKeyDer},
+    server::{ClientHello, ResolvesServerCert, WebPkiClientVerifier},
+    sign::CertifiedKey,
+    RootCertStore, ServerConfig,
+};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+use crate::{
+    auth::{CertificatePrincipal, ClientSubjects, Principal},
+    config::Config,
+};
+
+/// Whether clients have to present a certificate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum ClientAuth {
+    /// Clients may present a certificate instead of sending an api key.
+    #[default]
+    Optional,
+    /// Connections without a valid client certificate are refused.
+    Required,
+}
+
+impl FromStr for ClientAuth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "optional" => Ok(Self::Optional),
+            "required" => Ok(Self::Required),
+            other => Err(format!("unknown client auth mode: {other}")),
+        }
+    }
+}
+
+/// Server certificate read from PEM files and read again whenever the files change.
+pub(super) struct ReloadingCertificate {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    provider: Arc<CryptoProvider>,
+    current: RwLock<Arc<CertifiedKey>>,
+    /// Modification times of the certificate and key files when they were last read.
+    modified: Mutex<(Option<SystemTime>, Option<SystemTime>)>,
+}
+
+impl ReloadingCertificate {
+    fn open(
+        cert_path: PathBuf,
+        key_path: PathBuf,
+        provider: Arc<CryptoProvider>,
+    ) -> io::Result<Self> {
+        let modified = (modified(&cert_path), modified(&key_path));
+        let current = load_certified_key(&cert_path, &key_path, &provider)?;
+
+        Ok(Self {
+            cert_path,
+            key_path,
+            provider,
+            current: RwLock::new(Arc::new(current)),
+            modified: Mutex::new(modified),
+        })
+    }
+
+    /// Read the certificate again if its files changed since they were last read, returning
+    /// whether it was replaced. The current certificate is kept when the files are invalid.
+    fn reload(&self) -> io::Result<bool> {
+        let modified = (modified(&self.cert_path), modified(&self.key_path));
+        let mut last_modified = self.modified.lock();
+        if *last_modified == modified {
+            return Ok(false);
+        }
+
+        let certified_key = load_certified_key(&self.cert_path, &self.key_path, &self.provider)?;
+        *self.current.write() = Arc::new(certified_key);
+        *last_modified = modified;
+        Ok(true)
+    }
+}
+
+impl fmt::Debug for ReloadingCertificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReloadingCertificate")
+            .field("cert_path", &self.cert_path)
+            .field("key_path", &self.key_path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResolvesServerCert for ReloadingCertificate {
+    fn resolve(&self, _: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.current.read().clone())
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn invalid_data(path: &Path, error: impl fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {error}", path.display()),
+    )
+}
+
+fn load_certificates(path: &Path) -> io::Result<Vec<CertificateDer<'static>>> {
+    let certificates = rustls_pemfile::certs(&mut io::BufReader::new(fs::File::open(path)?))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| invalid_data(path, error))?;
+    if certificates.is_empty() {
+        return Err(invalid_data(path, "no certificates found"));
+    }
+
+    Ok(certificates)
+}
+
+fn load_certified_key(
+    cert_path: &Path,
+    key_path: &Path,
+    provider: &CryptoProvider,
+) -> io::Result<CertifiedKey> {
+    let certificates = load_certificates(cert_path)?;
+    let key: PrivateKeyDer<'static> =
+        rustls_pemfile::private_key(&mut io::BufReader::new(fs::File::open(key_path)?))?
+            .ok_or_else(|| invalid_data(key_path, "no private key found"))?;
+    let key = provider
+        .key_provider
+        .load_private_key(key)
+        .map_err(|error| invalid_data(key_path, error))?;
+
+    let certified_key = CertifiedKey::new(certificates, key);
+    certified_key
+        .keys_match()
+        .map_err(|error| invalid_data(cert_path, error))?;
+    Ok(certified_key)
+}
+
+/// Build the TLS configuration of the server, `None` when TLS is not configured.
+///
+/// Clients are asked for a certificate signed by `TODO_TLS_CLIENT_CA` when it is set.
+pub(super) fn server_config(
+    config: &Config,
+) -> io::Result<Option<(ServerConfig, Arc<ReloadingCertificate>)>> {
+    let (cert_path, key_path) = match (&config.tls_cert, &config.tls_key) {
+        (Some(cert_path), Some(key_path)) => (cert_path.clone(), key_path.clone()),
+        (None, None) => return Ok(None),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "TODO_TLS_CERT and TODO_TLS_KEY must be set together",
+            ))
+        }
+    };
+
+    let provider = Arc::new(ring::default_provider());
+    let certificate = Arc::new(ReloadingCertificate::open(
+        cert_path,
+        key_path,
+        provider.clone(),
+    )?);
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(io::Error::other)?;
+    let builder = match &config.tls_client_ca {
+        Some(ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for certificate in load_certificates(ca_path)? {
+                roots
+                    .add(certificate)
+                    .map_err(|error| invalid_data(ca_path, error))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+            let verifier = match config.tls_client_auth {
+                ClientAuth::Optional => verifier.allow_unauthenticated(),
+                ClientAuth::Required => verifier,
+            };
+            builder.with_client_cert_verifier(verifier.build().map_err(io::Error::other)?)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    Ok(Some((
+        builder.with_cert_resolver(certificate.clone()),
+        certificate,
+    )))
+}
+
+/// Spawn a background task reading the server certificate again every `interval` when its
+/// files changed.
+pub(super) fn spawn_reload(
+    certificate: Arc<ReloadingCertificate>,
+    interval: Duration,
+) -> rt::task::JoinHandle<()> {
+    rt::spawn(async move {
+        let mut interval = rt::time::interval(interval);
+
+        loop {
+            interval.tick().await;
+
+            match certificate.reload() {
+                Ok(true) => tracing::info!(
+                    path = %certificate.cert_path.display(),
+                    "reloaded TLS certificate"
+                ),
+                Ok(false) => {}
+                Err(error) => tracing::error!(
+                    %error,
+                    "failed to reload TLS certificate, keeping the current one"
+                ),
+            }
+        }
+    })
+}
+
+/// Connection callback resolving the principal of a verified client certificate from the
+/// common name of its subject.
+pub(super) fn on_connect(subjects: ClientSubjects) -> impl Fn(&dyn Any, &mut Extensions) {
+    move |connection, data| {
+        let Some(stream) = connection.downcast_ref::<TlsStream<TcpStream>>() else {
+            return;
+        };
+        let Some(certificate) = stream
+            .get_ref()
+            .1
+            .peer_certificates()
+            .and_then(|certificates| certificates.first())
+        else {
+            return;
+        };
+
+        if let Some(principal) = certificate_principal(&subjects, certificate) {
+            data.insert(CertificatePrincipal(principal.clone()));
+        }
+    }
+}
+
+/// Principal of a verified client `certificate` from the common name of its subject, `None`
+/// when it has no common name or the common name is not in `subjects`.
+pub(super) fn certificate_principal<'a>(
+    subjects: &'a ClientSubjects,
+    certificate: &[u8],
+) -> Option<&'a Principal> {
+    let common_name = X509Certificate::from_der(certificate)
+        .ok()
+        .and_then(|(_, certificate)| {
+            certificate
+                .subject()
+                .iter_common_name()
+                .next()
+                .and_then(|common_name| common_name.as_str().ok())
+                .map(String::from)
+        });
+    let Some(common_name) = common_name else {
+        tracing::debug!("client certificate has no subject common name");
+        return None;
+    };
+
+    let principal = subjects.authenticate(&common_name);
+    if principal.is_none() {
+        tracing::debug!(
+            common_name,
+            "client certificate subject is not mapped to a principal"
+        );
+    }
+    principal
+}
@@ -1,44 +1,704 @@
-use std::sync::Mutex;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+    time::Duration,
+};
 
 use actix_web::{
-    delete, get, post, put,
-    web::{Data, Json, Path, Query, ServiceConfig},
-    HttpResponse, Responder,
+    delete, get,
+    http::{header, StatusCode},
+    post, put, rt,
+    web::{scope, Bytes, BytesMut, Data, Json, Path, Payload, Query, ServiceConfig},
+    HttpRequest, HttpResponse, Responder,
 };
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::StreamExt;
+use serde::{de, Deserialize, Deserializer, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
-use crate::{LogApiKey, RequireApiKey};
+use crate::{
+    audit::{Actor, AuditLog, Operation},
+    auth::{Principal, Tenant},
+    search::{Highlight, SearchIndex, SearchOptions},
+    security::{payload_too_large, BodyLimits},
+    snapshot::Snapshot,
+    store::PartitionedStore,
+    telemetry::RequestId,
+    version::{ApiVersion, Deprecation},
+    RequireApiKey,
+};
 
+/// In-memory todo storage holding a separate todo list for every tenant.
+///
+/// Todo lists are indexed by todo id and locked separately, see [`PartitionedStore`]. Todos
+/// are never changed in place, every change stores a new revision to the [`TodoRecord`] of
+/// the todo. The values of the current revisions are kept in a [`SearchIndex`].
 #[derive(Default)]
 pub(super) struct TodoStore {
-    todos: Mutex<Vec<Todo>>,
+    lists: PartitionedStore<Tenant, TodoRecord, TodoId>,
+    index: SearchIndex,
 }
 
-pub(super) fn configure(store: Data<TodoStore>) -> impl FnOnce(&mut ServiceConfig) {
-    |config: &mut ServiceConfig| {
+impl TodoStore {
+    /// Number of todos currently in the store across all tenants.
+    pub(super) fn len(&self) -> usize {
+        self.lists.len()
+    }
+
+    /// Whether the store can be used, i.e. it is not stuck behind a writer.
+    pub(super) fn is_available(&self) -> bool {
+        self.lists.is_responsive(Duration::from_millis(100))
+    }
+
+    /// Copy the contents of the store into a [`Snapshot`].
+    pub(super) fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            records: self
+                .lists
+                .partitions()
+                .into_iter()
+                .map(|(tenant, records)| (tenant, records.read().values().cloned().collect()))
+                .collect(),
+        }
+    }
+
+    /// Rebuild the search index from the current revisions of the stored todos.
+    pub(super) fn reindex(&self) {
+        for (tenant, records) in self.lists.partitions() {
+            let records = records.write();
+            self.index.rebuild(
+                &tenant,
+                records
+                    .values()
+                    .map(TodoRecord::current)
+                    .map(|todo| (todo.id, todo.value.as_str())),
+            );
+        }
+    }
+
+    /// Run `f` on the todo list of `tenant` under a read lock.
+    fn read<R>(&self, tenant: &Tenant, f: impl FnOnce(&BTreeMap<TodoId, TodoRecord>) -> R) -> R {
+        match self.lists.get(tenant) {
+            Some(records) => f(&records.read()),
+            None => f(&BTreeMap::new()),
+        }
+    }
+
+    /// Run `f` on the todo list of `tenant` under a write lock.
+    fn write<R>(
+        &self,
+        tenant: &Tenant,
+        f: impl FnOnce(&mut BTreeMap<TodoId, TodoRecord>) -> R,
+    ) -> R {
+        f(&mut self.lists.get_or_create(tenant).write())
+    }
+
+    /// Todos of `tenant`, including the ones in the trash when `include_deleted` is set.
+    pub(super) fn list(&self, tenant: &Tenant, include_deleted: bool) -> Vec<Todo> {
+        self.read(tenant, |records| {
+            records
+                .values()
+                .map(TodoRecord::current)
+                .filter(|todo| include_deleted || !todo.is_deleted())
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Todo `id` of `tenant` as it is now, or as it was `at` when given.
+    ///
+    /// Todos which are in the trash, or were at `at`, are not found.
+    pub(super) fn get(
+        &self,
+        tenant: &Tenant,
+        id: TodoId,
+        at: Option<RevisionAt>,
+    ) -> Result<Todo, ErrorResponse> {
+        self.read(tenant, |records| {
+            records
+                .get(&id)
+                .and_then(|record| match at {
+                    Some(at) => record.revision(at).map(|revision| &revision.todo),
+                    None => Some(record.current()),
+                })
+                .filter(|todo| !todo.is_deleted())
+                .cloned()
+                .ok_or_else(|| ErrorResponse::NotFound(format!("id = {id}")))
+        })
+    }
+
+    /// Store new `todo` to the list of `tenant`, failing with [`ErrorResponse::Conflict`] when
+    /// its id is taken.
+    pub(super) fn create(
+        &self,
+        tenant: &Tenant,
+        todo: Todo,
+        audit: &AuditLog,
+        actor: &Actor,
+    ) -> Result<Todo, ErrorResponse> {
+        let now = Utc::now();
+        let todo = todo.created(now);
+
+        self.write(tenant, |records| {
+            if let Some(existing) = records.get(&todo.id) {
+                return Err(ErrorResponse::Conflict(format!(
+                    "id = {}",
+                    existing.current().id
+                )));
+            }
+
+            records.insert(todo.id, TodoRecord::new(todo.clone(), now));
+            self.index.insert(tenant, todo.id, &todo.value);
+            audit.record(actor, tenant, Operation::Create, None, Some(&todo));
+            Ok(todo)
+        })
+    }
+
+    /// Apply `update` to todo `id` of `tenant` unless it is in the trash.
+    pub(super) fn update(
+        &self,
+        tenant: &Tenant,
+        id: TodoId,
+        update: TodoUpdateRequest,
+        audit: &AuditLog,
+        actor: &Actor,
+    ) -> Result<Todo, ErrorResponse> {
+        self.write(tenant, |records| {
+            let record = records
+                .get_mut(&id)
+                .filter(|record| !record.current().is_deleted())
+                .ok_or_else(|| ErrorResponse::NotFound(format!("id = {id}")))?;
+            let now = Utc::now();
+            let (before, after) = record.change(now, |todo| todo.update(update, now));
+            self.index.insert(tenant, id, &after.value);
+            audit.record(actor, tenant, Operation::Update, Some(&before), Some(after));
+            Ok(after.clone())
+        })
+    }
+
+    /// Move todo `id` of `tenant` to the trash unless it is there already.
+    pub(super) fn delete(
+        &self,
+        tenant: &Tenant,
+        id: TodoId,
+        audit: &AuditLog,
+        actor: &Actor,
+    ) -> Result<(), ErrorResponse> {
+        self.write(tenant, |records| {
+            let record = records
+                .get_mut(&id)
+                .filter(|record| !record.current().is_deleted())
+                .ok_or_else(|| ErrorResponse::NotFound(format!("id = {id}")))?;
+            let now = Utc::now();
+            let (before, after) = record.change(now, |todo| todo.deleted_at = Some(now));
+            audit.record(actor, tenant, Operation::Delete, Some(&before), Some(after));
+            Ok(())
+        })
+    }
+
+    /// Move todo `id` of `tenant` out of the trash.
+    pub(super) fn restore(
+        &self,
+        tenant: &Tenant,
+        id: TodoId,
+        audit: &AuditLog,
+        actor: &Actor,
+    ) -> Result<Todo, ErrorResponse> {
+        self.write(tenant, |records| {
+            let record = records
+                .get_mut(&id)
+                .filter(|record| record.current().is_deleted())
+                .ok_or_else(|| ErrorResponse::NotFound(format!("id = {id}")))?;
+            let now = Utc::now();
+            let (before, after) = record.change(now, |todo| {
+                todo.deleted_at = None;
+                todo.updated_at = Some(now);
+            });
+            audit.record(
+                actor,
+                tenant,
+                Operation::Restore,
+                Some(&before),
+                Some(after),
+            );
+            Ok(after.clone())
+        })
+    }
+
+    /// Todos of `tenant` matching the words of `value`, best match first, or every todo when
+    /// `value` has no words.
+    pub(super) fn search(
+        &self,
+        tenant: &Tenant,
+        value: &str,
+        include_deleted: bool,
+        options: SearchOptions,
+    ) -> Vec<SearchHit> {
+        self.read(tenant, |records| {
+            let visible = |todo: &&Todo| include_deleted || !todo.is_deleted();
+            match self.index.search(tenant, value, options) {
+                Some(matches) => matches
+                    .into_iter()
+                    .filter_map(|found| {
+                        let todo = records.get(&found.id).map(TodoRecord::current);
+                        todo.filter(visible).map(|todo| SearchHit {
+                            score: found.score,
+                            highlights: found.highlights,
+                            todo: todo.clone(),
+                        })
+                    })
+                    .collect(),
+                None => records
+                    .values()
+                    .map(TodoRecord::current)
+                    .filter(visible)
+                    .map(|todo| SearchHit {
+                        score: 0.0,
+                        highlights: Vec::new(),
+                        todo: todo.clone(),
+                    })
+                    .collect(),
+            }
+        })
+    }
+
+    /// Todos of `tenant` whose value contains `value` regardless of case, in order of their id.
+    pub(super) fn containing(
+        &self,
+        tenant: &Tenant,
+        value: &str,
+        include_deleted: bool,
+    ) -> Vec<Todo> {
+        let value = value.to_lowercase();
+        self.read(tenant, |records| {
+            records
+                .values()
+                .map(TodoRecord::current)
+                .filter(|todo| include_deleted || !todo.is_deleted())
+                .filter(|todo| todo.value.to_lowercase().contains(&value))
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Store `todos` to the list of `tenant` resolving id conflicts according to `policy`.
+    ///
+    /// The import is all or nothing, when `policy` is [`ConflictPolicy::Fail`] and a todo
+    /// conflicts nothing is stored and the id of the first conflicting todo is returned.
+    /// Every stored todo is recorded to `audit` on success.
+    fn import(
+        &self,
+        tenant: Tenant,
+        todos: Vec<Todo>,
+        policy: ConflictPolicy,
+        audit: &AuditLog,
+        actor: &Actor,
+    ) -> Result<ImportReport, TodoId> {
+        self.write(&tenant, |stored| {
+            let mut report = ImportReport::default();
+            let mut imported = stored.clone();
+            let mut changes = Vec::new();
+            let now = Utc::now();
+
+            for todo in todos.into_iter().map(|todo| todo.imported(now)) {
+                match (imported.get_mut(&todo.id), policy) {
+                    (None, _) => {
+                        changes.push((None, todo.clone()));
+                        imported.insert(todo.id, TodoRecord::new(todo, now));
+                        report.imported += 1;
+                    }
+                    (Some(_), ConflictPolicy::Fail) => return Err(todo.id),
+                    (Some(_), ConflictPolicy::Skip) => report.skipped += 1,
+                    (Some(existing), ConflictPolicy::Overwrite) => {
+                        let (before, _) = existing.change(now, |current| *current = todo.clone());
+                        changes.push((Some(before), todo));
+                        report.overwritten += 1;
+                    }
+                }
+            }
+
+            *stored = imported;
+            for (before, after) in &changes {
+                self.index.insert(&tenant, after.id, &after.value);
+                audit.record(
+                    actor,
+                    &tenant,
+                    Operation::Import,
+                    before.as_ref(),
+                    Some(after),
+                );
+            }
+            Ok(report)
+        })
+    }
+
+    /// Permanently remove todos moved to the trash before `before`, recording them to `audit`
+    /// and returning their count.
+    fn purge(&self, before: DateTime<Utc>, audit: &AuditLog) -> usize {
+        let actor = Actor::system();
+        let mut purged = 0;
+
+        for (tenant, records) in self.lists.partitions() {
+            let mut records = records.write();
+            let expired = records
+                .values()
+                .map(TodoRecord::current)
+                .filter(|todo| {
+                    todo.deleted_at
+                        .is_some_and(|deleted_at| deleted_at < before)
+                })
+                .map(|todo| todo.id)
+                .collect::<Vec<_>>();
+            for record in expired.iter().filter_map(|id| records.remove(id)) {
+                self.index.remove(&tenant, record.current().id);
+                audit.record(
+                    &actor,
+                    &tenant,
+                    Operation::Purge,
+                    Some(record.current()),
+                    None,
+                );
+                purged += 1;
+            }
+        }
+
+        purged
+    }
+}
+
+/// Spawn a background task purging todos kept in the trash longer than `retention` every
+/// `interval`.
+pub(super) fn spawn_purge(
+    store: Data<TodoStore>,
+    audit: Data<AuditLog>,
+    retention: Duration,
+    interval: Duration,
+) -> rt::task::JoinHandle<()> {
+    rt::spawn(async move {
+        let mut interval = rt::time::interval(interval);
+
+        loop {
+            interval.tick().await;
+
+            let Some(before) = TimeDelta::from_std(retention)
+                .ok()
+                .and_then(|retention| Utc::now().checked_sub_signed(retention))
+            else {
+                continue;
+            };
+            let purged = store.purge(before, &audit);
+            if purged > 0 {
+                tracing::info!(purged, "purged expired todos from the trash");
+            }
+        }
+    })
+}
+
+impl From<Snapshot> for TodoStore {
+    fn from(snapshot: Snapshot) -> Self {
+        let lists = snapshot
+            .records
+            .into_iter()
+            .map(|(tenant, records)| {
+                let records = records
+                    .into_iter()
+                    .map(|record| (record.current().id, record))
+                    .collect::<BTreeMap<_, _>>();
+                (tenant, records)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let store = Self {
+            lists: lists.into_iter().collect(),
+            index: SearchIndex::default(),
+        };
+        store.reindex();
+        store
+    }
+}
+
+/// Serve the todo routes of every version under its prefix, and the first version also
+/// without a prefix. Responses of the first version announce its `v1` deprecation.
+pub(super) fn configure(
+    store: Data<TodoStore>,
+    v1: Deprecation,
+) -> impl FnOnce(&mut ServiceConfig) {
+    move |config: &mut ServiceConfig| {
         config
             .app_data(store)
-            .service(search_todos)
+            .service(
+                scope(&format!("{}/todo", ApiVersion::V1.prefix()))
+                    .wrap(v1.headers())
+                    .configure(routes(ApiVersion::V1)),
+            )
+            .service(
+                scope(&format!("{}/todo", ApiVersion::V2.prefix()))
+                    .configure(routes(ApiVersion::V2)),
+            )
+            .service(
+                scope("/todo")
+                    .wrap(v1.headers())
+                    .configure(routes(ApiVersion::V1)),
+            );
+    }
+}
+
+/// Todo routes of `version`, relative to its `/todo` scope.
+fn routes(version: ApiVersion) -> impl FnOnce(&mut ServiceConfig) {
+    move |config: &mut ServiceConfig| {
+        match version {
+            ApiVersion::V1 => config.service(search_todos_v1),
+            ApiVersion::V2 => config.service(search_todos),
+        };
+        config
+            .service(export_todos)
+            .service(import_todos)
+            .service(get_trash)
             .service(get_todos)
             .service(create_todo)
             .service(delete_todo)
+            .service(restore_todo)
+            .service(get_revisions)
+            .service(revert_todo)
             .service(get_todo_by_id)
             .service(update_todo);
     }
 }
 
+/// Unique id of a todo item.
+// Generated clients represent it with their own type, see `docs/type-mappings.json`.
+#[derive(
+    Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(transparent)]
+#[schema(example = 1)]
+pub(super) struct TodoId(pub(super) i32);
+
+impl fmt::Display for TodoId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 /// Task to do.
 #[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
 pub(super) struct Todo {
     /// Unique id for the todo item.
     #[schema(example = 1)]
-    id: i32,
+    pub(super) id: TodoId,
     /// Description of the tasks to do.
     #[schema(example = "Remember to buy groceries")]
-    value: String,
+    pub(super) value: String,
     /// Mark is the task done or not
-    checked: bool,
+    pub(super) checked: bool,
+    /// Priority of the task, defaults to `normal`.
+    #[serde(default)]
+    pub(super) priority: Priority,
+    /// Free form labels of the task.
+    #[serde(default)]
+    #[schema(example = json!(["shopping"]))]
+    pub(super) tags: Vec<String>,
+    /// Optional deadline of the task.
+    pub(super) due_at: Option<DateTime<Utc>>,
+    /// Time the todo was created at, maintained by the server.
+    #[schema(read_only)]
+    pub(super) created_at: Option<DateTime<Utc>>,
+    /// Time the todo was last modified at, maintained by the server.
+    #[schema(read_only)]
+    pub(super) updated_at: Option<DateTime<Utc>>,
+    /// Time the todo was last checked done at, maintained by the server.
+    #[schema(read_only)]
+    pub(super) completed_at: Option<DateTime<Utc>>,
+    /// Time the todo was moved to the trash at, maintained by the server.
+    #[schema(read_only)]
+    pub(super) deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Todo {
+    /// Replace server maintained timestamps of a todo created at `now`.
+    pub(super) fn created(self, now: DateTime<Utc>) -> Self {
+        Self {
+            created_at: Some(now),
+            updated_at: Some(now),
+            completed_at: self.checked.then_some(now),
+            deleted_at: None,
+            ..self
+        }
+    }
+
+    pub(super) fn id(&self) -> TodoId {
+        self.id
+    }
+
+    pub(super) fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Fill in server maintained timestamps missing from an imported todo.
+    fn imported(self, now: DateTime<Utc>) -> Self {
+        let created_at = self.created_at.unwrap_or(now);
+        Self {
+            created_at: Some(created_at),
+            updated_at: Some(self.updated_at.unwrap_or(created_at)),
+            completed_at: self.completed_at.or_else(|| self.checked.then_some(now)),
+            ..self
+        }
+    }
+
+    /// Apply `update` made at `now`.
+    pub(super) fn update(&mut self, update: TodoUpdateRequest, now: DateTime<Utc>) {
+        if let Some(checked) = update.checked {
+            if checked != self.checked {
+                self.completed_at = checked.then_some(now);
+            }
+            self.checked = checked;
+        }
+        if let Some(value) = update.value {
+            self.value = value;
+        }
+        if let Some(priority) = update.priority {
+            self.priority = priority;
+        }
+        if let Some(tags) = update.tags {
+            self.tags = tags;
+        }
+        if let Some(due_at) = update.due_at {
+            self.due_at = due_at;
+        }
+        self.updated_at = Some(now);
+    }
+
+    /// Bring the editable fields back to their state in `revision` at `now`.
+    fn revert(&mut self, revision: &Todo, now: DateTime<Utc>) {
+        self.value.clone_from(&revision.value);
+        self.checked = revision.checked;
+        self.priority = revision.priority;
+        self.tags.clone_from(&revision.tags);
+        self.due_at = revision.due_at;
+        self.completed_at = if revision.checked {
+            revision.completed_at.or(Some(now))
+        } else {
+            None
+        };
+        self.updated_at = Some(now);
+    }
+}
+
+/// Stored todo with every revision it went through, oldest first. A record always has at
+/// least one revision, records without any are rejected when deserialized.
+#[derive(Serialize, Clone, Debug)]
+#[serde(transparent)]
+pub(super) struct TodoRecord {
+    revisions: Vec<Revision>,
+}
+
+impl<'de> Deserialize<'de> for TodoRecord {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let revisions = Vec::<Revision>::deserialize(deserializer)?;
+        if revisions.is_empty() {
+            return Err(de::Error::custom("todo record without revisions"));
+        }
+        Ok(Self { revisions })
+    }
+}
+
+impl TodoRecord {
+    pub(super) fn new(todo: Todo, now: DateTime<Utc>) -> Self {
+        Self {
+            revisions: vec![Revision {
+                rev: 1,
+                recorded_at: now,
+                todo,
+            }],
+        }
+    }
+
+    /// Latest revision of the todo.
+    pub(super) fn current(&self) -> &Todo {
+        &self
+            .revisions
+            .last()
+            .expect("todo record without revisions")
+            .todo
+    }
+
+    /// Store a new revision made by applying `change` to the current state at `now`, returning
+    /// the states before and after the change.
+    pub(super) fn change(
+        &mut self,
+        now: DateTime<Utc>,
+        change: impl FnOnce(&mut Todo),
+    ) -> (Todo, &Todo) {
+        let before = self.current().clone();
+        let mut todo = before.clone();
+        change(&mut todo);
+        let rev = self.revisions.len() as u32 + 1;
+        self.revisions.push(Revision {
+            rev,
+            recorded_at: now,
+            todo,
+        });
+
+        (before, self.current())
+    }
+
+    /// Revision the todo was in at `at`.
+    fn revision(&self, at: RevisionAt) -> Option<&Revision> {
+        match at {
+            RevisionAt::Rev(rev) => self.revisions.iter().find(|revision| revision.rev == rev),
+            RevisionAt::Time(time) => self
+                .revisions
+                .iter()
+                .rev()
+                .find(|revision| revision.recorded_at <= time),
+        }
+    }
+}
+
+/// Version of a `Todo`.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub(super) struct Revision {
+    /// Number of the revision, starting from 1 and increasing by one with every change.
+    #[schema(example = 1)]
+    rev: u32,
+    /// Time the revision was stored at.
+    recorded_at: DateTime<Utc>,
+    /// State of the todo in this revision.
+    todo: Todo,
+}
+
+/// Point of a todo's history, either a revision number or a time.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum RevisionAt {
+    Rev(u32),
+    Time(DateTime<Utc>),
+}
+
+impl FromStr for RevisionAt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse()
+            .map(Self::Rev)
+            .or_else(|_| DateTime::parse_from_rfc3339(s).map(|time| Self::Time(time.into())))
+            .map_err(|_| format!("expected revision number or RFC 3339 timestamp, got `{s}`"))
+    }
+}
+
+/// Priority of a `Todo`.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum Priority {
+    /// Can wait until everything else is done.
+    Low,
+    /// Default priority of a task.
+    #[default]
+    Normal,
+    /// Should be done before tasks of normal priority.
+    High,
+    /// Needs to be done right away.
+    Urgent,
 }
 
 /// Request to update existing `Todo` item.
@@ -46,72 +706,167 @@ pub(super) struct Todo {
 pub(super) struct TodoUpdateRequest {
     /// Optional new value for the `Todo` task.
     #[schema(example = "Dentist at 14.00")]
-    value: Option<String>,
+    pub(super) value: Option<String>,
     /// Optional check status to mark is the task done or not.
-    checked: Option<bool>,
+    pub(super) checked: Option<bool>,
+    /// Optional new priority of the task.
+    pub(super) priority: Option<Priority>,
+    /// Optional new tags replacing the current tags of the task.
+    pub(super) tags: Option<Vec<String>>,
+    /// Optional new deadline of the task, `null` removes the deadline.
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schema(value_type = Option<DateTime<Utc>>)]
+    pub(super) due_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// Deserialize a field which is present, also as `null`, to `Some`. Together with
+/// `#[serde(default)]` a missing field is `None`, telling it apart from `null`.
+fn present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
 
 /// Todo endpoint error responses
-#[derive(Serialize, Deserialize, Clone, ToSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub(super) enum ErrorResponse {
+    /// When request could not be parsed.
+    BadRequest(String),
     /// When Todo is not found by search term.
     NotFound(String),
     /// When there is a conflict storing a new todo.
     Conflict(String),
     /// When todo endpoint was called without correct credentials
     Unauthorized(String),
+    /// When the request body is larger than allowed
+    PayloadTooLarge(String),
+    /// When the caller exceeded the rate limit of the endpoint
+    TooManyRequests(String),
+}
+
+/// Respond with the status code of the error.
+impl From<ErrorResponse> for HttpResponse {
+    fn from(error: ErrorResponse) -> Self {
+        let status = match &error {
+            ErrorResponse::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ErrorResponse::NotFound(_) => StatusCode::NOT_FOUND,
+            ErrorResponse::Conflict(_) => StatusCode::CONFLICT,
+            ErrorResponse::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ErrorResponse::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorResponse::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+        };
+
+        HttpResponse::build(status).json(error)
+    }
+}
+
+/// List todos query
+#[derive(Deserialize, Debug, IntoParams)]
+pub(super) struct ListTodos {
+    /// Include todos moved to the trash, defaults to `false`.
+    include_deleted: Option<bool>,
 }
 
 /// Get list of todos.
 ///
-/// List todos from in-memory todo store.
+/// List todos of the caller's tenant from in-memory todo store. Todos in the trash are
+/// only listed when `include_deleted=true` is given. Todos are listed in order of their id,
+/// not in the order they were created.
 ///
 /// One could call the api endpoint with following curl.
 /// ```text
-/// curl localhost:8080/todo
+/// curl localhost:8080/v2/todo
 /// ```
 #[utoipa::path(
+    context_path = "/todo",
+    params(
+        ListTodos
+    ),
     responses(
         (status = 200, description = "List current todo items", body = [Todo])
     )
 )]
-#[get("/todo")]
-pub(super) async fn get_todos(todo_store: Data<TodoStore>) -> impl Responder {
-    let todos = todo_store.todos.lock().unwrap();
+#[get("")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn get_todos(
+    query: Query<ListTodos>,
+    principal: Principal,
+    todo_store: Data<TodoStore>,
+) -> impl Responder {
+    let include_deleted = query.include_deleted.unwrap_or_default();
 
-    HttpResponse::Ok().json(todos.clone())
+    HttpResponse::Ok().json(todo_store.list(&principal.tenant, include_deleted))
+}
+
+/// Get list of todos in the trash.
+///
+/// List deleted todos of the caller's tenant which were not purged yet. They can be brought
+/// back with restore.
+///
+/// One could call the api endpoint with following curl.
+/// ```text
+/// curl localhost:8080/v2/todo/trash
+/// ```
+#[utoipa::path(
+    context_path = "/todo",
+    responses(
+        (status = 200, description = "List todo items in the trash", body = [Todo])
+    )
+)]
+#[get("/trash")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn get_trash(principal: Principal, todo_store: Data<TodoStore>) -> impl Responder {
+    todo_store.read(&principal.tenant, |records| {
+        HttpResponse::Ok().json(
+            records
+                .values()
+                .map(TodoRecord::current)
+                .filter(|todo| todo.is_deleted())
+                .collect::<Vec<_>>(),
+        )
+    })
 }
 
 /// Create new Todo to shared in-memory storage.
 ///
 /// Post a new `Todo` in request body as json to store it. Api will return
 /// created `Todo` on success or `ErrorResponse::Conflict` if todo with same id already exists.
+/// Bodies which are not a valid `Todo` are rejected with `ErrorResponse::BadRequest`.
 ///
 /// One could call the api with.
 /// ```text
-/// curl localhost:8080/todo -d '{"id": 1, "value": "Buy movie ticket", "checked": false}'
+/// curl localhost:8080/v2/todo -d '{"id": 1, "value": "Buy movie ticket", "checked": false}'
 /// ```
 #[utoipa::path(
+    context_path = "/todo",
     request_body = Todo,
     responses(
         (status = 201, description = "Todo created successfully", body = Todo),
-        (status = 409, description = "Todo with id already exists", body = ErrorResponse, example = json!(ErrorResponse::Conflict(String::from("id = 1"))))
+        (status = 400, description = "Request body could not be parsed", body = ErrorResponse, example = json!(ErrorResponse::BadRequest(String::from("Json deserialize error: missing field `checked` at line 1 column 38")))),
+        (status = 409, description = "Todo with id already exists", body = ErrorResponse, example = json!(ErrorResponse::Conflict(String::from("id = 1")))),
+        (status = 413, description = "Request body too large", body = ErrorResponse, example = json!(ErrorResponse::PayloadTooLarge(String::from("request body exceeds 65536 bytes"))))
     )
 )]
-#[post("/todo")]
-pub(super) async fn create_todo(todo: Json<Todo>, todo_store: Data<TodoStore>) -> impl Responder {
-    let mut todos = todo_store.todos.lock().unwrap();
-    let todo = &todo.into_inner();
-
-    todos
-        .iter()
-        .find(|existing| existing.id == todo.id)
-        .map(|existing| {
-            HttpResponse::Conflict().json(ErrorResponse::Conflict(format!("id = {}", existing.id)))
-        })
-        .unwrap_or_else(|| {
-            todos.push(todo.clone());
+#[post("")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn create_todo(
+    principal: Principal,
+    todo: Json<Todo>,
+    request_id: RequestId,
+    todo_store: Data<TodoStore>,
+    audit: Data<AuditLog>,
+) -> impl Responder {
+    let actor = Actor::new(&principal, &request_id);
 
+    todo_store
+        .create(&principal.tenant, todo.into_inner(), &audit, &actor)
+        .map_or_else(HttpResponse::from, |todo| {
             HttpResponse::Created().json(todo)
         })
 }
@@ -120,150 +875,1004 @@ pub(super) async fn create_todo(todo: Json<Todo>, todo_store: Data<TodoStore>) -
 ///
 /// This endpoint needs `api_key` authentication in order to call. Api key can be found from README.md.
 ///
-/// Api will delete todo from shared in-memory storage by the provided id and return success 200.
-/// If storage does not contain `Todo` with given id 404 not found will be returned.
+/// Api will move todo with the provided id to the trash and return success 200. Todos in the
+/// trash are purged permanently once their retention expires unless they are restored before.
+/// If storage does not contain `Todo` with given id or it is already in the trash 404 not
+/// found will be returned.
 #[utoipa::path(
+    context_path = "/todo",
     responses(
         (status = 200, description = "Todo deleted successfully"),
         (status = 401, description = "Unauthorized to delete Todo", body = ErrorResponse, example = json!(ErrorResponse::Unauthorized(String::from("missing api key")))),
         (status = 404, description = "Todo not found by id", body = ErrorResponse, example = json!(ErrorResponse::NotFound(String::from("id = 1"))))
     ),
     params(
-        ("id", description = "Unique storage id of Todo")
+        ("id" = TodoId, Path, description = "Unique storage id of Todo")
     ),
     security(
         ("api_key" = [])
     )
 )]
-#[delete("/todo/{id}", wrap = "RequireApiKey")]
-pub(super) async fn delete_todo(id: Path<i32>, todo_store: Data<TodoStore>) -> impl Responder {
-    let mut todos = todo_store.todos.lock().unwrap();
-    let id = id.into_inner();
+#[delete("/{id}", wrap = "RequireApiKey")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn delete_todo(
+    id: Path<TodoId>,
+    principal: Principal,
+    request_id: RequestId,
+    todo_store: Data<TodoStore>,
+    audit: Data<AuditLog>,
+) -> impl Responder {
+    let actor = Actor::new(&principal, &request_id);
 
-    let new_todos = todos
-        .iter()
-        .filter(|todo| todo.id != id)
-        .cloned()
-        .collect::<Vec<_>>();
+    todo_store
+        .delete(&principal.tenant, id.into_inner(), &audit, &actor)
+        .map_or_else(HttpResponse::from, |()| HttpResponse::Ok().finish())
+}
 
-    if new_todos.len() == todos.len() {
-        HttpResponse::NotFound().json(ErrorResponse::NotFound(format!("id = {id}")))
-    } else {
-        *todos = new_todos;
-        HttpResponse::Ok().finish()
-    }
+/// Restore Todo from the trash by given path variable id.
+///
+/// This endpoint needs `api_key` authentication in order to call. Api key can be found from README.md.
+///
+/// Api will move todo with the provided id out of the trash and return the restored `Todo`
+/// with status 200. If the trash does not contain `Todo` with given id 404 not found will be
+/// returned.
+#[utoipa::path(
+    context_path = "/todo",
+    responses(
+        (status = 200, description = "Todo restored successfully", body = Todo),
+        (status = 401, description = "Unauthorized to restore Todo", body = ErrorResponse, example = json!(ErrorResponse::Unauthorized(String::from("missing api key")))),
+        (status = 404, description = "Todo not found from the trash by id", body = ErrorResponse, example = json!(ErrorResponse::NotFound(String::from("id = 1"))))
+    ),
+    params(
+        ("id" = TodoId, Path, description = "Unique storage id of Todo")
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+#[post("/{id}/restore", wrap = "RequireApiKey")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn restore_todo(
+    id: Path<TodoId>,
+    principal: Principal,
+    request_id: RequestId,
+    todo_store: Data<TodoStore>,
+    audit: Data<AuditLog>,
+) -> impl Responder {
+    let actor = Actor::new(&principal, &request_id);
+
+    todo_store
+        .restore(&principal.tenant, id.into_inner(), &audit, &actor)
+        .map_or_else(HttpResponse::from, |todo| HttpResponse::Ok().json(todo))
+}
+
+/// Get todo query
+#[derive(Deserialize, Debug, IntoParams)]
+pub(super) struct GetTodo {
+    /// Revision number or RFC 3339 timestamp to read the todo at, defaults to latest revision.
+    #[param(example = "2")]
+    at: Option<String>,
 }
 
 /// Get Todo by given todo id.
 ///
-/// Return found `Todo` with status 200 or 404 not found if `Todo` is not found from shared in-memory storage.
+/// Return found `Todo` with status 200 or 404 not found if `Todo` is not found from shared in-memory storage
+/// or it is in the trash.
+///
+/// With `at` query parameter the todo is returned as it was in the given revision or at the
+/// given time, 404 not found is returned if it did not exist yet or was in the trash then.
 #[utoipa::path(
+    context_path = "/todo",
     responses(
         (status = 200, description = "Todo found from storage", body = Todo),
+        (status = 400, description = "Invalid `at` query parameter", body = ErrorResponse, example = json!(ErrorResponse::BadRequest(String::from("expected revision number or RFC 3339 timestamp, got `yesterday`")))),
         (status = 404, description = "Todo not found by id", body = ErrorResponse, example = json!(ErrorResponse::NotFound(String::from("id = 1"))))
     ),
     params(
-        ("id", description = "Unique storage id of Todo")
+        ("id" = TodoId, Path, description = "Unique storage id of Todo"),
+        GetTodo
     )
 )]
-#[get("/todo/{id}")]
-pub(super) async fn get_todo_by_id(id: Path<i32>, todo_store: Data<TodoStore>) -> impl Responder {
-    let todos = todo_store.todos.lock().unwrap();
+#[get("/{id}")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn get_todo_by_id(
+    id: Path<TodoId>,
+    query: Query<GetTodo>,
+    principal: Principal,
+    todo_store: Data<TodoStore>,
+) -> impl Responder {
+    let at = match query.at.as_deref().map(RevisionAt::from_str).transpose() {
+        Ok(at) => at,
+        Err(error) => return ErrorResponse::BadRequest(error).into(),
+    };
+
+    todo_store
+        .get(&principal.tenant, id.into_inner(), at)
+        .map_or_else(HttpResponse::from, |todo| HttpResponse::Ok().json(todo))
+}
+
+/// List revisions of Todo by given todo id.
+///
+/// Return every revision of the `Todo` oldest first with status 200, including the revisions
+/// of a todo in the trash, or 404 not found if `Todo` is not found from shared in-memory storage.
+#[utoipa::path(
+    context_path = "/todo",
+    responses(
+        (status = 200, description = "Revisions of the todo", body = [Revision]),
+        (status = 404, description = "Todo not found by id", body = ErrorResponse, example = json!(ErrorResponse::NotFound(String::from("id = 1"))))
+    ),
+    params(
+        ("id" = TodoId, Path, description = "Unique storage id of Todo")
+    )
+)]
+#[get("/{id}/revisions")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn get_revisions(
+    id: Path<TodoId>,
+    principal: Principal,
+    todo_store: Data<TodoStore>,
+) -> impl Responder {
     let id = id.into_inner();
 
-    todos
-        .iter()
-        .find(|todo| todo.id == id)
-        .map(|todo| HttpResponse::Ok().json(todo))
-        .unwrap_or_else(|| {
-            HttpResponse::NotFound().json(ErrorResponse::NotFound(format!("id = {id}")))
-        })
+    todo_store.read(&principal.tenant, |records| {
+        records
+            .get(&id)
+            .map(|record| HttpResponse::Ok().json(&record.revisions))
+            .unwrap_or_else(|| {
+                HttpResponse::NotFound().json(ErrorResponse::NotFound(format!("id = {id}")))
+            })
+    })
+}
+
+/// Revert Todo with given id to an earlier revision.
+///
+/// This endpoint needs `api_key` authentication in order to call.
+/// Calls without a valid api key are rejected with 401, they used to be served and only
+/// logged.
+///
+/// Stores a new revision of the `Todo` with the value, check status, priority, tags and
+/// deadline of revision `rev` and returns the reverted `Todo` with status 200. If the todo is
+/// not found, it is in the trash or it has no revision `rev` then 404 not found is returned.
+#[utoipa::path(
+    context_path = "/todo",
+    responses(
+        (status = 200, description = "Todo reverted successfully", body = Todo),
+        (status = 404, description = "Todo or revision not found", body = ErrorResponse, example = json!(ErrorResponse::NotFound(String::from("id = 1, rev = 3"))))
+    ),
+    params(
+        ("id" = TodoId, Path, description = "Unique storage id of Todo"),
+        ("rev", description = "Revision to revert the todo to")
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+#[post("/{id}/revert/{rev}", wrap = "RequireApiKey")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn revert_todo(
+    path: Path<(TodoId, u32)>,
+    principal: Principal,
+    request_id: RequestId,
+    todo_store: Data<TodoStore>,
+    audit: Data<AuditLog>,
+) -> impl Responder {
+    let (id, rev) = path.into_inner();
+
+    todo_store.write(&principal.tenant, |records| {
+        records
+            .get_mut(&id)
+            .filter(|record| !record.current().is_deleted())
+            .and_then(|record| {
+                let revision = record.revision(RevisionAt::Rev(rev))?.todo.clone();
+                let now = Utc::now();
+                let (before, after) = record.change(now, |todo| todo.revert(&revision, now));
+                todo_store.index.insert(&principal.tenant, id, &after.value);
+                audit.record(
+                    &Actor::new(&principal, &request_id),
+                    &principal.tenant,
+                    Operation::Revert,
+                    Some(&before),
+                    Some(after),
+                );
+
+                Some(HttpResponse::Ok().json(after))
+            })
+            .unwrap_or_else(|| {
+                HttpResponse::NotFound()
+                    .json(ErrorResponse::NotFound(format!("id = {id}, rev = {rev}")))
+            })
+    })
 }
 
 /// Update Todo with given id.
 ///
-/// This endpoint supports optional authentication.
+/// This endpoint needs `api_key` authentication in order to call.
+/// Calls without a valid api key are rejected with 401, they used to be served and only
+/// logged.
 ///
 /// Tries to update `Todo` by given id as path variable. If todo is found by id values are
 /// updated according `TodoUpdateRequest` and updated `Todo` is returned with status 200.
-/// If todo is not found then 404 not found is returned.
+/// If todo is not found or it is in the trash then 404 not found is returned.
 #[utoipa::path(
+    context_path = "/todo",
     request_body = TodoUpdateRequest,
     responses(
         (status = 200, description = "Todo updated successfully", body = Todo),
-        (status = 404, description = "Todo not found by id", body = ErrorResponse, example = json!(ErrorResponse::NotFound(String::from("id = 1"))))
+        (status = 400, description = "Request body could not be parsed", body = ErrorResponse, example = json!(ErrorResponse::BadRequest(String::from("Json deserialize error: invalid type: string \"yes\", expected a boolean at line 1 column 17")))),
+        (status = 404, description = "Todo not found by id", body = ErrorResponse, example = json!(ErrorResponse::NotFound(String::from("id = 1")))),
+        (status = 413, description = "Request body too large", body = ErrorResponse, example = json!(ErrorResponse::PayloadTooLarge(String::from("request body exceeds 65536 bytes"))))
     ),
     params(
-        ("id", description = "Unique storage id of Todo")
+        ("id" = TodoId, Path, description = "Unique storage id of Todo")
     ),
     security(
-        (),
         ("api_key" = [])
     )
 )]
-#[put("/todo/{id}", wrap = "LogApiKey")]
+#[put("/{id}", wrap = "RequireApiKey")]
+#[tracing::instrument(skip_all)]
 pub(super) async fn update_todo(
-    id: Path<i32>,
+    id: Path<TodoId>,
+    principal: Principal,
     todo: Json<TodoUpdateRequest>,
+    request_id: RequestId,
     todo_store: Data<TodoStore>,
+    audit: Data<AuditLog>,
 ) -> impl Responder {
-    let mut todos = todo_store.todos.lock().unwrap();
-    let id = id.into_inner();
-    let todo = todo.into_inner();
-
-    todos
-        .iter_mut()
-        .find_map(|todo| if todo.id == id { Some(todo) } else { None })
-        .map(|existing_todo| {
-            if let Some(checked) = todo.checked {
-                existing_todo.checked = checked;
-            }
-            if let Some(value) = todo.value {
-                existing_todo.value = value;
-            }
+    let actor = Actor::new(&principal, &request_id);
 
-            HttpResponse::Ok().json(existing_todo)
-        })
-        .unwrap_or_else(|| {
-            HttpResponse::NotFound().json(ErrorResponse::NotFound(format!("id = {id}")))
-        })
+    todo_store
+        .update(
+            &principal.tenant,
+            id.into_inner(),
+            todo.into_inner(),
+            &audit,
+            &actor,
+        )
+        .map_or_else(HttpResponse::from, |todo| HttpResponse::Ok().json(todo))
 }
 
 /// Search todos Query
 #[derive(Deserialize, Debug, IntoParams)]
 pub(super) struct SearchTodos {
-    /// Content that should be found from Todo's value field
+    /// Words that should be found from Todo's value field, every todo matches when empty.
     value: Option<String>,
+    /// Include todos moved to the trash, defaults to `false`.
+    include_deleted: Option<bool>,
+    /// Also match other forms of the words, e.g. `buying` for `buy`, defaults to `false`.
+    stem: Option<bool>,
+    /// Also match words within a typo or two, defaults to `true`.
+    fuzzy: Option<bool>,
+}
+
+impl SearchTodos {
+    /// Search the todos of `tenant` in `todo_store`, defaulting the missing parameters.
+    pub(super) fn search(&self, todo_store: &TodoStore, tenant: &Tenant) -> Vec<SearchHit> {
+        let options = SearchOptions {
+            stem: self.stem.unwrap_or_default(),
+            fuzzy: self.fuzzy.unwrap_or(true),
+        };
+
+        todo_store.search(
+            tenant,
+            self.value.as_deref().unwrap_or_default(),
+            self.include_deleted.unwrap_or_default(),
+            options,
+        )
+    }
+}
+
+/// `Todo` found by search.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub(super) struct SearchHit {
+    /// Relevance of the todo to the search, higher is better.
+    #[schema(example = 1.1)]
+    pub(super) score: f64,
+    /// Words of the todo's value matching the search, in order.
+    pub(super) highlights: Vec<Highlight>,
+    /// Found todo.
+    pub(super) todo: Todo,
 }
 
 /// Search Todos with by value
 ///
-/// Perform search from `Todo`s present in in-memory storage by matching Todo's value to
-/// value provided as query parameter. Returns 200 and matching `Todo` items.
+/// Perform full-text search from `Todo`s of the caller's tenant by matching words of Todo's
+/// value to words of value provided as query parameter. Returns 200 and matching `Todo` items
+/// with their score and the matching words highlighted, best match first.
+///
+/// Words are matched regardless of case and diacritics. Every word of the query has to match a
+/// word of the todo, either exactly, as the beginning of a word or, unless `fuzzy=false` is
+/// given, within a typo or two. With `stem=true` other forms of the words match as well.
+///
+/// One could call the api endpoint with following curl.
+/// ```text
+/// curl 'localhost:8080/v2/todo/search?value=grocerys&stem=true'
+/// ```
 #[utoipa::path(
+    context_path = "/todo",
     params(
         SearchTodos
     ),
     responses(
-        (status = 200, description = "Search Todos did not result error", body = [Todo]),
+        (status = 200, description = "Search Todos did not result error", body = [SearchHit]),
     )
 )]
-#[get("/todo/search")]
+#[get("/search")]
+#[tracing::instrument(skip_all)]
 pub(super) async fn search_todos(
     query: Query<SearchTodos>,
+    principal: Principal,
     todo_store: Data<TodoStore>,
 ) -> impl Responder {
-    let todos = todo_store.todos.lock().unwrap();
-
-    HttpResponse::Ok().json(
-        todos
-            .iter()
-            .filter(|todo| {
-                todo.value
-                    .to_lowercase()
-                    .contains(&query.value.as_ref().unwrap_or(&String::new()).to_lowercase())
+    HttpResponse::Ok().json(query.search(&todo_store, &principal.tenant))
+}
+
+/// Search todos query of the first version.
+#[derive(Deserialize, Debug, IntoParams)]
+pub(super) struct SearchTodosV1 {
+    /// Content that should be found from Todo's value field
+    value: Option<String>,
+    /// Include todos moved to the trash, defaults to `false`.
+    include_deleted: Option<bool>,
+}
+
+/// Search Todos with by value
+///
+/// Perform search from `Todo`s of the caller's tenant present in in-memory storage by matching
+/// Todo's value to value provided as query parameter. Returns 200 and `Todo` items whose value
+/// contains the given value regardless of case, in order of their id.
+///
+/// The current version of the api searches by words and ranks the found todos instead.
+///
+/// One could call the api endpoint with following curl.
+/// ```text
+/// curl 'localhost:8080/v1/todo/search?value=milk'
+/// ```
+#[utoipa::path(
+    context_path = "/todo",
+    operation_id = "search_todos",
+    params(
+        SearchTodosV1
+    ),
+    responses(
+        (status = 200, description = "Search Todos did not result error", body = [Todo]),
+    )
+)]
+#[get("/search")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn search_todos_v1(
+    query: Query<SearchTodosV1>,
+    principal: Principal,
+    todo_store: Data<TodoStore>,
+) -> impl Responder {
+    HttpResponse::Ok().json(todo_store.containing(
+        &principal.tenant,
+        query.value.as_deref().unwrap_or_default(),
+        query.include_deleted.unwrap_or_default(),
+    ))
+}
+
+/// Format of exported and imported todos.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum TransferFormat {
+    /// Newline delimited JSON, one `Todo` per line.
+    Ndjson,
+    /// Comma separated values with a header row.
+    Csv,
+}
+
+impl TransferFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Ndjson => "application/x-ndjson",
+            Self::Csv => "text/csv",
+        }
+    }
+
+    /// Resolve format from an `Accept` or `Content-Type` header value.
+    fn from_media_type(media_type: &str) -> Option<Self> {
+        media_type
+            .split(',')
+            .map(|media_type| media_type.split(';').next().unwrap_or_default().trim())
+            .find_map(|media_type| match media_type {
+                "application/x-ndjson" => Some(Self::Ndjson),
+                "text/csv" => Some(Self::Csv),
+                _ => None,
             })
+    }
+
+    /// Format given as query parameter, or else resolved from `header`.
+    fn requested(
+        format: Option<Self>,
+        req: &HttpRequest,
+        header: header::HeaderName,
+    ) -> Option<Self> {
+        format.or_else(|| {
+            req.headers()
+                .get(header)
+                .and_then(|value| value.to_str().ok())
+                .and_then(Self::from_media_type)
+        })
+    }
+
+    /// Format of `body`, NDJSON if it starts with a JSON object and CSV otherwise.
+    fn sniff(body: &[u8]) -> Self {
+        match body.trim_ascii_start().first() {
+            Some(b'{') => Self::Ndjson,
+            _ => Self::Csv,
+        }
+    }
+}
+
+/// How to handle imported todos whose id already exists in the store.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum ConflictPolicy {
+    /// Keep the stored todo and skip the imported one.
+    Skip,
+    /// Replace the stored todo with the imported one.
+    Overwrite,
+    /// Reject the whole import with `ErrorResponse::Conflict`.
+    #[default]
+    Fail,
+}
+
+/// Header row of CSV export, the fields of [`CsvTodo`] in order.
+const CSV_HEADER: &str =
+    "id,value,checked,priority,tags,due_at,created_at,updated_at,completed_at,deleted_at\n";
+
+/// Row of CSV export and import.
+#[derive(Serialize, Deserialize)]
+struct CsvTodo {
+    id: TodoId,
+    value: String,
+    checked: bool,
+    #[serde(default)]
+    priority: Priority,
+    /// Tags separated by `;`, with `;` and `\` within tags escaped by `\`.
+    #[serde(default)]
+    tags: String,
+    #[serde(default)]
+    due_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+impl From<Todo> for CsvTodo {
+    fn from(todo: Todo) -> Self {
+        Self {
+            id: todo.id,
+            value: todo.value,
+            checked: todo.checked,
+            priority: todo.priority,
+            tags: todo
+                .tags
+                .iter()
+                .map(|tag| tag.replace('\\', "\\\\").replace(';', "\\;"))
+                .collect::<Vec<_>>()
+                .join(";"),
+            due_at: todo.due_at,
+            created_at: todo.created_at,
+            updated_at: todo.updated_at,
+            completed_at: todo.completed_at,
+            deleted_at: todo.deleted_at,
+        }
+    }
+}
+
+impl From<CsvTodo> for Todo {
+    fn from(row: CsvTodo) -> Self {
+        Self {
+            id: row.id,
+            value: row.value,
+            checked: row.checked,
+            priority: row.priority,
+            tags: split_tags(&row.tags),
+            due_at: row.due_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            completed_at: row.completed_at,
+            deleted_at: row.deleted_at,
+        }
+    }
+}
+
+/// Split the `;` separated tags of a CSV row, unescaping `\;` and `\\` within tags.
+fn split_tags(tags: &str) -> Vec<String> {
+    let mut split = vec![String::new()];
+    let mut chars = tags.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => split.push(String::new()),
+            '\\' => split.last_mut().unwrap().extend(chars.next()),
+            c => split.last_mut().unwrap().push(c),
+        }
+    }
+    split.retain(|tag| !tag.is_empty());
+    split
+}
+
+/// Export todos query
+#[derive(Deserialize, Debug, IntoParams)]
+pub(super) struct ExportTodos {
+    /// Format of the export, overrides the format negotiated from `Accept` header.
+    format: Option<TransferFormat>,
+}
+
+/// Export all todos.
+///
+/// Streams every `Todo` of the caller's tenant from the in-memory storage as newline
+/// delimited JSON or as CSV with a header row, which is written even if there are no todos.
+/// The format is taken from `format` query parameter or negotiated from `Accept` header and
+/// defaults to newline delimited JSON.
+///
+/// One could export todos as CSV with.
+/// ```text
+/// curl localhost:8080/v2/todo/export -H 'Accept: text/csv'
+/// ```
+#[utoipa::path(
+    context_path = "/todo",
+    params(
+        ExportTodos
+    ),
+    responses(
+        (status = 200, description = "All todos in requested format", body = String, content_type = ["application/x-ndjson", "text/csv"])
+    )
+)]
+#[get("/export")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn export_todos(
+    req: HttpRequest,
+    query: Query<ExportTodos>,
+    principal: Principal,
+    todo_store: Data<TodoStore>,
+) -> impl Responder {
+    let format = TransferFormat::requested(query.format, &req, header::ACCEPT)
+        .unwrap_or(TransferFormat::Ndjson);
+    let todos = todo_store.read(&principal.tenant, |records| {
+        records
+            .values()
+            .map(TodoRecord::current)
             .cloned()
-            .collect::<Vec<_>>(),
+            .collect::<Vec<_>>()
+    });
+
+    let header =
+        (format == TransferFormat::Csv).then_some(Ok(Bytes::from_static(CSV_HEADER.as_bytes())));
+    let lines = todos.into_iter().map(move |todo| {
+        let mut line = Vec::new();
+        let result = match format {
+            TransferFormat::Ndjson => serde_json::to_writer(&mut line, &todo)
+                .map(|_| line.push(b'\n'))
+                .map_err(actix_web::error::ErrorInternalServerError),
+            TransferFormat::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(&mut line);
+                writer
+                    .serialize(CsvTodo::from(todo))
+                    .and_then(|_| writer.flush().map_err(csv::Error::from))
+                    .map_err(actix_web::error::ErrorInternalServerError)
+            }
+        };
+        result.map(|_| Bytes::from(line))
+    });
+
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .streaming(futures::stream::iter(header.into_iter().chain(lines)))
+}
+
+/// Import todos query
+#[derive(Deserialize, Debug, IntoParams)]
+pub(super) struct ImportTodos {
+    /// Format of the request body, overrides the format resolved from `Content-Type` header.
+    format: Option<TransferFormat>,
+    /// How to handle todos whose id already exists, defaults to `fail`.
+    conflict: Option<ConflictPolicy>,
+}
+
+/// Summary of an import.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, Default)]
+pub(super) struct ImportReport {
+    /// Number of todos added to the storage.
+    imported: usize,
+    /// Number of existing todos replaced with the imported ones.
+    overwritten: usize,
+    /// Number of imported todos skipped because their id already existed.
+    skipped: usize,
+}
+
+/// Import todos.
+///
+/// Read todos from request body in newline delimited JSON or CSV format, the same formats
+/// produced by export. The format is taken from `format` query parameter or resolved from
+/// `Content-Type` header. Otherwise, e.g. for `application/octet-stream` bodies sent by the
+/// generated client, a body starting with a JSON object is read as newline delimited JSON and
+/// any other body as CSV.
+///
+/// Todos whose id already exists are handled according to `conflict` query parameter. With
+/// the default `fail` policy nothing is imported and `ErrorResponse::Conflict` is returned if
+/// any of the ids already exists.
+///
+/// One could import a CSV export with.
+/// ```text
+/// curl localhost:8080/v2/todo/import?conflict=skip -H 'Content-Type: text/csv' --data-binary @todos.csv
+/// ```
+#[utoipa::path(
+    context_path = "/todo",
+    params(
+        ImportTodos
+    ),
+    request_body(content = Vec<u8>, description = "Todos in newline delimited JSON or CSV format", content_type = "application/octet-stream"),
+    responses(
+        (status = 200, description = "Todos imported successfully", body = ImportReport),
+        (status = 400, description = "Request body could not be parsed", body = ErrorResponse, example = json!(ErrorResponse::BadRequest(String::from("record 1: missing field `checked`")))),
+        (status = 409, description = "Todo with id already exists", body = ErrorResponse, example = json!(ErrorResponse::Conflict(String::from("id = 1")))),
+        (status = 413, description = "Request body too large", body = ErrorResponse, example = json!(ErrorResponse::PayloadTooLarge(String::from("request body exceeds 10485760 bytes"))))
+    )
+)]
+#[post("/import")]
+#[tracing::instrument(skip_all)]
+pub(super) async fn import_todos(
+    req: HttpRequest,
+    query: Query<ImportTodos>,
+    mut payload: Payload,
+    principal: Principal,
+    request_id: RequestId,
+    todo_store: Data<TodoStore>,
+    audit: Data<AuditLog>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let format = TransferFormat::requested(query.format, &req, header::CONTENT_TYPE);
+    let limit = req
+        .app_data::<Data<BodyLimits>>()
+        .map_or(usize::MAX, |limits| limits.import);
+
+    let mut body = BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk?;
+        if body.len() + chunk.len() > limit {
+            return Ok(payload_too_large(limit));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    let format = format.unwrap_or_else(|| TransferFormat::sniff(&body));
+    let todos = match parse_todos(&body, format) {
+        Ok(todos) => todos,
+        Err(error) => {
+            return Ok(HttpResponse::BadRequest().json(ErrorResponse::BadRequest(error)));
+        }
+    };
+
+    let actor = Actor::new(&principal, &request_id);
+    Ok(
+        match todo_store.import(
+            principal.tenant,
+            todos,
+            query.conflict.unwrap_or_default(),
+            &audit,
+            &actor,
+        ) {
+            Ok(report) => HttpResponse::Ok().json(report),
+            Err(id) => HttpResponse::Conflict().json(ErrorResponse::Conflict(format!("id = {id}"))),
+        },
     )
 }
+
+fn parse_todos(body: &[u8], format: TransferFormat) -> Result<Vec<Todo>, String> {
+    match format {
+        TransferFormat::Ndjson => body
+            .split(|byte| *byte == b'\n')
+            .enumerate()
+            .filter(|(_, line)| !line.trim_ascii().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_slice(line).map_err(|error| format!("line {}: {error}", index + 1))
+            })
+            .collect(),
+        TransferFormat::Csv => csv::Reader::from_reader(body)
+            .deserialize::<CsvTodo>()
+            .enumerate()
+            .map(|(index, row)| {
+                row.map(Todo::from)
+                    .map_err(|error| format!("record {}: {error}", index + 1))
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        num::NonZeroUsize,
+    };
+
+    use actix_web::{
+        http::StatusCode,
+        test::{self, TestRequest},
+        App,
+    };
+    use proptest::{collection, option, prelude::*, sample};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{audit, auth::ApiKeys, API_KEY_NAME};
+
+    const API_KEY: &str = "utoipa-rocks";
+
+    const WORDS: [&str; 6] = ["buy", "milk", "bread", "call", "mom", "dentist"];
+
+    /// Call of the todo api.
+    #[derive(Clone, Debug)]
+    enum Call {
+        Create {
+            id: i32,
+            value: String,
+        },
+        Update {
+            id: i32,
+            value: Option<String>,
+            checked: Option<bool>,
+        },
+        Delete {
+            id: i32,
+        },
+        Get {
+            id: i32,
+        },
+        Search {
+            value: String,
+        },
+    }
+
+    fn value() -> impl Strategy<Value = String> {
+        collection::vec(sample::select(WORDS.to_vec()), 1..4).prop_map(|words| words.join(" "))
+    }
+
+    /// Calls on a handful of ids, so they often collide.
+    fn call() -> impl Strategy<Value = Call> {
+        let id = 1..6;
+        prop_oneof![
+            (id.clone(), value()).prop_map(|(id, value)| Call::Create { id, value }),
+            (id.clone(), option::of(value()), option::of(any::<bool>()))
+                .prop_map(|(id, value, checked)| Call::Update { id, value, checked }),
+            id.clone().prop_map(|id| Call::Delete { id }),
+            id.prop_map(|id| Call::Get { id }),
+            sample::select(WORDS.to_vec()).prop_map(|word| Call::Search {
+                value: String::from(word)
+            }),
+        ]
+    }
+
+    /// Reference model of a todo list.
+    #[derive(Clone, Debug, PartialEq)]
+    struct ModelTodo {
+        value: String,
+        checked: bool,
+        deleted: bool,
+    }
+
+    fn assert_matches(todo: &Todo, model: &ModelTodo) -> Result<(), TestCaseError> {
+        prop_assert_eq!(&todo.value, &model.value);
+        prop_assert_eq!(todo.checked, model.checked);
+        prop_assert!(!todo.is_deleted());
+        Ok(())
+    }
+
+    /// Make `calls` to an in-process app and check every response against the model.
+    async fn check_calls(calls: Vec<Call>) -> Result<(), TestCaseError> {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(ApiKeys::default()))
+                .configure(configure(
+                    Data::new(TodoStore::default()),
+                    Deprecation {
+                        deprecated_at: DateTime::UNIX_EPOCH,
+                        sunset_at: DateTime::UNIX_EPOCH,
+                    },
+                ))
+                .configure(audit::configure(Data::new(AuditLog::in_memory(
+                    NonZeroUsize::new(100).unwrap(),
+                )))),
+        )
+        .await;
+        let request = |request: TestRequest| request.insert_header((API_KEY_NAME, API_KEY));
+        let mut model = BTreeMap::<i32, ModelTodo>::new();
+
+        for call in calls {
+            let live = |model: &BTreeMap<i32, ModelTodo>, id| {
+                model.get(&id).filter(|todo| !todo.deleted).cloned()
+            };
+
+            match call {
+                Call::Create { id, value } => {
+                    let response = request(TestRequest::post().uri("/todo"))
+                        .set_json(json!({"id": id, "value": value, "checked": false}))
+                        .send_request(&app)
+                        .await;
+                    // Ids stay unique, also with the todo in the trash.
+                    if model.contains_key(&id) {
+                        prop_assert_eq!(response.status(), StatusCode::CONFLICT);
+                        continue;
+                    }
+                    prop_assert_eq!(response.status(), StatusCode::CREATED);
+                    let todo = ModelTodo {
+                        value,
+                        checked: false,
+                        deleted: false,
+                    };
+                    assert_matches(&test::read_body_json(response).await, &todo)?;
+                    model.insert(id, todo);
+                }
+                Call::Update { id, value, checked } => {
+                    let response = request(TestRequest::put().uri(&format!("/todo/{id}")))
+                        .set_json(json!({"value": value, "checked": checked}))
+                        .send_request(&app)
+                        .await;
+                    let Some(mut todo) = live(&model, id) else {
+                        prop_assert_eq!(response.status(), StatusCode::NOT_FOUND);
+                        continue;
+                    };
+                    prop_assert_eq!(response.status(), StatusCode::OK);
+                    todo.value = value.unwrap_or(todo.value);
+                    todo.checked = checked.unwrap_or(todo.checked);
+                    assert_matches(&test::read_body_json(response).await, &todo)?;
+                    model.insert(id, todo);
+                }
+                Call::Delete { id } => {
+                    let response = request(TestRequest::delete().uri(&format!("/todo/{id}")))
+                        .send_request(&app)
+                        .await;
+                    let Some(mut todo) = live(&model, id) else {
+                        prop_assert_eq!(response.status(), StatusCode::NOT_FOUND);
+                        continue;
+                    };
+                    prop_assert_eq!(response.status(), StatusCode::OK);
+                    todo.deleted = true;
+                    model.insert(id, todo);
+                }
+                Call::Get { id } => {
+                    let response = request(TestRequest::get().uri(&format!("/todo/{id}")))
+                        .send_request(&app)
+                        .await;
+                    match live(&model, id) {
+                        Some(todo) => {
+                            prop_assert_eq!(response.status(), StatusCode::OK);
+                            assert_matches(&test::read_body_json(response).await, &todo)?;
+                        }
+                        None => prop_assert_eq!(response.status(), StatusCode::NOT_FOUND),
+                    }
+                }
+                Call::Search { value } => {
+                    // The first version finds the todos containing the value.
+                    let response =
+                        request(TestRequest::get().uri(&format!("/todo/search?value={value}")))
+                            .send_request(&app)
+                            .await;
+                    prop_assert_eq!(response.status(), StatusCode::OK);
+                    let todos: Vec<Todo> = test::read_body_json(response).await;
+                    let ids = todos.iter().map(|todo| todo.id.0).collect::<Vec<_>>();
+                    let containing = model
+                        .iter()
+                        .filter(|(_, todo)| !todo.deleted && todo.value.contains(&value))
+                        .map(|(id, _)| *id)
+                        .collect::<Vec<_>>();
+                    prop_assert_eq!(ids, containing);
+
+                    let response =
+                        request(TestRequest::get().uri(&format!("/v2/todo/search?value={value}")))
+                            .send_request(&app)
+                            .await;
+                    prop_assert_eq!(response.status(), StatusCode::OK);
+                    let hits: Vec<SearchHit> = test::read_body_json(response).await;
+
+                    // Hits are a subset of the live todos, each found once.
+                    let mut found = BTreeSet::new();
+                    for hit in &hits {
+                        let id = hit.todo.id.0;
+                        prop_assert!(found.insert(id), "{id} found twice");
+                        let todo = live(&model, id);
+                        prop_assert!(todo.is_some(), "{id} is not a live todo");
+                        assert_matches(&hit.todo, &todo.unwrap())?;
+                    }
+                    // Todos containing the word itself are always found.
+                    for (id, todo) in &model {
+                        if !todo.deleted && todo.value.split(' ').any(|word| word == value) {
+                            prop_assert!(found.contains(id), "{id} `{}` not found", todo.value);
+                        }
+                    }
+                }
+            }
+
+            let response = request(TestRequest::get().uri("/todo"))
+                .send_request(&app)
+                .await;
+            let todos: Vec<Todo> = test::read_body_json(response).await;
+            let ids = todos.iter().map(|todo| todo.id.0).collect::<Vec<_>>();
+            let live_ids = model
+                .iter()
+                .filter(|(_, todo)| !todo.deleted)
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+            prop_assert_eq!(ids, live_ids);
+        }
+
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn api_behaves_like_model(calls in collection::vec(call(), 1..40)) {
+            actix_web::rt::System::new().block_on(check_calls(calls))?;
+        }
+    }
+
+    #[test]
+    fn records_without_revisions_are_rejected() {
+        let error = serde_json::from_value::<Snapshot>(json!({ "records": { "tenant": [[]] } }))
+            .err()
+            .expect("snapshot with an empty todo record must not load");
+        assert!(error.to_string().contains("todo record without revisions"));
+    }
+
+    #[test]
+    fn csv_header_lists_row_fields() {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer
+            .serialize(CsvTodo::from(
+                serde_json::from_value::<Todo>(
+                    json!({ "id": 1, "value": "buy milk", "checked": false }),
+                )
+                .unwrap(),
+            ))
+            .unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert!(csv.starts_with(CSV_HEADER));
+    }
+
+    #[test]
+    fn null_due_at_removes_deadline() {
+        let update =
+            |body: serde_json::Value| serde_json::from_value::<TodoUpdateRequest>(body).unwrap();
+        assert_eq!(update(json!({})).due_at, None);
+        assert_eq!(update(json!({ "due_at": null })).due_at, Some(None));
+        assert!(update(json!({ "due_at": "2024-01-01T12:00:00Z" }))
+            .due_at
+            .is_some_and(|due_at| due_at.is_some()));
+    }
+
+    #[test]
+    fn csv_tags_round_trip() {
+        let tags = ["a;b", "c\\", "", "d"].map(String::from).to_vec();
+        let row = CsvTodo::from(
+            serde_json::from_value::<Todo>(
+                json!({ "id": 1, "value": "buy milk", "checked": false, "tags": tags }),
+            )
+            .unwrap(),
+        );
+        assert_eq!(row.tags, "a\\;b;c\\\\;;d");
+        assert_eq!(Todo::from(row).tags, ["a;b", "c\\", "d"]);
+    }
+
+    #[test]
+    fn import_format_is_sniffed_from_body() {
+        assert_eq!(
+            TransferFormat::sniff(b"\n {\"id\": 1}"),
+            TransferFormat::Ndjson
+        );
+        assert_eq!(
+            TransferFormat::sniff(CSV_HEADER.as_bytes()),
+            TransferFormat::Csv
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Versions of the todo routes, served side by side under `/v1/todo` and `/v2/todo`.
+
+use std::time::SystemTime;
+
+use actix_web::{http::header::HttpDate, middleware::DefaultHeaders};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use utoipa::{
+    openapi::{
+        header::Header, path::PathItem, Deprecated, ObjectBuilder, OpenApi, RefOr, SchemaType,
+    },
+    Modify, OpenApi as _,
+};
+
+use crate::{ApiDoc, ApiDocV1};
+
+/// Version of the todo routes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ApiVersion {
+    /// First version, also served without a prefix for clients deployed before versioning.
+    V1,
+    /// Current version.
+    V2,
+}
+
+impl ApiVersion {
+    /// Every version, the current one first.
+    pub(super) const ALL: [Self; 2] = [Self::V2, Self::V1];
+
+    /// Name of the version, e.g. `v2`.
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Self::V1 => "v1",
+            Self::V2 => "v2",
+        }
+    }
+
+    /// Prefix of the todo routes of the version, e.g. `/v2`.
+    pub(super) fn prefix(self) -> &'static str {
+        match self {
+            Self::V1 => "/v1",
+            Self::V2 => "/v2",
+        }
+    }
+
+    /// `path` without the prefix of its version, e.g. `/todo/{id}` for `/v2/todo/{id}`.
+    pub(super) fn unversioned(path: &str) -> &str {
+        Self::ALL
+            .into_iter()
+            .find_map(|version| {
+                path.strip_prefix(version.prefix())
+                    .filter(|path| path.starts_with('/'))
+            })
+            .unwrap_or(path)
+    }
+
+    /// Path the OpenAPI document of the version is served at.
+    pub(super) fn document_path(self) -> &'static str {
+        match self {
+            Self::V1 => "/api-docs/v1/openapi.json",
+            Self::V2 => "/api-docs/v2/openapi.json",
+        }
+    }
+
+    /// OpenAPI document of the version.
+    pub(super) fn openapi(self) -> OpenApi {
+        let mut openapi = ApiDoc::openapi();
+        self.modify(&mut openapi);
+        openapi
+    }
+}
+
+/// Move the todo paths under the prefix of the version, marking the operations of older
+/// versions deprecated. Operations which changed since the first version are put back to how
+/// they were in its document.
+impl Modify for ApiVersion {
+    fn modify(&self, openapi: &mut OpenApi) {
+        if *self == Self::V1 {
+            openapi.paths.paths.extend(ApiDocV1::openapi().paths.paths);
+            let description = openapi.info.description.get_or_insert_with(String::new);
+            if !description.is_empty() {
+                description.push_str("\n\n");
+            }
+            description.push_str(
+                "The `/v1/todo` routes are also served without the `/v1` prefix, as `/todo`, for \
+                 clients deployed before versioning.",
+            );
+        }
+        let todo_paths = openapi
+            .paths
+            .paths
+            .keys()
+            .filter(|path| path.starts_with("/todo"))
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in todo_paths {
+            let mut path_item = openapi.paths.paths.remove(&path).unwrap();
+            if *self == Self::V1 {
+                deprecate(&mut path_item);
+            }
+            openapi
+                .paths
+                .paths
+                .insert(format!("{}{path}", self.prefix()), path_item);
+        }
+    }
+}
+
+/// Mark the operations of `path_item` deprecated and document the headers announcing it.
+fn deprecate(path_item: &mut PathItem) {
+    let string_header = |description: &str| {
+        let mut header = Header::new(ObjectBuilder::new().schema_type(SchemaType::String));
+        header.description = Some(String::from(description));
+        header
+    };
+    let deprecation = string_header("Time the version was deprecated at, e.g. `@1792368000`.");
+    let sunset = string_header("Time the version stops being served at as an HTTP date.");
+
+    for operation in path_item.operations.values_mut() {
+        operation.deprecated = Some(Deprecated::True);
+        // The rate limit rejects requests before they reach the routes of the version.
+        let responses = operation
+            .responses
+            .responses
+            .iter_mut()
+            .filter(|(status, _)| *status != "429")
+            .filter_map(|(_, response)| match response {
+                RefOr::T(response) => Some(response),
+                RefOr::Ref(_) => None,
+            });
+        for response in responses {
+            response
+                .headers
+                .insert(String::from("Deprecation"), deprecation.clone());
+            response
+                .headers
+                .insert(String::from("Sunset"), sunset.clone());
+        }
+    }
+}
+
+/// Deprecation of a version announced on its responses.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Deprecation {
+    /// Time the version was deprecated at.
+    pub(super) deprecated_at: DateTime<Utc>,
+    /// Time the version stops being served at.
+    pub(super) sunset_at: DateTime<Utc>,
+}
+
+impl Deprecation {
+    /// Deprecation of `v1` unless configured otherwise.
+    pub(super) const V1: Self = Self {
+        deprecated_at: midnight(2026, 10, 19),
+        sunset_at: midnight(2027, 4, 19),
+    };
+
+    /// Middleware adding the `Deprecation` and `Sunset` headers to the responses of the
+    /// version.
+    pub(super) fn headers(&self) -> DefaultHeaders {
+        DefaultHeaders::new()
+            .add((
+                "Deprecation",
+                format!("@{}", self.deprecated_at.timestamp()),
+            ))
+            .add((
+                "Sunset",
+                HttpDate::from(SystemTime::from(self.sunset_at)).to_string(),
+            ))
+    }
+}
+
+/// Midnight UTC at the start of `year`-`month`-`day`.
+const fn midnight(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    match NaiveDate::from_ymd_opt(year, month, day) {
+        Some(date) => date.and_time(NaiveTime::MIN).and_utc(),
+        None => panic!("invalid date"),
+    }
+}
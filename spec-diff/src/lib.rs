@@ -0,0 +1,861 @@
+//! Classify the changes between two OpenAPI 3.0 documents as breaking or non-breaking.
+//!
+//! A change is breaking when a client written against the old document can fail against a
+//! server implementing the new one. Schemas are compared in the direction data flows: a
+//! request schema may accept more than before, e.g. a new optional field or enum value,
+//! while a response schema may only promise more, e.g. a new field or a field becoming
+//! required. Narrowing a request schema or widening a response schema is breaking.
+//!
+//! ```
+//! use serde_json::json;
+//!
+//! let old = json!({"paths": {"/todo": {"get": {"responses": {"200": {}}}}}});
+//! let new = json!({"paths": {"/todo": {"get": {"responses": {"201": {}}}}}});
+//!
+//! let report = spec_diff::diff(&old, &new);
+//! assert!(report.is_breaking());
+//! assert_eq!(report.changes[0].location, "GET /todo response 200");
+//! ```
+
+use std::{collections::BTreeSet, sync::OnceLock};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Whether a change can break existing clients.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Clients of the old document can fail against the new one.
+    Breaking,
+    /// Clients of the old document keep working.
+    NonBreaking,
+}
+
+/// Single difference between the documents.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub severity: Severity,
+    /// Where the change is, e.g. `PUT /todo/{id} request body application/json $.value`.
+    pub location: String,
+    /// What changed.
+    pub message: String,
+}
+
+/// Every difference found between the documents, in document order.
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct Report {
+    /// Number of breaking changes.
+    pub breaking: usize,
+    /// Number of non-breaking changes.
+    pub non_breaking: usize,
+    pub changes: Vec<Change>,
+}
+
+impl Report {
+    /// Is any of the changes breaking.
+    pub fn is_breaking(&self) -> bool {
+        self.breaking > 0
+    }
+}
+
+/// Compare the `old` OpenAPI document with the `new` one.
+pub fn diff(old: &Value, new: &Value) -> Report {
+    let mut diff = Diff {
+        old,
+        new,
+        compared: BTreeSet::new(),
+        report: Report::default(),
+    };
+    diff.paths();
+    diff.report
+}
+
+/// Way data described by a schema flows.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Direction {
+    /// Sent by the client, the new schema may accept more.
+    Request,
+    /// Sent by the server, the new schema may promise more.
+    Response,
+}
+
+impl Direction {
+    /// Severity of a change accepting more values in this direction.
+    fn widened(self) -> Severity {
+        match self {
+            Self::Request => Severity::NonBreaking,
+            Self::Response => Severity::Breaking,
+        }
+    }
+
+    /// Severity of a change accepting fewer values in this direction.
+    fn narrowed(self) -> Severity {
+        match self {
+            Self::Request => Severity::Breaking,
+            Self::Response => Severity::NonBreaking,
+        }
+    }
+}
+
+struct Diff<'a> {
+    old: &'a Value,
+    new: &'a Value,
+    /// Pairs of referenced schemas already compared, which ends recursive schemas and reports
+    /// the changes of a shared schema only where it is used first.
+    compared: BTreeSet<(String, String, Direction)>,
+    report: Report,
+}
+
+impl<'a> Diff<'a> {
+    fn change(&mut self, severity: Severity, location: &str, message: impl Into<String>) {
+        match severity {
+            Severity::Breaking => self.report.breaking += 1,
+            Severity::NonBreaking => self.report.non_breaking += 1,
+        }
+        self.report.changes.push(Change {
+            severity,
+            location: String::from(location),
+            message: message.into(),
+        });
+    }
+
+    fn paths(&mut self) {
+        let (old, new) = (self.old, self.new);
+        let old_paths = object(old.get("paths"));
+        let new_paths = object(new.get("paths"));
+
+        for (path, old_item) in old_paths {
+            let new_item = new_paths.get(path);
+            for method in METHODS {
+                let Some(old_operation) = old_item.get(method) else {
+                    continue;
+                };
+                let location = format!("{} {path}", method.to_uppercase());
+                match new_item.and_then(|item| Some((item, item.get(method)?))) {
+                    Some(new) => self.operation(&location, (old_item, old_operation), new),
+                    None => self.change(Severity::Breaking, &location, "operation removed"),
+                }
+            }
+        }
+        for (path, new_item) in new_paths {
+            for method in METHODS {
+                let added = new_item.get(method).is_some()
+                    && old_paths
+                        .get(path)
+                        .and_then(|item| item.get(method))
+                        .is_none();
+                if added {
+                    let location = format!("{} {path}", method.to_uppercase());
+                    self.change(Severity::NonBreaking, &location, "operation added");
+                }
+            }
+        }
+    }
+
+    fn operation(&mut self, at: &str, old: (&'a Value, &'a Value), new: (&'a Value, &'a Value)) {
+        let (old_item, old_operation) = old;
+        let (new_item, new_operation) = new;
+
+        let old_id = old_operation.get("operationId");
+        let new_id = new_operation.get("operationId");
+        if old_id != new_id {
+            self.change(
+                Severity::Breaking,
+                at,
+                format!(
+                    "operationId changed from {} to {}",
+                    old_id.unwrap_or(&Value::Null),
+                    new_id.unwrap_or(&Value::Null)
+                ),
+            );
+        }
+
+        let deprecated =
+            |operation: &Value| operation.get("deprecated") == Some(&Value::Bool(true));
+        match (deprecated(old_operation), deprecated(new_operation)) {
+            (false, true) => self.change(Severity::NonBreaking, at, "operation deprecated"),
+            (true, false) => self.change(Severity::NonBreaking, at, "operation undeprecated"),
+            _ => {}
+        }
+
+        let old_secured = secured(self.old, old_operation);
+        let new_secured = secured(self.new, new_operation);
+        if old_secured != new_secured {
+            let (severity, message) = match new_secured {
+                true => (Severity::Breaking, "security requirement added"),
+                false => (Severity::NonBreaking, "security requirement removed"),
+            };
+            self.change(severity, at, message);
+        }
+
+        self.parameters(
+            at,
+            &parameters(self.old, old_item, old_operation),
+            &parameters(self.new, new_item, new_operation),
+        );
+        self.request_body(
+            at,
+            old_operation
+                .get("requestBody")
+                .map(|body| resolve(self.old, body)),
+            new_operation
+                .get("requestBody")
+                .map(|body| resolve(self.new, body)),
+        );
+        self.responses(
+            at,
+            object(old_operation.get("responses")),
+            object(new_operation.get("responses")),
+        );
+    }
+
+    fn parameters(&mut self, at: &str, old: &[&'a Value], new: &[&'a Value]) {
+        let key = |parameter: &Value| {
+            format!(
+                "{}.{}",
+                parameter["in"].as_str().unwrap_or_default(),
+                parameter["name"].as_str().unwrap_or_default()
+            )
+        };
+
+        for old_parameter in old {
+            let location = format!("{at} parameter {}", key(old_parameter));
+            let Some(new_parameter) = new.iter().find(|new| key(new) == key(old_parameter)) else {
+                self.change(Severity::Breaking, &location, "parameter removed");
+                continue;
+            };
+            match (required(old_parameter), required(new_parameter)) {
+                (false, true) => self.change(Severity::Breaking, &location, "parameter required"),
+                (true, false) => {
+                    self.change(Severity::NonBreaking, &location, "parameter optional")
+                }
+                _ => {}
+            }
+            if let (Some(old_schema), Some(new_schema)) =
+                (old_parameter.get("schema"), new_parameter.get("schema"))
+            {
+                let location = format!("{location} $");
+                self.schema(&location, Direction::Request, old_schema, new_schema);
+            }
+        }
+        for new_parameter in new {
+            if !old.iter().any(|old| key(old) == key(new_parameter)) {
+                let location = format!("{at} parameter {}", key(new_parameter));
+                match required(new_parameter) {
+                    true => self.change(Severity::Breaking, &location, "required parameter added"),
+                    false => {
+                        self.change(Severity::NonBreaking, &location, "optional parameter added")
+                    }
+                }
+            }
+        }
+    }
+
+    fn request_body(&mut self, at: &str, old: Option<&'a Value>, new: Option<&'a Value>) {
+        let location = format!("{at} request body");
+        match (old, new) {
+            (None, None) => {}
+            (None, Some(new)) => match required(new) {
+                true => self.change(Severity::Breaking, &location, "required body added"),
+                false => self.change(Severity::NonBreaking, &location, "optional body added"),
+            },
+            (Some(_), None) => self.change(Severity::Breaking, &location, "body removed"),
+            (Some(old), Some(new)) => {
+                match (required(old), required(new)) {
+                    (false, true) => self.change(Severity::Breaking, &location, "body required"),
+                    (true, false) => self.change(Severity::NonBreaking, &location, "body optional"),
+                    _ => {}
+                }
+                self.content(&location, Direction::Request, old, new);
+            }
+        }
+    }
+
+    fn responses(&mut self, at: &str, old: &'a Map<String, Value>, new: &'a Map<String, Value>) {
+        for (status, old_response) in old {
+            let location = format!("{at} response {status}");
+            let Some(new_response) = new.get(status) else {
+                self.change(Severity::Breaking, &location, "response removed");
+                continue;
+            };
+            let old_response = resolve(self.old, old_response);
+            let new_response = resolve(self.new, new_response);
+
+            let old_headers = object(old_response.get("headers"));
+            let new_headers = object(new_response.get("headers"));
+            for (name, old_header) in old_headers {
+                let location = format!("{location} header {name}");
+                match new_headers.get(name) {
+                    Some(new_header) => {
+                        let old_header = resolve(self.old, old_header);
+                        let new_header = resolve(self.new, new_header);
+                        if let (Some(old_schema), Some(new_schema)) =
+                            (old_header.get("schema"), new_header.get("schema"))
+                        {
+                            let location = format!("{location} $");
+                            self.schema(&location, Direction::Response, old_schema, new_schema);
+                        }
+                    }
+                    None => self.change(Severity::Breaking, &location, "header removed"),
+                }
+            }
+            for name in new_headers.keys() {
+                if !old_headers.contains_key(name) {
+                    let location = format!("{location} header {name}");
+                    self.change(Severity::NonBreaking, &location, "header added");
+                }
+            }
+
+            self.content(&location, Direction::Response, old_response, new_response);
+        }
+        for status in new.keys() {
+            if !old.contains_key(status) {
+                let location = format!("{at} response {status}");
+                self.change(Severity::NonBreaking, &location, "response added");
+            }
+        }
+    }
+
+    /// Compare the media types of a request body or response.
+    fn content(&mut self, at: &str, direction: Direction, old: &'a Value, new: &'a Value) {
+        let old_content = object(old.get("content"));
+        let new_content = object(new.get("content"));
+
+        for (media_type, old_media) in old_content {
+            let location = format!("{at} {media_type}");
+            match new_content.get(media_type) {
+                Some(new_media) => {
+                    if let (Some(old_schema), Some(new_schema)) =
+                        (old_media.get("schema"), new_media.get("schema"))
+                    {
+                        let location = format!("{location} $");
+                        self.schema(&location, direction, old_schema, new_schema);
+                    }
+                }
+                None => self.change(Severity::Breaking, &location, "media type removed"),
+            }
+        }
+        for media_type in new_content.keys() {
+            if !old_content.contains_key(media_type) {
+                let location = format!("{at} {media_type}");
+                self.change(Severity::NonBreaking, &location, "media type added");
+            }
+        }
+    }
+
+    fn schema(&mut self, at: &str, direction: Direction, old: &'a Value, new: &'a Value) {
+        if let (Some(old_ref), Some(new_ref)) = (reference(old), reference(new)) {
+            let pair = (String::from(old_ref), String::from(new_ref), direction);
+            if !self.compared.insert(pair) {
+                return;
+            }
+        }
+        let old = resolve(self.old, old);
+        let new = resolve(self.new, new);
+
+        let old_type = old.get("type").and_then(Value::as_str);
+        let new_type = new.get("type").and_then(Value::as_str);
+        if old_type != new_type {
+            let severity = match (old_type, new_type) {
+                (Some("integer"), Some("number")) | (Some(_), None) => direction.widened(),
+                (Some("number"), Some("integer")) | (None, Some(_)) => direction.narrowed(),
+                _ => Severity::Breaking,
+            };
+            let message = format!(
+                "type changed from {} to {}",
+                old_type.unwrap_or("any"),
+                new_type.unwrap_or("any")
+            );
+            self.change(severity, at, message);
+        }
+
+        let old_format = old.get("format").and_then(Value::as_str);
+        let new_format = new.get("format").and_then(Value::as_str);
+        if old_format != new_format {
+            let severity = match (old_format, new_format) {
+                (Some("int32"), Some("int64")) | (Some(_), None) => direction.widened(),
+                (Some("int64"), Some("int32")) | (None, Some(_)) => direction.narrowed(),
+                _ => Severity::Breaking,
+            };
+            let message = format!(
+                "format changed from {} to {}",
+                old_format.unwrap_or("none"),
+                new_format.unwrap_or("none")
+            );
+            self.change(severity, at, message);
+        }
+
+        match (nullable(old), nullable(new)) {
+            (false, true) => self.change(direction.widened(), at, "became nullable"),
+            (true, false) => self.change(direction.narrowed(), at, "no longer nullable"),
+            _ => {}
+        }
+
+        self.enumeration(at, direction, old.get("enum"), new.get("enum"));
+        self.bounds(at, direction, old, new);
+        self.properties(at, direction, old, new);
+
+        if let (Some(old_items), Some(new_items)) = (old.get("items"), new.get("items")) {
+            self.schema(&format!("{at}[]"), direction, old_items, new_items);
+        }
+        for composition in ["allOf", "oneOf", "anyOf"] {
+            self.composition(
+                at,
+                direction,
+                composition,
+                old.get(composition),
+                new.get(composition),
+            );
+        }
+    }
+
+    fn enumeration(
+        &mut self,
+        at: &str,
+        direction: Direction,
+        old: Option<&Value>,
+        new: Option<&Value>,
+    ) {
+        match (old.and_then(Value::as_array), new.and_then(Value::as_array)) {
+            (Some(_), None) => self.change(direction.widened(), at, "enum removed"),
+            (None, Some(_)) => self.change(direction.narrowed(), at, "enum added"),
+            (Some(old), Some(new)) => {
+                for value in old.iter().filter(|value| !new.contains(value)) {
+                    self.change(
+                        direction.narrowed(),
+                        at,
+                        format!("enum value {value} removed"),
+                    );
+                }
+                for value in new.iter().filter(|value| !old.contains(value)) {
+                    self.change(direction.widened(), at, format!("enum value {value} added"));
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    /// Compare the numeric, length and size limits of the schemas.
+    fn bounds(&mut self, at: &str, direction: Direction, old: &Value, new: &Value) {
+        for (keyword, lower) in [
+            ("minimum", true),
+            ("maximum", false),
+            ("minLength", true),
+            ("maxLength", false),
+            ("minItems", true),
+            ("maxItems", false),
+        ] {
+            let old_bound = old.get(keyword).and_then(Value::as_f64);
+            let new_bound = new.get(keyword).and_then(Value::as_f64);
+            let narrowed = match (old_bound, new_bound) {
+                (None, None) => continue,
+                (Some(old_bound), Some(new_bound)) if old_bound == new_bound => continue,
+                (Some(_), None) => false,
+                (None, Some(_)) => true,
+                (Some(old_bound), Some(new_bound)) => (new_bound > old_bound) == lower,
+            };
+            let (severity, verb) = match narrowed {
+                true => (direction.narrowed(), "narrowed"),
+                false => (direction.widened(), "widened"),
+            };
+            let message = format!(
+                "{keyword} {verb} from {} to {}",
+                old.get(keyword).unwrap_or(&Value::Null),
+                new.get(keyword).unwrap_or(&Value::Null)
+            );
+            self.change(severity, at, message);
+        }
+    }
+
+    fn properties(&mut self, at: &str, direction: Direction, old: &'a Value, new: &'a Value) {
+        let old_properties = object(old.get("properties"));
+        let new_properties = object(new.get("properties"));
+        let old_required = required_properties(old);
+        let new_required = required_properties(new);
+        // Servers ignore read only properties sent by clients.
+        let ignored = |schema: &Value| {
+            direction == Direction::Request
+                && resolve(self.new, schema).get("readOnly") == Some(&Value::Bool(true))
+        };
+
+        for (name, old_property) in old_properties {
+            let location = format!("{at}.{name}");
+            let was_required = old_required.contains(name.as_str());
+            let Some(new_property) = new_properties.get(name) else {
+                let severity = match direction {
+                    Direction::Request => Severity::Breaking,
+                    Direction::Response if was_required => Severity::Breaking,
+                    Direction::Response => Severity::NonBreaking,
+                };
+                self.change(severity, &location, "property removed");
+                continue;
+            };
+            if !ignored(new_property) {
+                match (was_required, new_required.contains(name.as_str())) {
+                    (false, true) => {
+                        self.change(direction.narrowed(), &location, "property required")
+                    }
+                    (true, false) => {
+                        self.change(direction.widened(), &location, "property optional")
+                    }
+                    _ => {}
+                }
+            }
+            self.schema(&location, direction, old_property, new_property);
+        }
+        for (name, new_property) in new_properties {
+            if old_properties.contains_key(name) {
+                continue;
+            }
+            let location = format!("{at}.{name}");
+            let severity = match new_required.contains(name.as_str()) && !ignored(new_property) {
+                true => direction.narrowed(),
+                false => Severity::NonBreaking,
+            };
+            self.change(severity, &location, "property added");
+        }
+    }
+
+    /// Compare the schemas of an `allOf`, `oneOf` or `anyOf`, matching them by their key.
+    fn composition(
+        &mut self,
+        at: &str,
+        direction: Direction,
+        keyword: &str,
+        old: Option<&'a Value>,
+        new: Option<&'a Value>,
+    ) {
+        let old_schemas = old.and_then(Value::as_array).map(Vec::as_slice);
+        let new_schemas = new.and_then(Value::as_array).map(Vec::as_slice);
+        let (old_schemas, new_schemas) = (
+            old_schemas.unwrap_or_default(),
+            new_schemas.unwrap_or_default(),
+        );
+        // Every schema of an `allOf` has to match, so more of them accept fewer values, while
+        // more schemas of a `oneOf` or `anyOf` accept more values.
+        let (added, removed) = match keyword {
+            "allOf" => (direction.narrowed(), direction.widened()),
+            _ => (direction.widened(), direction.narrowed()),
+        };
+        let old_keys = keys(self.old, old_schemas);
+        let new_keys = keys(self.new, new_schemas);
+
+        for (old_key, old_schema) in old_keys.iter().zip(old_schemas) {
+            let location = format!("{at} {keyword}[{old_key}]");
+            match new_keys.iter().position(|new_key| new_key == old_key) {
+                Some(index) => self.schema(&location, direction, old_schema, &new_schemas[index]),
+                None => self.change(removed, &location, "schema removed"),
+            }
+        }
+        for new_key in new_keys.iter().filter(|key| !old_keys.contains(key)) {
+            let location = format!("{at} {keyword}[{new_key}]");
+            self.change(added, &location, "schema added");
+        }
+    }
+}
+
+/// Properties of `value`, or none when it is no object.
+fn object(value: Option<&Value>) -> &Map<String, Value> {
+    static EMPTY: OnceLock<Map<String, Value>> = OnceLock::new();
+    value
+        .and_then(Value::as_object)
+        .unwrap_or_else(|| EMPTY.get_or_init(Map::new))
+}
+
+fn reference(value: &Value) -> Option<&str> {
+    value.get("$ref").and_then(Value::as_str)
+}
+
+/// Value referred to by `value` within `document`, or `value` itself when it is no reference.
+///
+/// Unresolvable references resolve to `value`, which has no properties to compare.
+fn resolve<'a>(document: &'a Value, value: &'a Value) -> &'a Value {
+    reference(value)
+        .and_then(|reference| reference.strip_prefix('#'))
+        .and_then(|pointer| document.pointer(pointer))
+        .unwrap_or(value)
+}
+
+fn required(value: &Value) -> bool {
+    value.get("required") == Some(&Value::Bool(true))
+}
+
+fn nullable(schema: &Value) -> bool {
+    schema.get("nullable") == Some(&Value::Bool(true))
+}
+
+fn required_properties(schema: &Value) -> BTreeSet<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
+/// Does `operation` of `document` require any credentials.
+fn secured(document: &Value, operation: &Value) -> bool {
+    operation
+        .get("security")
+        .or_else(|| document.get("security"))
+        .and_then(Value::as_array)
+        .is_some_and(|requirements| {
+            !requirements.is_empty()
+                && !requirements
+                    .iter()
+                    .any(|requirement| object(Some(requirement)).is_empty())
+        })
+}
+
+/// Parameters of `operation` including the ones shared by its path `item`.
+fn parameters<'a>(document: &'a Value, item: &'a Value, operation: &'a Value) -> Vec<&'a Value> {
+    let mut parameters: Vec<&Value> = Vec::new();
+    for parameter in [item, operation]
+        .into_iter()
+        .filter_map(|value| value.get("parameters").and_then(Value::as_array))
+        .flatten()
+    {
+        let parameter = resolve(document, parameter);
+        // Operation parameters override path parameters of the same name and location.
+        parameters.retain(|existing| {
+            existing.get("in") != parameter.get("in")
+                || existing.get("name") != parameter.get("name")
+        });
+        parameters.push(parameter);
+    }
+    parameters
+}
+
+/// Keys matching the schemas of a composition across the documents: the schema referred to,
+/// the title, the required properties, or else the position.
+fn keys(document: &Value, schemas: &[Value]) -> Vec<String> {
+    schemas
+        .iter()
+        .enumerate()
+        .map(|(index, schema)| {
+            if let Some(reference) = reference(schema) {
+                return String::from(reference.rsplit('/').next().unwrap_or(reference));
+            }
+            let schema = resolve(document, schema);
+            if let Some(title) = schema.get("title").and_then(Value::as_str) {
+                return String::from(title);
+            }
+            let required = required_properties(schema);
+            match required.is_empty() {
+                true => index.to_string(),
+                false => required.into_iter().collect::<Vec<_>>().join(","),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// Document with a single `POST /todo` of `Todo` and response `status`.
+    fn document(todo: Value, status: &str) -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/todo": {
+                    "post": {
+                        "operationId": "create_todo",
+                        "requestBody": {
+                            "content": {
+                                "application/json": {"schema": {"$ref": "#/components/schemas/Todo"}}
+                            },
+                            "required": true
+                        },
+                        "responses": {
+                            status: {
+                                "description": "Todo created",
+                                "content": {
+                                    "application/json": {"schema": {"$ref": "#/components/schemas/Todo"}}
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {"schemas": {"Todo": todo}}
+        })
+    }
+
+    fn todo(required: &[&str], priorities: &[&str]) -> Value {
+        json!({
+            "type": "object",
+            "required": required,
+            "properties": {
+                "id": {"type": "integer", "format": "int32"},
+                "value": {"type": "string"},
+                "priority": {"type": "string", "enum": priorities}
+            }
+        })
+    }
+
+    fn changes(report: &Report) -> Vec<(Severity, &str, &str)> {
+        report
+            .changes
+            .iter()
+            .map(|change| {
+                (
+                    change.severity,
+                    change.location.as_str(),
+                    change.message.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_document_has_no_changes() {
+        let document: Value =
+            serde_json::from_str(include_str!("../../docs/v2/openapi.json")).unwrap();
+
+        let report = diff(&document, &document);
+
+        assert!(report.changes.is_empty(), "{:?}", report.changes);
+    }
+
+    #[test]
+    fn removed_operation_is_breaking() {
+        let old = document(todo(&["id"], &["low"]), "201");
+        let mut new = old.clone();
+        new["paths"]["/todo/{id}"] = new["paths"]["/todo"].take();
+
+        let report = diff(&old, &new);
+
+        assert_eq!(
+            changes(&report),
+            [
+                (Severity::Breaking, "POST /todo", "operation removed"),
+                (Severity::NonBreaking, "POST /todo/{id}", "operation added"),
+            ]
+        );
+        assert!(report.is_breaking());
+    }
+
+    #[test]
+    fn required_field_breaks_requests_only() {
+        let old = document(todo(&["id"], &["low"]), "201");
+        let new = document(todo(&["id", "value"], &["low"]), "201");
+
+        let report = diff(&old, &new);
+
+        assert_eq!(
+            changes(&report),
+            [
+                (
+                    Severity::Breaking,
+                    "POST /todo request body application/json $.value",
+                    "property required"
+                ),
+                (
+                    Severity::NonBreaking,
+                    "POST /todo response 201 application/json $.value",
+                    "property required"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn enum_changes_break_the_direction_they_narrow() {
+        let old = document(todo(&["id"], &["low", "high"]), "201");
+        let narrowed = document(todo(&["id"], &["low"]), "201");
+
+        assert_eq!(
+            changes(&diff(&old, &narrowed)),
+            [
+                (
+                    Severity::Breaking,
+                    "POST /todo request body application/json $.priority",
+                    "enum value \"high\" removed"
+                ),
+                (
+                    Severity::NonBreaking,
+                    "POST /todo response 201 application/json $.priority",
+                    "enum value \"high\" removed"
+                ),
+            ]
+        );
+        assert_eq!(
+            changes(&diff(&narrowed, &old)),
+            [
+                (
+                    Severity::NonBreaking,
+                    "POST /todo request body application/json $.priority",
+                    "enum value \"high\" added"
+                ),
+                (
+                    Severity::Breaking,
+                    "POST /todo response 201 application/json $.priority",
+                    "enum value \"high\" added"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_response_status_is_breaking() {
+        let old = document(todo(&["id"], &["low"]), "201");
+        let new = document(todo(&["id"], &["low"]), "200");
+
+        let report = diff(&old, &new);
+
+        assert_eq!(
+            changes(&report),
+            [
+                (
+                    Severity::Breaking,
+                    "POST /todo response 201",
+                    "response removed"
+                ),
+                (
+                    Severity::NonBreaking,
+                    "POST /todo response 200",
+                    "response added"
+                ),
+            ]
+        );
+        assert_eq!((report.breaking, report.non_breaking), (1, 1));
+    }
+
+    #[test]
+    fn recursive_schemas_are_compared_once() {
+        let tree = json!({
+            "type": "object",
+            "properties": {"children": {"type": "array", "items": {"$ref": "#/components/schemas/Todo"}}}
+        });
+        let old = document(tree.clone(), "201");
+        let mut new = old.clone();
+        new["components"]["schemas"]["Todo"]["properties"]["value"] = json!({"type": "string"});
+
+        let report = diff(&old, &new);
+
+        assert_eq!(
+            changes(&report),
+            [
+                (
+                    Severity::NonBreaking,
+                    "POST /todo request body application/json $.value",
+                    "property added"
+                ),
+                (
+                    Severity::NonBreaking,
+                    "POST /todo response 201 application/json $.value",
+                    "property added"
+                ),
+            ]
+        );
+    }
+}
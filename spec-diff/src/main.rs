@@ -0,0 +1,51 @@
+//! Compare two OpenAPI documents and report the changes between them as JSON.
+//!
+//! ```sh
+//! git show HEAD:docs/v2/openapi.json > /tmp/openapi.json
+//! cargo run -p spec-diff -- /tmp/openapi.json docs/v2/openapi.json
+//! ```
+//!
+//! Exits with 0 when no change is breaking, 1 when any is and 2 when the documents cannot be
+//! compared.
+
+use std::{env, fs, process::ExitCode};
+
+use serde_json::Value;
+
+fn read(path: &str) -> Result<Value, String> {
+    let document = fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+    serde_json::from_str(&document).map_err(|error| format!("{path}: {error}"))
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let [old, new] = args.as_slice() else {
+        eprintln!("usage: spec-diff <old openapi.json> <new openapi.json>");
+        return ExitCode::from(2);
+    };
+    let (old, new) = match (read(old), read(new)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(error), _) | (_, Err(error)) => {
+            eprintln!("spec-diff: {error}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let report = spec_diff::diff(&old, &new);
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).expect("report serializes")
+    );
+    for change in report
+        .changes
+        .iter()
+        .filter(|change| change.severity == spec_diff::Severity::Breaking)
+    {
+        eprintln!("breaking: {}: {}", change.location, change.message);
+    }
+
+    match report.is_breaking() {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
@@ -1,4 +1,8 @@
-use actix_todo::{types, ClientTodoExt, Client};
+use std::{env, fs};
+
+use actix_todo::{
+    types, Bulk, Certificate, Client, ClientOptions, ClientTodoExt, Identity, TodoId,
+};
 
 const API_KEY: &str = "utoipa-rocks";
 
@@ -6,17 +10,30 @@ const API_KEY: &str = "utoipa-rocks";
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut headers = reqwest::header::HeaderMap::new();
     headers.insert("todo_apikey", API_KEY.parse().unwrap());
-    let default_client = reqwest::ClientBuilder::new()
-        .default_headers(headers)
+    // Talk to a server with a private CA or requiring a client certificate over https with
+    // e.g. `TODO_BASE_URL=https://localhost:8080 TODO_CA_CERT=ca.pem`.
+    let mut options = ClientOptions::new();
+    if let Ok(path) = env::var("TODO_CA_CERT") {
+        options = options.root_certificate(Certificate::from_pem(&fs::read(path)?)?);
+    }
+    if let Ok(path) = env::var("TODO_CLIENT_IDENTITY") {
+        options = options.identity(Identity::from_pem(&fs::read(path)?)?);
+    }
+    let default_client = options
+        .apply(reqwest::ClientBuilder::new().default_headers(headers))
         .build()?;
-    let client = Client::new_with_client("http://localhost:8080", default_client);
+    let baseurl =
+        env::var("TODO_BASE_URL").unwrap_or_else(|_| String::from("http://localhost:8080"));
+    let client = Client::new_with_client(&baseurl, default_client);
 
     let todo1 = client
         .create_todo()
-        .body(types::Todo {
-            id: 10,
-            value: "Write a blog post".to_string(),
-            checked: false,
+        .body_map(|todo| {
+            todo.id(TodoId::new(10))
+                .value("Write a blog post")
+                .checked(false)
+                .priority(Some(types::Priority::High))
+                .tags(vec!["writing".to_string()])
         })
         .send()
         .await?;
@@ -24,10 +41,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let todo2 = client
         .create_todo()
-        .body(&types::Todo {
-            id: 20,
-            value: "Attend a daily standup".to_string(),
-            checked: false,
+        .body_map(|todo| {
+            todo.id(TodoId::new(20))
+                .value("Attend a daily standup")
+                .checked(false)
         })
         .send()
         .await?;
@@ -42,8 +59,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let todo_list = client.get_todos().send().await?;
     println!("todo list: {todo_list:?}");
-    for todo in todo_list.into_inner() {
-        client.delete_todo().id(todo.id).send().await?;
+    let deleted = Bulk::new()
+        .concurrency(4)
+        .on_progress(|progress| println!("deleted {}/{}", progress.completed, progress.total))
+        .run(todo_list.into_inner(), |todo| {
+            client.delete_todo().id(todo.id).send()
+        })
+        .await;
+    if let Some((_, error)) = deleted.errors().next() {
+        return Err(error.to_string().into());
     }
 
     let todo_list = client.get_todos().send().await?;